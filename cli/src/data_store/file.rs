@@ -0,0 +1,430 @@
+use super::data_dir;
+use async_trait::async_trait;
+use initiative_core::{DataStore, MemoryDataStore, Thing, ThingQuery, Uuid};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A data store that keeps the journal in a JSON file, by default
+/// `$XDG_DATA_HOME/initiative/journal.json`.
+///
+/// Reads are served from memory, and writes are collected in memory until the app flushes them
+/// at the end of each command (or the data store is dropped). The whole file is then rewritten by
+/// way of a temporary file that is renamed into place, so the file on disk is always either the
+/// old version or the new one, never something in between.
+///
+/// While it's open, the journal is locked against other instances of initiative.sh, which would
+/// otherwise overwrite each other's changes.
+pub struct FileDataStore {
+    path: PathBuf,
+    memory: MemoryDataStore,
+
+    /// Whether there are changes in memory that haven't been written to the file yet.
+    dirty: bool,
+
+    /// Held for as long as the journal is open. The lock is released when the file is closed.
+    _lock: File,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileData {
+    things: Vec<Thing>,
+    key_values: BTreeMap<String, String>,
+}
+
+impl FileDataStore {
+    /// Open the journal at `path`, creating it on the first write if it doesn't exist yet. A file
+    /// that exists but can't be read is an error rather than being silently replaced, as is a
+    /// journal that another instance already has open.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let lock = lock(&path)?;
+        let memory = MemoryDataStore::default();
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let data: FileData = serde_json::from_str(&contents)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                memory.things.borrow_mut().extend(
+                    data.things
+                        .into_iter()
+                        .filter_map(|thing| thing.uuid().cloned().map(|uuid| (uuid, thing))),
+                );
+                memory.key_values.borrow_mut().extend(data.key_values);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        Ok(Self {
+            path,
+            memory,
+            dirty: false,
+            _lock: lock,
+        })
+    }
+
+    /// The default location of the journal, following the XDG base directory specification.
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("journal.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Note whether a change to the in-memory copy succeeded, in which case the file needs to be
+    /// written on the next flush.
+    fn track(&mut self, result: Result<(), ()>) -> Result<(), ()> {
+        self.dirty |= result.is_ok();
+        result
+    }
+
+    fn write(&mut self) -> io::Result<()> {
+        if self.dirty {
+            write_file(
+                &self.path,
+                &self.memory.things.borrow(),
+                &self.memory.key_values.borrow(),
+            )?;
+            self.dirty = false;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for FileDataStore {
+    fn drop(&mut self) {
+        self.write().ok();
+    }
+}
+
+#[async_trait(?Send)]
+impl DataStore for FileDataStore {
+    async fn health_check(&self) -> Result<(), ()> {
+        match self.path.parent() {
+            Some(parent) => fs::create_dir_all(parent).map_err(|_| ()),
+            None => Err(()),
+        }
+    }
+
+    async fn delete_thing_by_uuid(&mut self, uuid: &Uuid) -> Result<(), ()> {
+        let result = self.memory.delete_thing_by_uuid(uuid).await;
+        self.track(result)
+    }
+
+    async fn edit_thing(&mut self, thing: &Thing) -> Result<(), ()> {
+        let result = self.memory.edit_thing(thing).await;
+        self.track(result)
+    }
+
+    async fn get_all_the_things(&self) -> Result<Vec<Thing>, ()> {
+        self.memory.get_all_the_things().await
+    }
+
+    async fn get_thing_by_uuid(&self, uuid: &Uuid) -> Result<Option<Thing>, ()> {
+        self.memory.get_thing_by_uuid(uuid).await
+    }
+
+    async fn get_thing_by_name(&self, name: &str) -> Result<Option<Thing>, ()> {
+        self.memory.get_thing_by_name(name).await
+    }
+
+    async fn get_things_by_name_start(
+        &self,
+        name: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Thing>, ()> {
+        self.memory.get_things_by_name_start(name, limit).await
+    }
+
+    async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+        self.memory.get_things_by_query(query).await
+    }
+
+    async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()> {
+        let result = self.memory.save_thing(thing).await;
+        self.track(result)
+    }
+
+    async fn set_value(&mut self, key: &str, value: &str) -> Result<(), ()> {
+        let result = self.memory.set_value(key, value).await;
+        self.track(result)
+    }
+
+    async fn get_value(&self, key: &str) -> Result<Option<String>, ()> {
+        self.memory.get_value(key).await
+    }
+
+    async fn delete_value(&mut self, key: &str) -> Result<(), ()> {
+        let result = self.memory.delete_value(key).await;
+        self.track(result)
+    }
+
+    async fn flush(&mut self) -> Result<(), ()> {
+        self.write().map_err(|_| ())
+    }
+}
+
+/// Take an advisory lock on a file alongside the journal, so that two instances of initiative.sh
+/// can't have it open at once. The journal itself isn't locked because each write replaces it
+/// with a new file.
+fn lock(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut lock_name = path.file_name().unwrap_or_default().to_os_string();
+    lock_name.push(".lock");
+    let file = File::create(path.with_file_name(lock_name))?;
+
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "the journal is already open in another instance of initiative.sh",
+        )),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// Write the journal to a temporary file alongside the real one, flush it to disk, then rename it
+/// over the original. Renaming within a directory is atomic, so a crash or full disk at any point
+/// leaves the previous version intact.
+fn write_file(
+    path: &Path,
+    things: &HashMap<Uuid, Thing>,
+    key_values: &HashMap<String, String>,
+) -> io::Result<()> {
+    let mut things: Vec<Thing> = things.values().cloned().collect();
+    things.sort_by_key(|thing| thing.uuid().cloned());
+
+    let data = FileData {
+        things,
+        key_values: key_values
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    };
+    let contents = serde_json::to_vec_pretty(&data)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no parent directory"))?;
+    fs::create_dir_all(dir)?;
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&contents)?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        fs::remove_file(&temp_path).ok();
+    })?;
+
+    // Make sure the rename itself survives a crash. Not every platform allows opening a
+    // directory, so this is best-effort.
+    if let Ok(dir) = File::open(dir) {
+        dir.sync_all().ok();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn persist_test() {
+        let path = temp_path();
+
+        {
+            let mut data_store = FileDataStore::open(&path).unwrap();
+            assert_eq!(Ok(()), data_store.health_check().await);
+            assert!(!path.exists());
+
+            data_store.save_thing(&thing("Odysseus")).await.unwrap();
+            data_store.set_value("time", "1:08:00:00").await.unwrap();
+        }
+
+        {
+            let mut data_store = FileDataStore::open(&path).unwrap();
+            assert_eq!(
+                Some("Odysseus".to_string()),
+                data_store
+                    .get_thing_by_name("odysseus")
+                    .await
+                    .unwrap()
+                    .map(|thing| thing.name().to_string()),
+            );
+            assert_eq!(
+                Ok(Some("1:08:00:00".to_string())),
+                data_store.get_value("time").await,
+            );
+
+            let uuid = *data_store.get_all_the_things().await.unwrap()[0]
+                .uuid()
+                .unwrap();
+            data_store.delete_thing_by_uuid(&uuid).await.unwrap();
+            data_store.delete_value("time").await.unwrap();
+        }
+
+        {
+            let data_store = FileDataStore::open(&path).unwrap();
+            assert_eq!(Ok(Vec::new()), data_store.get_all_the_things().await);
+            assert_eq!(Ok(None), data_store.get_value("time").await);
+        }
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn flush_test() {
+        let path = temp_path();
+        let mut data_store = FileDataStore::open(&path).unwrap();
+
+        data_store.save_thing(&thing("Odysseus")).await.unwrap();
+        data_store.save_thing(&thing("Penelope")).await.unwrap();
+        assert!(!path.exists());
+
+        data_store.flush().await.unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("Odysseus"), "{}", contents);
+        assert!(contents.contains("Penelope"), "{}", contents);
+
+        // Nothing has changed, so there's nothing to write.
+        fs::remove_file(&path).unwrap();
+        data_store.flush().await.unwrap();
+        assert!(!path.exists());
+
+        drop(data_store);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_failure_test() {
+        let path = temp_path();
+        let mut data_store = FileDataStore::open(&path).unwrap();
+        data_store.save_thing(&thing("Odysseus")).await.unwrap();
+        data_store.flush().await.unwrap();
+
+        // Replace the journal with a directory so that the rename can't succeed.
+        fs::remove_file(&path).unwrap();
+        fs::create_dir_all(&path).unwrap();
+
+        data_store.save_thing(&thing("Penelope")).await.unwrap();
+        assert_eq!(Err(()), data_store.flush().await);
+
+        // The change is kept in memory, and written on the next flush that succeeds.
+        assert!(data_store
+            .get_thing_by_name("Penelope")
+            .await
+            .unwrap()
+            .is_some());
+        fs::remove_dir(&path).unwrap();
+        assert_eq!(Ok(()), data_store.flush().await);
+        drop(data_store);
+
+        assert_eq!(
+            2,
+            FileDataStore::open(&path)
+                .unwrap()
+                .get_all_the_things()
+                .await
+                .unwrap()
+                .len(),
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn app_flush_failure_test() {
+        let path = temp_path();
+        let mut app = initiative_core::app(FileDataStore::open(&path).unwrap(), &|_| {});
+        app.init().await;
+
+        fs::create_dir_all(&path).unwrap();
+        let output = app.command("npc named Odysseus").await.unwrap();
+        assert!(
+            output.ends_with("\n\n! Your journal couldn't be saved. initiative.sh will try again after your next command."),
+            "{}",
+            output,
+        );
+
+        fs::remove_dir(&path).unwrap();
+        let output = app.command("Odysseus").await.unwrap();
+        assert!(!output.contains("couldn't be saved"), "{}", output);
+        assert!(path.is_file());
+
+        drop(app);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn lock_test() {
+        let path = temp_path();
+        let data_store = FileDataStore::open(&path).unwrap();
+
+        assert_eq!(
+            io::ErrorKind::WouldBlock,
+            FileDataStore::open(&path).err().unwrap().kind(),
+        );
+
+        drop(data_store);
+        assert!(FileDataStore::open(&path).is_ok());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn conformance_test() {
+        let path = temp_path();
+        initiative_core::test_data_store(FileDataStore::open(&path).unwrap()).await;
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_test() {
+        let path = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{\"things\": [").unwrap();
+
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            FileDataStore::open(&path).err().unwrap().kind(),
+        );
+
+        // The original file is left alone.
+        assert_eq!("{\"things\": [", fs::read_to_string(&path).unwrap());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("initiative-test-{}", Uuid::new_v4()))
+            .join("journal.json")
+    }
+
+    fn thing(name: &str) -> Thing {
+        serde_json::from_str(&format!(
+            r#"{{"type":"Npc","uuid":"{}","name":"{}"}}"#,
+            Uuid::new_v4(),
+            name,
+        ))
+        .unwrap()
+    }
+}
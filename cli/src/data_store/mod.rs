@@ -0,0 +1,20 @@
+pub use file::FileDataStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteDataStore;
+
+mod file;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// The directory in which the journal is kept, following the XDG base directory specification:
+/// `$XDG_DATA_HOME/initiative`, or `~/.local/share/initiative` if that isn't set.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .map(|data_home| data_home.join("initiative"))
+}
@@ -0,0 +1,284 @@
+use super::data_dir;
+use async_trait::async_trait;
+use initiative_core::{DataStore, Thing, ThingQuery, Uuid};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+/// A data store backed by a SQLite database, for journals that have outgrown `FileDataStore`.
+///
+/// Things are stored as JSON alongside a handful of indexed columns used to narrow down lookups:
+/// the lowercased name, the location, and the type. Names are compared by their lowercase form,
+/// so "Gandalf" and "gANDALF" are the same name here just as they are in `MemoryDataStore`.
+pub struct SqliteDataStore {
+    connection: Connection,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS things (
+        uuid TEXT PRIMARY KEY NOT NULL,
+        type TEXT NOT NULL,
+        name_lower TEXT,
+        location_uuid TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS things_name_lower ON things (name_lower);
+    CREATE INDEX IF NOT EXISTS things_location_uuid ON things (location_uuid);
+
+    CREATE TABLE IF NOT EXISTS key_values (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );
+";
+
+impl SqliteDataStore {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// The default location of the database, alongside where `FileDataStore` would keep its
+    /// journal.
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("journal.sqlite"))
+    }
+
+    fn init(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    fn query_things(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Thing>, ()> {
+        let mut statement = self.connection.prepare_cached(sql).map_err(|_| ())?;
+
+        let rows = statement
+            .query_map(params_from_iter(params), |row| row.get::<_, String>(0))
+            .map_err(|_| ())?;
+
+        rows.map(|row| {
+            row.map_err(|_| ())
+                .and_then(|data| serde_json::from_str(&data).map_err(|_| ()))
+        })
+        .collect()
+    }
+
+    fn write_thing(&mut self, thing: &Thing, replace: bool) -> Result<(), ()> {
+        let uuid = thing.uuid().ok_or(())?;
+        let data = serde_json::to_value(thing).map_err(|_| ())?;
+
+        self.connection
+            .execute(
+                if replace {
+                    "INSERT OR REPLACE INTO things (uuid, type, name_lower, location_uuid, data) VALUES (?1, ?2, ?3, ?4, ?5)"
+                } else {
+                    "INSERT INTO things (uuid, type, name_lower, location_uuid, data) VALUES (?1, ?2, ?3, ?4, ?5)"
+                },
+                params![
+                    uuid.to_string(),
+                    thing.as_str(),
+                    thing.name().value().map(|name| name.to_lowercase()),
+                    data["location_uuid"].as_str(),
+                    data.to_string(),
+                ],
+            )
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+}
+
+#[async_trait(?Send)]
+impl DataStore for SqliteDataStore {
+    async fn health_check(&self) -> Result<(), ()> {
+        self.connection
+            .query_row("SELECT 1", [], |_| Ok(()))
+            .map_err(|_| ())
+    }
+
+    async fn delete_thing_by_uuid(&mut self, uuid: &Uuid) -> Result<(), ()> {
+        match self
+            .connection
+            .execute("DELETE FROM things WHERE uuid = ?1", [uuid.to_string()])
+        {
+            Ok(0) | Err(_) => Err(()),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    async fn edit_thing(&mut self, thing: &Thing) -> Result<(), ()> {
+        self.write_thing(thing, true)
+    }
+
+    async fn get_all_the_things(&self) -> Result<Vec<Thing>, ()> {
+        self.query_things("SELECT data FROM things", Vec::new())
+    }
+
+    async fn get_thing_by_uuid(&self, uuid: &Uuid) -> Result<Option<Thing>, ()> {
+        self.query_things(
+            "SELECT data FROM things WHERE uuid = ?1",
+            vec![uuid.to_string().into()],
+        )
+        .map(|things| things.into_iter().next())
+    }
+
+    async fn get_thing_by_name(&self, name: &str) -> Result<Option<Thing>, ()> {
+        self.query_things(
+            "SELECT data FROM things WHERE name_lower = ?1 LIMIT 1",
+            vec![name.to_lowercase().into()],
+        )
+        .map(|things| things.into_iter().next())
+    }
+
+    async fn get_things_by_name_start(
+        &self,
+        name: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Thing>, ()> {
+        let start = name.to_lowercase();
+
+        // Every string that starts with `start` sorts between `start` and `start` followed by the
+        // highest possible character, which lets SQLite use the index rather than LIKE.
+        let end = format!("{}{}", start, char::MAX);
+
+        self.query_things(
+            "SELECT data FROM things WHERE name_lower >= ?1 AND name_lower < ?2 LIMIT ?3",
+            vec![
+                start.into(),
+                end.into(),
+                limit
+                    .and_then(|limit| i64::try_from(limit).ok())
+                    .unwrap_or(-1)
+                    .into(),
+            ],
+        )
+    }
+
+    async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+        let mut sql = "SELECT data FROM things WHERE 1".to_string();
+        let mut params = Vec::new();
+
+        if let Some(thing) = &query.thing {
+            params.push(thing.as_str().to_string().into());
+            sql.push_str(&format!(" AND type = ?{}", params.len()));
+        }
+
+        if let Some(location_uuid) = &query.location_uuid {
+            let location_uuid: &Uuid = location_uuid.as_ref();
+            params.push(location_uuid.to_string().into());
+            sql.push_str(&format!(" AND location_uuid = ?{}", params.len()));
+        }
+
+        self.query_things(&sql, params).map(|things| {
+            things
+                .into_iter()
+                .filter(|thing| query.matches(thing))
+                .collect()
+        })
+    }
+
+    async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()> {
+        self.write_thing(thing, false)
+    }
+
+    async fn set_value(&mut self, key: &str, value: &str) -> Result<(), ()> {
+        self.connection
+            .execute(
+                "INSERT OR REPLACE INTO key_values (key, value) VALUES (?1, ?2)",
+                [key, value],
+            )
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+
+    async fn get_value(&self, key: &str) -> Result<Option<String>, ()> {
+        self.connection
+            .query_row(
+                "SELECT value FROM key_values WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|_| ())
+    }
+
+    async fn delete_value(&mut self, key: &str) -> Result<(), ()> {
+        self.connection
+            .execute("DELETE FROM key_values WHERE key = ?1", [key])
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_UUID: Uuid = Uuid::from_u128(u128::MAX);
+
+    #[tokio::test]
+    async fn sqlite_conformance_test() {
+        initiative_core::test_data_store(data_store()).await;
+    }
+
+    #[test]
+    fn sqlite_index_test() {
+        let ds = data_store();
+
+        for (sql, index) in [
+            (
+                "SELECT data FROM things WHERE name_lower = 'a'",
+                "things_name_lower",
+            ),
+            (
+                "SELECT data FROM things WHERE name_lower >= 'a' AND name_lower < 'b'",
+                "things_name_lower",
+            ),
+            (
+                "SELECT data FROM things WHERE location_uuid = 'a'",
+                "things_location_uuid",
+            ),
+        ] {
+            let plan: String = ds
+                .connection
+                .query_row(&format!("EXPLAIN QUERY PLAN {}", sql), [], |row| row.get(3))
+                .unwrap();
+            assert!(plan.contains(index), "{}: {}", sql, plan);
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_persist_test() {
+        let path = std::env::temp_dir().join(format!("initiative-test-{}.sqlite", Uuid::new_v4()));
+
+        {
+            let mut ds = SqliteDataStore::open(&path).unwrap();
+            ds.save_thing(&odysseus()).await.unwrap();
+            ds.set_value("time", "1:08:00:00").await.unwrap();
+        }
+
+        {
+            let ds = SqliteDataStore::open(&path).unwrap();
+            assert_eq!(Ok(Some(odysseus())), ds.get_thing_by_name("odysseus").await);
+            assert_eq!(
+                Ok(Some("1:08:00:00".to_string())),
+                ds.get_value("time").await
+            );
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn data_store() -> SqliteDataStore {
+        SqliteDataStore::open_in_memory().unwrap()
+    }
+
+    fn odysseus() -> Thing {
+        serde_json::from_str(&format!(
+            r#"{{"type":"Npc","uuid":"{}","name":"Odysseus"}}"#,
+            TEST_UUID,
+        ))
+        .unwrap()
+    }
+}
@@ -6,16 +6,25 @@
 //! * No autocomplete suggestions are displayed
 //! * Markdown output is displayed literally rather than being formatted
 //! * No scrolling is present in the rich interface
-//! * No data storage is available
 //! * Import/export don't work
 
+pub use data_store::FileDataStore;
+#[cfg(feature = "sqlite")]
+pub use data_store::SqliteDataStore;
+pub use sync::{request_sync, HttpSyncTransport};
+
+mod data_store;
 mod light;
 mod rich;
+mod sync;
 
 use initiative_core::App;
 use std::io;
 
-pub async fn run(app: App) -> io::Result<()> {
+pub async fn run(mut app: App) -> io::Result<()> {
+    // Checks that the data store is usable and loads anything persisted from previous sessions.
+    app.init().await;
+
     if termion::is_tty(&io::stdin()) {
         rich::run(app).await
     } else {
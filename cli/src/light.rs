@@ -1,3 +1,4 @@
+use super::sync::sync_if_requested;
 use initiative_core::App;
 use std::io;
 
@@ -8,10 +9,18 @@ pub async fn run(mut app: App) -> io::Result<()> {
     loop {
         match stdin.read_line(&mut buffer) {
             Ok(0) => return Ok(()),
-            Ok(_) => match app.command(&buffer).await {
-                Ok(s) => println!("\n{}\n", s),
-                Err(e) => eprintln!("\n{}\n", e),
-            },
+            Ok(_) => {
+                match app.command(&buffer).await {
+                    Ok(s) => println!("\n{}\n", s),
+                    Err(e) => eprintln!("\n{}\n", e),
+                }
+
+                match sync_if_requested(&mut app).await {
+                    Some(Ok(s)) => println!("{}\n", s),
+                    Some(Err(e)) => eprintln!("{}\n", e),
+                    None => {}
+                }
+            }
             Err(e) => return Err(e),
         }
 
@@ -4,11 +4,38 @@ use std::error::Error;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let data_store = core::NullDataStore;
-    cli::run(core::app(data_store, &event_dispatcher)).await?;
+    #[cfg(feature = "sqlite")]
+    let app = if let Some(path) = cli::SqliteDataStore::default_path() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let data_store = cli::SqliteDataStore::open(&path)
+            .map_err(|e| format!("Couldn't open the journal at {}: {}", path.display(), e))?;
+        let mut app = core::app(data_store, &event_dispatcher);
+        app.configure_undo_history(50, true);
+        app
+    } else {
+        core::app(core::NullDataStore, &event_dispatcher)
+    };
+
+    #[cfg(not(feature = "sqlite"))]
+    let app = if let Some(path) = cli::FileDataStore::default_path() {
+        let data_store = cli::FileDataStore::open(&path)
+            .map_err(|e| format!("Couldn't open the journal at {}: {}", path.display(), e))?;
+        let mut app = core::app(data_store, &event_dispatcher);
+        app.configure_undo_history(50, true);
+        app
+    } else {
+        core::app(core::NullDataStore, &event_dispatcher)
+    };
+
+    cli::run(app).await?;
     Ok(())
 }
 
 fn event_dispatcher(event: core::Event) {
-    println!("Dispatched event: {:?}", event);
+    match event {
+        core::Event::Sync => cli::request_sync(),
+        event => println!("Dispatched event: {:?}", event),
+    }
 }
@@ -1,5 +1,6 @@
 mod wrap;
 
+use super::sync::sync_if_requested;
 use initiative_core::App;
 use std::fmt;
 use std::io;
@@ -91,7 +92,7 @@ pub async fn run(mut app: App) -> io::Result<()> {
 
         print!("{}", termion::clear::All);
 
-        let output = app.command(&command).await.unwrap_or_else(|e| {
+        let error = |e| {
             format!(
                 "{}{}{}{}{}",
                 color::Fg(color::Black),
@@ -100,7 +101,14 @@ pub async fn run(mut app: App) -> io::Result<()> {
                 color::Fg(color::Reset),
                 color::Bg(color::Reset),
             )
-        });
+        };
+
+        let mut output = app.command(&command).await.unwrap_or_else(error);
+
+        if let Some(result) = sync_if_requested(&mut app).await {
+            output.push_str("\n\n");
+            output.push_str(&result.unwrap_or_else(error));
+        }
 
         wrap(&output, termion::terminal_size().unwrap().0 as usize - 4)
             .lines()
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use initiative_core::{App, SyncChange, SyncTransport};
+use serde::Deserialize;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static SYNC_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Syncs with an instance of `initiative-sync-server` over HTTP.
+pub struct HttpSyncTransport {
+    agent: ureq::Agent,
+    url: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct PullResponse {
+    changes: Vec<SyncChange>,
+    cursor: u64,
+}
+
+impl HttpSyncTransport {
+    pub fn new(url: &str, token: &str) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            url: format!("{}/changes", url.trim_end_matches('/')),
+            token: format!("Bearer {}", token),
+        }
+    }
+
+    /// The server configured by the `INITIATIVE_SYNC_URL` and `INITIATIVE_SYNC_TOKEN`
+    /// environment variables, if both are set.
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("INITIATIVE_SYNC_URL")
+            .ok()
+            .filter(|s| !s.is_empty())?;
+        let token = env::var("INITIATIVE_SYNC_TOKEN")
+            .ok()
+            .filter(|s| !s.is_empty())?;
+        Some(Self::new(&url, &token))
+    }
+}
+
+#[async_trait(?Send)]
+impl SyncTransport for HttpSyncTransport {
+    async fn push(&mut self, changes: &[SyncChange]) -> Result<(), ()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        self.agent
+            .post(&self.url)
+            .set("Authorization", &self.token)
+            .send_json(serde_json::json!({ "changes": changes }))
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+
+    async fn pull(&mut self, cursor: u64) -> Result<(Vec<SyncChange>, u64), ()> {
+        let response: PullResponse = self
+            .agent
+            .get(&self.url)
+            .query("cursor", &cursor.to_string())
+            .set("Authorization", &self.token)
+            .call()
+            .map_err(|_| ())?
+            .into_json()
+            .map_err(|_| ())?;
+
+        Ok((response.changes, response.cursor))
+    }
+}
+
+/// Ask for the journal to be synced once the current command has finished. Call this when the app
+/// dispatches [`initiative_core::Event::Sync`].
+pub fn request_sync() {
+    SYNC_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Sync the journal if it was requested since the last call.
+pub(crate) async fn sync_if_requested(app: &mut App) -> Option<Result<String, String>> {
+    if !SYNC_REQUESTED.swap(false, Ordering::Relaxed) {
+        return None;
+    }
+
+    Some(match HttpSyncTransport::from_env() {
+        Some(mut transport) => app.sync(&mut transport).await,
+        None => Err("Sync isn't set up yet. Set `INITIATIVE_SYNC_URL` to the address of your sync server and `INITIATIVE_SYNC_TOKEN` to your token, then try again.".to_string()),
+    })
+}
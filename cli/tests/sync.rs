@@ -0,0 +1,135 @@
+use initiative_cli::HttpSyncTransport;
+use initiative_core::{App, MemoryDataStore, Uuid};
+use initiative_sync_server::{Journals, Server, Tokens};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// The journal shared with initiative-core's integration tests.
+const BACKUP: &str = include_str!("../../core/tests/fixtures/waterdeep.json");
+
+/// Start a sync server with a single user in a background thread, returning its URL, the user's
+/// token, and the directory holding its data.
+fn start_server() -> (String, String, PathBuf) {
+    start_server_with(|server| server)
+}
+
+fn start_server_with(configure: impl FnOnce(Server) -> Server) -> (String, String, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("initiative-sync-{}", Uuid::new_v4()));
+    let tokens_path = dir.join("tokens");
+    let token = Tokens::add_user(&tokens_path, "alice").unwrap();
+
+    let server = configure(
+        Server::bind(
+            "127.0.0.1:0",
+            Journals::new(dir.join("journals")),
+            Tokens::load(&tokens_path).unwrap(),
+        )
+        .unwrap(),
+    );
+    let url = format!("http://{}", server.local_addr());
+
+    thread::spawn(move || server.run());
+
+    (url, token, dir)
+}
+
+async fn app() -> App {
+    let mut app = initiative_core::app(MemoryDataStore::default(), &|_| {});
+    app.init().await;
+    app
+}
+
+#[tokio::test]
+async fn sync_through_server() {
+    let (url, token, dir) = start_server();
+    let mut laptop = app().await;
+    let mut phone = app().await;
+
+    laptop
+        .bulk_import(serde_json::from_str(BACKUP).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        Ok("Your journal is in sync. Sent 1 change and received 0 changes.".to_string()),
+        laptop.sync(&mut HttpSyncTransport::new(&url, &token)).await,
+    );
+    assert_eq!(
+        Ok("Your journal is in sync. Sent 0 changes and received 1 change.".to_string()),
+        phone.sync(&mut HttpSyncTransport::new(&url, &token)).await,
+    );
+
+    phone.command("Durnan is an elf").await.unwrap();
+    phone
+        .sync(&mut HttpSyncTransport::new(&url, &token))
+        .await
+        .unwrap();
+    laptop
+        .sync(&mut HttpSyncTransport::new(&url, &token))
+        .await
+        .unwrap();
+
+    let output = laptop.command("Durnan").await.unwrap();
+    assert!(output.contains("elf"), "{}", output);
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn sync_while_other_requests_stall() {
+    let (url, token, dir) = start_server_with(|server| {
+        server
+            .with_workers(2)
+            .with_request_timeout(Duration::from_millis(200))
+    });
+    let mut app = app().await;
+
+    // Pushes that promise a body but never send it, enough to occupy every worker.
+    let mut stalled: Vec<TcpStream> = (0..4)
+        .map(|_| {
+            let mut stream = TcpStream::connect(url.trim_start_matches("http://")).unwrap();
+            write!(
+                stream,
+                "POST /changes HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: 100\r\n\r\n{{",
+                token,
+            )
+            .unwrap();
+            stream
+        })
+        .collect();
+
+    app.bulk_import(serde_json::from_str(BACKUP).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        Ok("Your journal is in sync. Sent 1 change and received 0 changes.".to_string()),
+        app.sync(&mut HttpSyncTransport::new(&url, &token)).await,
+    );
+
+    for stream in &mut stalled {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 "), "{}", response);
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn sync_with_wrong_token() {
+    let (url, _, dir) = start_server();
+    let mut app = app().await;
+
+    assert_eq!(
+        Err("Couldn't reach the sync server. Your changes will be sent next time.".to_string()),
+        app.sync(&mut HttpSyncTransport::new(&url, "not-a-token"))
+            .await,
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,7 +1,9 @@
 use super::{
-    Autocomplete, AutocompleteSuggestion, Command, CommandMatches, ContextAwareParse, Runnable,
+    Autocomplete, AutocompleteSuggestion, Command, CommandMatches, CommandType, ContextAwareParse,
+    Runnable,
 };
 use crate::app::AppMeta;
+use crate::storage::StorageCommand;
 use crate::utils::CaseInsensitiveStr;
 use async_trait::async_trait;
 use std::borrow::Cow;
@@ -46,6 +48,38 @@ impl CommandAlias {
             Self::StrictWildcard { command, .. } => command,
         }
     }
+
+    /// If this alias refers to a thing by name, eg. `save Bob`, return a copy that refers to it
+    /// by its new name instead.
+    pub fn with_renamed_thing(&self, old_name: &str, new_name: &str) -> Option<Self> {
+        let command = match self.get_command().get_type() {
+            Some(CommandType::Storage(StorageCommand::Delete { name })) if name.eq_ci(old_name) => {
+                StorageCommand::Delete {
+                    name: new_name.to_string(),
+                }
+            }
+            Some(CommandType::Storage(StorageCommand::Load { name })) if name.eq_ci(old_name) => {
+                StorageCommand::Load {
+                    name: new_name.to_string(),
+                }
+            }
+            Some(CommandType::Storage(StorageCommand::Save { name })) if name.eq_ci(old_name) => {
+                StorageCommand::Save {
+                    name: new_name.to_string(),
+                }
+            }
+            _ => return None,
+        };
+
+        match self {
+            Self::Literal { term, .. } => Some(Self::literal(
+                term.clone(),
+                command.to_string(),
+                command.into(),
+            )),
+            Self::StrictWildcard { .. } => Some(Self::strict_wildcard(command.into())),
+        }
+    }
 }
 
 impl Hash for CommandAlias {
@@ -322,6 +356,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn with_renamed_thing_test() {
+        let alias = literal(
+            "save",
+            "save Bob",
+            StorageCommand::Save { name: "Bob".into() }.into(),
+        );
+
+        if let Some(CommandAlias::Literal {
+            term,
+            summary,
+            command,
+        }) = alias.with_renamed_thing("bob", "Robert")
+        {
+            assert_eq!("save", term);
+            assert_eq!("save Robert", summary);
+            assert_eq!(
+                Box::new(Command::from(StorageCommand::Save {
+                    name: "Robert".into(),
+                })),
+                command,
+            );
+        } else {
+            panic!();
+        }
+
+        assert_eq!(None, alias.with_renamed_thing("Alice", "Robert"));
+        assert_eq!(
+            None,
+            literal("about", "about", AppCommand::About.into()).with_renamed_thing("Bob", "Robert"),
+        );
+    }
+
     fn event_dispatcher(_event: Event) {}
 
     fn app_meta() -> AppMeta {
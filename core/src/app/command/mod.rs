@@ -14,10 +14,14 @@ mod runnable;
 mod tutorial;
 
 use super::AppMeta;
+use crate::combat::CombatCommand;
+use crate::log::LogCommand;
 use crate::reference::ReferenceCommand;
 use crate::storage::StorageCommand;
-use crate::time::TimeCommand;
-use crate::world::WorldCommand;
+use crate::time::{CalendarCommand, TimeCommand};
+use crate::travel::TravelCommand;
+use crate::weather::WeatherCommand;
+use crate::world::{NoteCommand, WorldCommand};
 use async_trait::async_trait;
 use futures::join;
 use std::fmt;
@@ -48,10 +52,16 @@ impl Command {
         let parse_results = join!(
             CommandAlias::parse_input(input, app_meta),
             AppCommand::parse_input(input, app_meta),
+            CalendarCommand::parse_input(input, app_meta),
+            CombatCommand::parse_input(input, app_meta),
+            LogCommand::parse_input(input, app_meta),
+            NoteCommand::parse_input(input, app_meta),
             ReferenceCommand::parse_input(input, app_meta),
             StorageCommand::parse_input(input, app_meta),
             TimeCommand::parse_input(input, app_meta),
+            TravelCommand::parse_input(input, app_meta),
             TutorialCommand::parse_input(input, app_meta),
+            WeatherCommand::parse_input(input, app_meta),
             WorldCommand::parse_input(input, app_meta),
         );
 
@@ -62,7 +72,13 @@ impl Command {
             .union(parse_results.3)
             .union(parse_results.4)
             .union(parse_results.5)
-            .union(parse_results.6);
+            .union(parse_results.6)
+            .union(parse_results.7)
+            .union(parse_results.8)
+            .union(parse_results.9)
+            .union(parse_results.10)
+            .union(parse_results.11)
+            .union(parse_results.12);
 
         // While it is normally a fatal error to encounter two command subtypes claiming canonical
         // matches on a given input, the exception is where aliases are present. In this case, we
@@ -160,10 +176,16 @@ impl Autocomplete for Command {
         let results = join!(
             CommandAlias::autocomplete(input, app_meta),
             AppCommand::autocomplete(input, app_meta),
+            CalendarCommand::autocomplete(input, app_meta),
+            CombatCommand::autocomplete(input, app_meta),
+            LogCommand::autocomplete(input, app_meta),
+            NoteCommand::autocomplete(input, app_meta),
             ReferenceCommand::autocomplete(input, app_meta),
             StorageCommand::autocomplete(input, app_meta),
             TimeCommand::autocomplete(input, app_meta),
+            TravelCommand::autocomplete(input, app_meta),
             TutorialCommand::autocomplete(input, app_meta),
+            WeatherCommand::autocomplete(input, app_meta),
             WorldCommand::autocomplete(input, app_meta),
         );
 
@@ -175,6 +197,12 @@ impl Autocomplete for Command {
             .chain(results.4)
             .chain(results.5)
             .chain(results.6)
+            .chain(results.7)
+            .chain(results.8)
+            .chain(results.9)
+            .chain(results.10)
+            .chain(results.11)
+            .chain(results.12)
             .collect()
     }
 }
@@ -183,10 +211,16 @@ impl Autocomplete for Command {
 pub enum CommandType {
     Alias(CommandAlias),
     App(AppCommand),
+    Calendar(CalendarCommand),
+    Combat(CombatCommand),
+    Log(LogCommand),
+    Note(NoteCommand),
     Reference(ReferenceCommand),
     Storage(StorageCommand),
     Time(TimeCommand),
+    Travel(TravelCommand),
     Tutorial(TutorialCommand),
+    Weather(WeatherCommand),
     World(WorldCommand),
 }
 
@@ -199,10 +233,16 @@ impl CommandType {
         match self {
             Self::Alias(c) => c.run(input, app_meta).await,
             Self::App(c) => c.run(input, app_meta).await,
+            Self::Calendar(c) => c.run(input, app_meta).await,
+            Self::Combat(c) => c.run(input, app_meta).await,
+            Self::Log(c) => c.run(input, app_meta).await,
+            Self::Note(c) => c.run(input, app_meta).await,
             Self::Reference(c) => c.run(input, app_meta).await,
             Self::Storage(c) => c.run(input, app_meta).await,
             Self::Time(c) => c.run(input, app_meta).await,
+            Self::Travel(c) => c.run(input, app_meta).await,
             Self::Tutorial(c) => c.run(input, app_meta).await,
+            Self::Weather(c) => c.run(input, app_meta).await,
             Self::World(c) => c.run(input, app_meta).await,
         }
     }
@@ -213,10 +253,16 @@ impl fmt::Display for CommandType {
         match self {
             Self::Alias(c) => write!(f, "{}", c),
             Self::App(c) => write!(f, "{}", c),
+            Self::Calendar(c) => write!(f, "{}", c),
+            Self::Combat(c) => write!(f, "{}", c),
+            Self::Log(c) => write!(f, "{}", c),
+            Self::Note(c) => write!(f, "{}", c),
             Self::Reference(c) => write!(f, "{}", c),
             Self::Storage(c) => write!(f, "{}", c),
             Self::Time(c) => write!(f, "{}", c),
+            Self::Travel(c) => write!(f, "{}", c),
             Self::Tutorial(c) => write!(f, "{}", c),
+            Self::Weather(c) => write!(f, "{}", c),
             Self::World(c) => write!(f, "{}", c),
         }
     }
@@ -242,6 +288,30 @@ impl From<CommandAlias> for CommandType {
     }
 }
 
+impl From<CalendarCommand> for CommandType {
+    fn from(c: CalendarCommand) -> CommandType {
+        CommandType::Calendar(c)
+    }
+}
+
+impl From<CombatCommand> for CommandType {
+    fn from(c: CombatCommand) -> CommandType {
+        CommandType::Combat(c)
+    }
+}
+
+impl From<LogCommand> for CommandType {
+    fn from(c: LogCommand) -> CommandType {
+        CommandType::Log(c)
+    }
+}
+
+impl From<NoteCommand> for CommandType {
+    fn from(c: NoteCommand) -> CommandType {
+        CommandType::Note(c)
+    }
+}
+
 impl From<ReferenceCommand> for CommandType {
     fn from(c: ReferenceCommand) -> CommandType {
         CommandType::Reference(c)
@@ -260,12 +330,24 @@ impl From<TimeCommand> for CommandType {
     }
 }
 
+impl From<TravelCommand> for CommandType {
+    fn from(c: TravelCommand) -> CommandType {
+        CommandType::Travel(c)
+    }
+}
+
 impl From<TutorialCommand> for CommandType {
     fn from(c: TutorialCommand) -> CommandType {
         CommandType::Tutorial(c)
     }
 }
 
+impl From<WeatherCommand> for CommandType {
+    fn from(c: WeatherCommand) -> CommandType {
+        CommandType::Weather(c)
+    }
+}
+
 impl From<WorldCommand> for CommandType {
     fn from(c: WorldCommand) -> CommandType {
         CommandType::World(c)
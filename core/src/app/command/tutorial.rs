@@ -399,7 +399,10 @@ impl TutorialCommand {
                 }
             }
             Self::LoadingFromJournal { .. } => {
-                matches!(command, Some(CommandType::Storage(StorageCommand::Journal)))
+                matches!(
+                    command,
+                    Some(CommandType::Storage(StorageCommand::Journal { .. }))
+                )
             }
             Self::SrdReference { npc_name, .. } => {
                 if let Some(CommandType::Storage(StorageCommand::Load { name })) = command {
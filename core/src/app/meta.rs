@@ -1,4 +1,5 @@
 use super::{CommandAlias, Event};
+use crate::storage::backup::ImportOptions;
 use crate::storage::{DataStore, Repository};
 use crate::world;
 use rand::prelude::*;
@@ -9,8 +10,16 @@ pub struct AppMeta {
     pub command_aliases: HashSet<CommandAlias>,
     pub demographics: world::Demographics,
     pub event_dispatcher: &'static dyn Fn(Event),
+
+    /// The options given to the most recent `import` command, used once the user has chosen a
+    /// file to import.
+    pub import_options: ImportOptions,
+
     pub rng: SmallRng,
     pub repository: Repository,
+
+    /// The number of the current session, used to group entries in the session log.
+    pub session: u32,
 }
 
 impl AppMeta {
@@ -22,8 +31,10 @@ impl AppMeta {
             command_aliases: HashSet::default(),
             demographics: world::Demographics::default(),
             event_dispatcher,
+            import_options: ImportOptions::default(),
             repository: Repository::new(data_store),
             rng: SmallRng::from_entropy(),
+            session: 1,
         }
     }
 }
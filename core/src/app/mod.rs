@@ -10,8 +10,13 @@ pub use command::assert_autocomplete;
 mod command;
 mod meta;
 
-use crate::storage::backup::{import, BackupData};
+use crate::log;
+use crate::storage::backup::{import, preview, BackupData, ImportOptions, ImportStrategy};
+use crate::storage::spreadsheet::import_csv;
+use crate::storage::sync::{sync, SyncTransport};
+use crate::storage::KeyValue;
 use crate::utils::CaseInsensitiveStr;
+use command::CommandType;
 use initiative_macros::motd;
 
 /// The application wrapper. Its inner [`AppMeta`] object holds metadata associated with the
@@ -28,11 +33,33 @@ pub struct App {
 /// An event that can occur while the app is running that may require special handling by the UI.
 #[derive(Debug)]
 pub enum Event {
-    /// The user typed the `export` command and the journal backup is ready to download.
-    Export(BackupData),
+    /// The user typed the `export` command and the journal backup or document is ready to
+    /// download.
+    Export(ExportData),
+
+    /// The user typed the `log export` command and the session log, formatted as markdown, is
+    /// ready to download.
+    ExportLog(String),
 
     /// The user typed the `import` command and should be prompted to select a file to import.
     Import,
+
+    /// The user typed the `sync` command. The UI should call [`App::sync`] with a transport
+    /// connected to the user's sync server.
+    Sync,
+}
+
+/// The contents of an [`Event::Export`].
+#[derive(Debug)]
+pub enum ExportData {
+    /// A JSON backup of the journal, or part of it, as produced by `export`.
+    Backup(BackupData),
+
+    /// The journal as a readable campaign document, as produced by `export markdown`.
+    Markdown(String),
+
+    /// The journal as a web page, as produced by `export html`.
+    Html(String),
 }
 
 impl App {
@@ -40,11 +67,20 @@ impl App {
         App { meta }
     }
 
+    /// Set how many changes can be undone, and whether the undo history should be kept in the
+    /// data store so that it survives a reload. This must be called before [`App::init`].
+    pub fn configure_undo_history(&mut self, len: usize, persist: bool) {
+        self.meta.repository.set_undo_history_len(len);
+        self.meta.repository.set_persist_undo_history(persist);
+    }
+
     /// Initialize a running application. This is done as a separate step from the constructor
     /// because it runs asynchronously. Its purpose, in turn, is to trigger the underlying data
     /// store to initialize, which may involve opening a database connection.
     pub async fn init(&mut self) -> &'static str {
         self.meta.repository.init().await;
+        self.meta.session = log::next_session(&self.meta).await;
+        self.meta.repository.flush().await.ok();
         let (motd, motd_len) = motd!("! Local storage is not available in your browser. You will be able to use initiative.sh, but anything you save will not persist beyond this session.");
 
         if self.meta.repository.data_store_enabled() {
@@ -58,10 +94,30 @@ impl App {
     ///
     /// On success or failure, returns a String that can be displayed back to the user.
     pub async fn command(&mut self, input: &str) -> Result<String, String> {
-        Command::parse_input_irrefutable(input, &self.meta)
-            .await
-            .run(input, &mut self.meta)
+        let command = Command::parse_input_irrefutable(input, &self.meta).await;
+
+        // Browsing the log shouldn't add to it.
+        if matches!(command.get_type(), Some(CommandType::Log(_))) {
+            let result = command.run(input, &mut self.meta).await;
+            return self.flush(result).await;
+        }
+
+        let time = self
+            .meta
+            .repository
+            .get_key_value(&KeyValue::Time(None))
             .await
+            .ok()
+            .and_then(|kv| kv.time())
+            .unwrap_or_default();
+
+        let result = command.run(input, &mut self.meta).await;
+
+        if let Ok(output) = &result {
+            log::record(&mut self.meta, input, output, time).await;
+        }
+
+        self.flush(result).await
     }
 
     /// The user has updated their input and a new set of suggestions should be populated. This
@@ -79,11 +135,70 @@ impl App {
     }
 
     /// The part of the import flow that occurs after the user selects a file in response to the
-    /// [`Event::Import`].
+    /// [`Event::Import`]. The options given to the `import` command that triggered the event
+    /// determine whether the backup is imported or only previewed.
     pub async fn bulk_import(&mut self, data: BackupData) -> Result<String, String> {
-        import(&mut self.meta.repository, data)
+        let ImportOptions {
+            preview: is_preview,
+            strategy,
+        } = std::mem::take(&mut self.meta.import_options);
+
+        if is_preview {
+            Ok(preview(&self.meta.repository, data, strategy)
+                .await
+                .to_string())
+        } else {
+            let result = Ok(import(&mut self.meta.repository, data, strategy)
+                .await
+                .to_string());
+            self.flush(result).await
+        }
+    }
+
+    /// The counterpart to [`App::bulk_import`] for spreadsheets: create a journal entry for each
+    /// row of a CSV file, describing any rows that couldn't be imported. Rows naming an entry
+    /// that's already in the journal are among them, so the options for previewing an import and
+    /// choosing how to handle existing entries don't apply.
+    pub async fn bulk_import_csv(&mut self, csv: &str) -> Result<String, String> {
+        let ImportOptions { preview, strategy } = std::mem::take(&mut self.meta.import_options);
+
+        if preview {
+            return Err("`import --preview` only works with journal backups.".to_string());
+        } else if strategy != ImportStrategy::default() {
+            return Err(format!(
+                "`import {}` only works with journal backups. Spreadsheet rows naming an entry that's already in your journal are never imported.",
+                strategy.flag(),
+            ));
+        }
+
+        let result = import_csv(&mut self.meta.repository, csv)
             .await
-            .map(|stats| stats.to_string())
-            .map_err(|_| "Failed to import.".to_string())
+            .map(|stats| stats.to_string());
+        self.flush(result).await
+    }
+
+    /// Exchange changes to the journal with other devices through a sync server. The first sync
+    /// on a device sends the whole journal; after that, only what changed is sent.
+    pub async fn sync(&mut self, transport: &mut dyn SyncTransport) -> Result<String, String> {
+        let result = sync(&mut self.meta.repository, transport)
+            .await
+            .map(|report| report.to_string())
+            .map_err(|e| e.to_string());
+        self.flush(result).await
+    }
+
+    /// Write the changes made by a command through to the data store, warning the user in the
+    /// command's output if they couldn't be saved. They stay in memory, so the next command will
+    /// try again.
+    async fn flush(&mut self, result: Result<String, String>) -> Result<String, String> {
+        if self.meta.repository.flush().await.is_ok() {
+            return result;
+        }
+
+        let warning = "! Your journal couldn't be saved. initiative.sh will try again after your next command.";
+        match result {
+            Ok(output) => Ok(format!("{}\n\n{}", output, warning)),
+            Err(output) => Err(format!("{}\n\n{}", output, warning)),
+        }
     }
 }
@@ -0,0 +1,791 @@
+use super::{Combat, Combatant};
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
+};
+use crate::reference::{Condition, Monster};
+use crate::storage::{Change, KeyValue};
+use crate::time::{self, Interval};
+use crate::utils::CaseInsensitiveStr;
+use async_trait::async_trait;
+use caith::Roller;
+use std::fmt;
+
+/// The largest amount of damage, healing, or hit points that a command can give.
+const MAX_HP: i32 = u16::MAX as i32;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CombatCommand {
+    Add {
+        name: String,
+        initiative: Initiative,
+    },
+    Condition {
+        name: String,
+        condition: Condition,
+    },
+    Damage {
+        name: String,
+        amount: i32,
+    },
+    End,
+    Heal {
+        name: String,
+        amount: i32,
+    },
+    Next,
+    SetHp {
+        name: String,
+        hp: i32,
+    },
+    Show,
+}
+
+/// How a combatant's place in the initiative order is to be determined.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Initiative {
+    /// A value rolled at the table, eg. `fight Aria 17`.
+    Fixed(i32),
+
+    /// A dice formula to roll, eg. `fight goblin d20+2`.
+    Roll(String),
+
+    /// A d20 roll, plus the initiative bonus of SRD monsters.
+    Default,
+}
+
+#[async_trait(?Send)]
+impl Runnable for CombatCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        let combat = app_meta
+            .repository
+            .get_key_value(&KeyValue::Combat(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .combat();
+
+        if let Self::Show = self {
+            return combat.map(|combat| combat.to_string()).ok_or_else(|| {
+                "There is no fight in progress. Use `fight [name]` to add a combatant.".to_string()
+            });
+        }
+
+        let mut combat = match (combat, &self) {
+            (Some(combat), _) => combat,
+            (None, Self::Add { .. }) => Combat::default(),
+            (None, _) => return Err("There is no fight in progress.".to_string()),
+        };
+
+        let mut effects_ended = String::new();
+        let mut time_change = None;
+
+        let (message, time_advanced) = match self {
+            Self::Add { name, initiative } => {
+                let (name, uuid, monster) = match app_meta.repository.get_by_name(&name).await {
+                    Ok(thing) if thing.npc().is_some() => (
+                        thing.name().value().cloned().unwrap_or(name),
+                        thing.uuid().cloned(),
+                        None,
+                    ),
+                    _ => match name.parse::<Monster>() {
+                        Ok(monster) => (
+                            monster_name(&combat, monster.get_name()),
+                            None,
+                            Some(monster),
+                        ),
+                        Err(()) => (name, None, None),
+                    },
+                };
+
+                if combat.combatants.iter().any(|c| c.name.eq_ci(&name)) {
+                    return Err(format!("{} is already in the fight.", name));
+                }
+
+                let initiative_value = match initiative {
+                    Initiative::Fixed(value) => value,
+                    Initiative::Roll(formula) => roll_initiative(&formula, app_meta)?,
+                    Initiative::Default => match &monster {
+                        Some(monster) if monster.get_initiative_bonus() != 0 => roll_initiative(
+                            &format!("d20{:+}", monster.get_initiative_bonus()),
+                            app_meta,
+                        )?,
+                        _ => roll_initiative("d20", app_meta)?,
+                    },
+                };
+
+                let mut combatant = Combatant::new(name, uuid, initiative_value);
+
+                let message = if let Some(monster) = monster {
+                    combatant.hp = Some(monster.get_hit_points());
+                    combatant.max_hp = combatant.hp;
+
+                    format!(
+                        "{} joins the fight with an initiative of {} and {} HP.",
+                        combatant.name,
+                        initiative_value,
+                        monster.get_hit_points(),
+                    )
+                } else {
+                    format!(
+                        "{} joins the fight with an initiative of {}.",
+                        combatant.name, initiative_value,
+                    )
+                };
+
+                combat.add(combatant);
+                (message, false)
+            }
+            Self::Condition { name, condition } => {
+                let combatant = get_combatant(&mut combat, &name)?;
+                let condition_name = condition.get_name();
+
+                let message = if let Some(index) = combatant
+                    .conditions
+                    .iter()
+                    .position(|c| c.eq_ci(condition_name))
+                {
+                    combatant.conditions.remove(index);
+                    format!(
+                        "{} is no longer {}.",
+                        combatant.name,
+                        condition_name.to_lowercase(),
+                    )
+                } else {
+                    combatant.conditions.push(condition_name.to_string());
+                    format!(
+                        "{} is now {}.",
+                        combatant.name,
+                        condition_name.to_lowercase(),
+                    )
+                };
+
+                (message, false)
+            }
+            Self::Damage { name, amount } => {
+                check_amount(amount)?;
+                let combatant = get_combatant(&mut combat, &name)?;
+                let hp = combatant.hp.ok_or_else(|| untracked_hp(&combatant.name))? - amount;
+                combatant.hp = Some(hp);
+
+                let message = if hp > 0 {
+                    format!(
+                        "{} takes {} damage and has {} HP remaining.",
+                        combatant.name, amount, hp,
+                    )
+                } else {
+                    format!("{} takes {} damage and is down!", combatant.name, amount)
+                };
+
+                (message, false)
+            }
+            Self::End => {
+                app_meta
+                    .repository
+                    .modify(Change::SetKeyValue {
+                        key_value: KeyValue::Combat(None),
+                    })
+                    .await
+                    .map_err(|_| "Storage error.".to_string())?;
+
+                return Ok(format!(
+                    "The fight is over after {} round{}. Use `undo` to reverse.",
+                    combat.round,
+                    if combat.round == 1 { "" } else { "s" },
+                ));
+            }
+            Self::Heal { name, amount } => {
+                check_amount(amount)?;
+                let combatant = get_combatant(&mut combat, &name)?;
+                let mut hp = combatant
+                    .hp
+                    .ok_or_else(|| untracked_hp(&combatant.name))?
+                    .max(0)
+                    + amount;
+
+                if let Some(max_hp) = combatant.max_hp {
+                    hp = hp.min(max_hp);
+                }
+                combatant.hp = Some(hp);
+
+                (
+                    format!(
+                        "{} heals {} and has {} HP remaining.",
+                        combatant.name, amount, hp,
+                    ),
+                    false,
+                )
+            }
+            Self::Next => {
+                if combat.combatants.is_empty() {
+                    return Err(
+                        "No one has joined the fight yet. Use `fight [name]` to add a combatant."
+                            .to_string(),
+                    );
+                }
+
+                let new_round = combat.next_turn();
+
+                if new_round {
+                    let (change, ended) = advance_time(app_meta).await?;
+                    time_change = Some(change);
+                    effects_ended = ended;
+                }
+
+                (
+                    format!(
+                        "It is {}'s turn.",
+                        combat
+                            .current()
+                            .map(|c| c.name.as_str())
+                            .unwrap_or_default(),
+                    ),
+                    new_round,
+                )
+            }
+            Self::SetHp { name, hp } => {
+                check_amount(hp)?;
+                let combatant = get_combatant(&mut combat, &name)?;
+                combatant.hp = Some(hp);
+                if !matches!(combatant.max_hp, Some(max_hp) if max_hp >= hp) {
+                    combatant.max_hp = Some(hp);
+                }
+
+                (format!("{} has {} HP.", combatant.name, hp), false)
+            }
+            Self::Show => unreachable!(),
+        };
+
+        let output = format!(
+            "{}\n\n_{}{} Use `undo` to reverse._{}",
+            combat,
+            message,
+            if time_advanced {
+                " One round has passed."
+            } else {
+                ""
+            },
+            effects_ended,
+        );
+
+        let combat_change = Change::SetKeyValue {
+            key_value: KeyValue::Combat(Some(combat)),
+        };
+
+        // A new round moves the clock forward as well, and both are undone together.
+        let change = if let Some(time_change) = time_change {
+            Change::Batch {
+                changes: vec![time_change, combat_change],
+            }
+        } else {
+            combat_change
+        };
+
+        app_meta
+            .repository
+            .modify(change)
+            .await
+            .map(|_| output)
+            .map_err(|_| "Storage error.".to_string())
+    }
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for CombatCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        if input.in_ci(&["fight", "initiative"]) {
+            CommandMatches::new_canonical(Self::Show)
+        } else if input.eq_ci("combat") {
+            CommandMatches::new_fuzzy(Self::Show)
+        } else if input.eq_ci("next") {
+            CommandMatches::new_canonical(Self::Next)
+        } else if input.in_ci(&["end fight", "end combat"]) {
+            CommandMatches::new_canonical(Self::End)
+        } else if let Some(args) = input
+            .strip_prefix_ci("fight ")
+            .or_else(|| input.strip_prefix_ci("initiative "))
+        {
+            parse_add(args.trim())
+                .map(CommandMatches::new_canonical)
+                .unwrap_or_default()
+        } else if let Some(args) = input.strip_prefix_ci("condition ") {
+            split_last_word(args)
+                .and_then(|(name, condition)| {
+                    Some(Self::Condition {
+                        name: name.to_string(),
+                        condition: condition.parse().ok()?,
+                    })
+                })
+                .map(CommandMatches::new_canonical)
+                .unwrap_or_default()
+        } else if let Some((prefix, (name, amount))) =
+            ["damage ", "heal ", "hp "].into_iter().find_map(|prefix| {
+                input
+                    .strip_prefix_ci(prefix)
+                    .and_then(split_last_word)
+                    .map(|args| (prefix, args))
+            })
+        {
+            match (prefix, parse_amount(amount)) {
+                ("damage ", Some(amount)) => CommandMatches::new_canonical(Self::Damage {
+                    name: name.to_string(),
+                    amount,
+                }),
+                ("heal ", Some(amount)) => CommandMatches::new_canonical(Self::Heal {
+                    name: name.to_string(),
+                    amount,
+                }),
+                ("hp ", Some(hp)) => CommandMatches::new_canonical(Self::SetHp {
+                    name: name.to_string(),
+                    hp,
+                }),
+                _ => CommandMatches::default(),
+            }
+        } else {
+            CommandMatches::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for CombatCommand {
+    async fn autocomplete(input: &str, app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        let combat = app_meta
+            .repository
+            .get_key_value(&KeyValue::Combat(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.combat());
+
+        let mut suggestions: Vec<AutocompleteSuggestion> = [
+            ("fight", "fight [name]", "add a combatant to the fight"),
+            ("initiative", "initiative", "show the initiative order"),
+        ]
+        .into_iter()
+        .chain(
+            [
+                ("next", "next", "advance to the next turn"),
+                ("damage", "damage [name] [amount]", "damage a combatant"),
+                ("heal", "heal [name] [amount]", "heal a combatant"),
+                ("hp", "hp [name] [amount]", "set a combatant's hit points"),
+                (
+                    "condition",
+                    "condition [name] [condition]",
+                    "toggle a condition",
+                ),
+                ("end fight", "end fight", "end the current fight"),
+            ]
+            .into_iter()
+            .filter(|_| combat.is_some()),
+        )
+        .filter(|(s, _, _)| s.starts_with_ci(input))
+        .map(|(_, term, summary)| AutocompleteSuggestion::new(term, summary))
+        .collect();
+
+        if let Some(combat) = combat {
+            for (prefix, suffix, summary) in [
+                ("damage ", " [amount]", "damage"),
+                ("heal ", " [amount]", "heal"),
+                ("hp ", " [amount]", "set hit points for"),
+                ("condition ", " [condition]", "toggle a condition on"),
+            ] {
+                if let Some(name_start) = input.strip_prefix_ci(prefix) {
+                    suggestions.extend(
+                        combat
+                            .combatants
+                            .iter()
+                            .filter(|c| c.name.starts_with_ci(name_start))
+                            .map(|c| {
+                                AutocompleteSuggestion::new(
+                                    format!("{}{}{}", &input[..prefix.len()], c.name, suffix),
+                                    format!("{} {}", summary, c.name),
+                                )
+                            }),
+                    );
+                }
+            }
+        }
+
+        suggestions
+    }
+}
+
+impl fmt::Display for CombatCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Add { name, initiative } => match initiative {
+                Initiative::Fixed(value) => write!(f, "fight {} {}", name, value),
+                Initiative::Roll(formula) => write!(f, "fight {} {}", name, formula),
+                Initiative::Default => write!(f, "fight {}", name),
+            },
+            Self::Condition { name, condition } => write!(
+                f,
+                "condition {} {}",
+                name,
+                condition.get_name().to_lowercase(),
+            ),
+            Self::Damage { name, amount } => write!(f, "damage {} {}", name, amount),
+            Self::End => write!(f, "end fight"),
+            Self::Heal { name, amount } => write!(f, "heal {} {}", name, amount),
+            Self::Next => write!(f, "next"),
+            Self::SetHp { name, hp } => write!(f, "hp {} {}", name, hp),
+            Self::Show => write!(f, "initiative"),
+        }
+    }
+}
+
+/// Parse the arguments to `fight`, ie. a name optionally followed by an initiative value (`17`),
+/// modifier (`+2`), or dice formula (`2d20k1+2`).
+fn parse_add(input: &str) -> Option<CombatCommand> {
+    if input.is_empty() {
+        return None;
+    }
+
+    let (name, initiative) = match split_last_word(input) {
+        Some((name, value)) if value.chars().all(|c| c.is_ascii_digit()) => {
+            (name, Initiative::Fixed(value.parse().ok()?))
+        }
+        Some((name, modifier)) if modifier.starts_with(&['+', '-'][..]) => {
+            (name, Initiative::Roll(format!("d20{}", modifier)))
+        }
+        Some((name, formula))
+            if formula.starts_with(|c: char| c.is_ascii_digit() || c == 'd')
+                && formula.chars().any(|c| c.is_ascii_digit())
+                && Roller::new(formula)
+                    .ok()
+                    .and_then(|r| r.roll().ok())
+                    .is_some() =>
+        {
+            (name, Initiative::Roll(formula.to_string()))
+        }
+        _ => (input, Initiative::Default),
+    };
+
+    Some(CombatCommand::Add {
+        name: name.to_string(),
+        initiative,
+    })
+}
+
+fn roll_initiative(formula: &str, app_meta: &mut AppMeta) -> Result<i32, String> {
+    Roller::new(formula)
+        .ok()
+        .and_then(|roller| roller.roll_with(&mut app_meta.rng).ok())
+        .and_then(|result| result.as_single().map(|r| r.get_total()))
+        .and_then(|total| total.try_into().ok())
+        .ok_or_else(|| format!("\"{}\" is not a valid dice formula.", formula))
+}
+
+/// SRD monsters often come in groups, so each one after the first gets a number, eg. "Goblin 2".
+fn monster_name(combat: &Combat, name: &str) -> String {
+    (1..)
+        .map(|i| match i {
+            1 => name.to_string(),
+            i => format!("{} {}", name, i),
+        })
+        .find(|candidate| !combat.combatants.iter().any(|c| c.name.eq_ci(candidate)))
+        .unwrap()
+}
+
+/// Split the input on the last space, eg. `"Goblin Archer 5"` becomes `("Goblin Archer", "5")`.
+fn split_last_word(input: &str) -> Option<(&str, &str)> {
+    input
+        .trim()
+        .rsplit_once(' ')
+        .map(|(start, end)| (start.trim_end(), end))
+        .filter(|(start, end)| !start.is_empty() && !end.is_empty())
+}
+
+/// A whole number of hit points. Numbers too large to track still parse, so that running the
+/// command can explain why it was refused rather than it going unrecognized.
+fn parse_amount(input: &str) -> Option<i32> {
+    let digits = input.strip_prefix('+').unwrap_or(input);
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(digits.parse().unwrap_or(i32::MAX))
+}
+
+fn check_amount(amount: i32) -> Result<(), String> {
+    if amount > MAX_HP {
+        Err(format!(
+            "That's more hit points than can be tracked. The most is {}.",
+            MAX_HP,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn get_combatant<'a>(combat: &'a mut Combat, name: &str) -> Result<&'a mut Combatant, String> {
+    combat
+        .get_mut(name)
+        .ok_or_else(|| format!("There is no combatant named \"{}\".", name))
+}
+
+fn untracked_hp(name: &str) -> String {
+    format!(
+        "{}'s hit points aren't being tracked. Use `hp {} [amount]` to set them.",
+        name, name,
+    )
+}
+
+/// The change that moves the clock forward by a round, along with a description of any effects
+/// that end as a result.
+async fn advance_time(app_meta: &AppMeta) -> Result<(Change, String), String> {
+    let old_time = app_meta
+        .repository
+        .get_key_value(&KeyValue::Time(None))
+        .await
+        .map_err(|_| "Storage error.".to_string())?
+        .time()
+        .unwrap_or_default();
+    let time = old_time
+        .checked_add(&Interval::new_rounds(1))
+        .ok_or_else(|| "Unable to advance time by 1 round.".to_string())?;
+    let effects_ended = time::describe_elapsed(&app_meta.repository, &old_time, &time).await;
+
+    Ok((
+        Change::SetKeyValue {
+            key_value: KeyValue::Time(Some(time)),
+        },
+        effects_ended,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::{Event, MemoryDataStore};
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Show),
+            block_on(CombatCommand::parse_input("initiative", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Show),
+            block_on(CombatCommand::parse_input("FIGHT", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_fuzzy(CombatCommand::Show),
+            block_on(CombatCommand::parse_input("combat", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Add {
+                name: "Goblin Archer".to_string(),
+                initiative: Initiative::Default,
+            }),
+            block_on(CombatCommand::parse_input("fight Goblin Archer", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Add {
+                name: "Aria".to_string(),
+                initiative: Initiative::Fixed(17),
+            }),
+            block_on(CombatCommand::parse_input("initiative Aria 17", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Add {
+                name: "goblin".to_string(),
+                initiative: Initiative::Roll("d20+2".to_string()),
+            }),
+            block_on(CombatCommand::parse_input("fight goblin +2", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Add {
+                name: "goblin".to_string(),
+                initiative: Initiative::Roll("2d20k1".to_string()),
+            }),
+            block_on(CombatCommand::parse_input("fight goblin 2d20k1", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Damage {
+                name: "Goblin Archer".to_string(),
+                amount: 5,
+            }),
+            block_on(CombatCommand::parse_input(
+                "damage Goblin Archer 5",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Heal {
+                name: "Bob".to_string(),
+                amount: 3,
+            }),
+            block_on(CombatCommand::parse_input("heal Bob 3", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::SetHp {
+                name: "Bob".to_string(),
+                hp: 12,
+            }),
+            block_on(CombatCommand::parse_input("hp Bob 12", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Condition {
+                name: "Bob".to_string(),
+                condition: Condition::Prone,
+            }),
+            block_on(CombatCommand::parse_input("condition Bob prone", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Next),
+            block_on(CombatCommand::parse_input("next", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::End),
+            block_on(CombatCommand::parse_input("end combat", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(CombatCommand::parse_input("damage Bob", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(CombatCommand::parse_input("damage Bob -5", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::SetHp {
+                name: "Bob".to_string(),
+                hp: 99999,
+            }),
+            block_on(CombatCommand::parse_input("hp Bob 99999", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CombatCommand::Damage {
+                name: "Bob".to_string(),
+                amount: i32::MAX,
+            }),
+            block_on(CombatCommand::parse_input(
+                "damage Bob 99999999999999",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(CombatCommand::parse_input(
+                "condition Bob hungry",
+                &app_meta
+            )),
+        );
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let mut app_meta = app_meta();
+
+        assert_autocomplete(
+            &[("fight [name]", "add a combatant to the fight")][..],
+            block_on(CombatCommand::autocomplete("f", &app_meta)),
+        );
+
+        assert_eq!(
+            Vec::<AutocompleteSuggestion>::new(),
+            block_on(CombatCommand::autocomplete("next", &app_meta)),
+        );
+
+        block_on(
+            CombatCommand::Add {
+                name: "Goblin".to_string(),
+                initiative: Initiative::Fixed(10),
+            }
+            .run("", &mut app_meta),
+        )
+        .unwrap();
+
+        assert_autocomplete(
+            &[("next", "advance to the next turn")][..],
+            block_on(CombatCommand::autocomplete("NE", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[("damage Goblin [amount]", "damage Goblin")][..],
+            block_on(CombatCommand::autocomplete("damage g", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            CombatCommand::Add {
+                name: "Goblin Archer".to_string(),
+                initiative: Initiative::Fixed(17),
+            },
+            CombatCommand::Add {
+                name: "Goblin Archer".to_string(),
+                initiative: Initiative::Roll("d20+2".to_string()),
+            },
+            CombatCommand::Add {
+                name: "Goblin Archer".to_string(),
+                initiative: Initiative::Default,
+            },
+            CombatCommand::Condition {
+                name: "Bob".to_string(),
+                condition: Condition::Prone,
+            },
+            CombatCommand::Damage {
+                name: "Bob".to_string(),
+                amount: 5,
+            },
+            CombatCommand::End,
+            CombatCommand::Heal {
+                name: "Bob".to_string(),
+                amount: 5,
+            },
+            CombatCommand::Next,
+            CombatCommand::SetHp {
+                name: "Bob".to_string(),
+                hp: 12,
+            },
+            CombatCommand::Show,
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+            assert_ne!("", command_string);
+
+            assert_eq!(
+                CommandMatches::new_canonical(command.clone()),
+                block_on(CombatCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(MemoryDataStore::default(), &event_dispatcher)
+    }
+}
@@ -0,0 +1,309 @@
+pub use command::CombatCommand;
+
+mod command;
+
+use crate::utils::CaseInsensitiveStr;
+use crate::Uuid;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The state of a fight in progress, persisted to the key-value store so that every change to it
+/// can be undone like any other.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Combat {
+    pub round: u32,
+    pub turn: usize,
+    pub combatants: Vec<Combatant>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Combatant {
+    pub name: String,
+
+    /// Set if the combatant is a character from the journal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+
+    pub initiative: i32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hp: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_hp: Option<i32>,
+
+    /// The names of any SRD conditions currently affecting the combatant.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<String>,
+}
+
+pub struct CombatantSummaryView<'a>(&'a Combatant);
+
+impl Combat {
+    /// Add a combatant to the initiative order. Ties go to whoever joined the fight first. Once the
+    /// first turn has ended, adding a combatant doesn't change whose turn it is.
+    pub fn add(&mut self, combatant: Combatant) {
+        let index = self
+            .combatants
+            .iter()
+            .position(|c| c.initiative < combatant.initiative)
+            .unwrap_or(self.combatants.len());
+
+        if (self.round > 1 || self.turn > 0) && index <= self.turn {
+            self.turn += 1;
+        }
+
+        self.combatants.insert(index, combatant);
+    }
+
+    /// Advance to the next combatant's turn, returning `true` if doing so begins a new round.
+    pub fn next_turn(&mut self) -> bool {
+        if self.combatants.is_empty() {
+            return false;
+        }
+
+        self.turn += 1;
+
+        if self.turn >= self.combatants.len() {
+            self.turn = 0;
+            self.round += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn current(&self) -> Option<&Combatant> {
+        self.combatants.get(self.turn)
+    }
+
+    /// Find a combatant by name. Exact matches are preferred, but an unambiguous prefix will do
+    /// in a pinch, since typing "Goblin Archer" repeatedly mid-fight gets old quickly.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Combatant> {
+        let index = self
+            .combatants
+            .iter()
+            .position(|c| c.name.eq_ci(name))
+            .or_else(|| {
+                let mut matches = self
+                    .combatants
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.name.starts_with_ci(name));
+
+                match (matches.next(), matches.next()) {
+                    (Some((i, _)), None) => Some(i),
+                    _ => None,
+                }
+            })?;
+
+        self.combatants.get_mut(index)
+    }
+}
+
+impl Default for Combat {
+    fn default() -> Self {
+        Self {
+            round: 1,
+            turn: 0,
+            combatants: Vec::new(),
+        }
+    }
+}
+
+impl Combatant {
+    pub fn new(name: String, uuid: Option<Uuid>, initiative: i32) -> Self {
+        Self {
+            name,
+            uuid,
+            initiative,
+            hp: None,
+            max_hp: None,
+            conditions: Vec::new(),
+        }
+    }
+
+    pub fn display_summary(&self) -> CombatantSummaryView {
+        CombatantSummaryView(self)
+    }
+}
+
+impl fmt::Display for Combat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "# Initiative: round {}", self.round)?;
+
+        if self.combatants.is_empty() {
+            write!(
+                f,
+                "\n\n_No one has joined the fight yet. Use `fight [name]` to add a combatant._",
+            )
+        } else {
+            writeln!(f)?;
+
+            self.combatants
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, combatant)| {
+                    if i == self.turn {
+                        write!(
+                            f,
+                            "\n* **{}:** **{}** _(current turn)_",
+                            combatant.initiative,
+                            combatant.display_summary(),
+                        )
+                    } else {
+                        write!(
+                            f,
+                            "\n* **{}:** {}",
+                            combatant.initiative,
+                            combatant.display_summary(),
+                        )
+                    }
+                })
+        }
+    }
+}
+
+impl<'a> fmt::Display for CombatantSummaryView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let combatant = self.0;
+        write!(f, "{}", combatant.name)?;
+
+        let mut details = Vec::new();
+
+        match (combatant.hp, combatant.max_hp) {
+            (Some(hp), _) if hp <= 0 => details.push("down".to_string()),
+            (Some(hp), Some(max_hp)) => details.push(format!("{}/{} HP", hp, max_hp)),
+            (Some(hp), None) => details.push(format!("{} HP", hp)),
+            (None, _) => {}
+        }
+
+        combatant
+            .conditions
+            .iter()
+            .for_each(|condition| details.push(condition.to_lowercase()));
+
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_test() {
+        let mut combat = Combat::default();
+        combat.add(c("Bob", 10));
+        combat.add(c("Aria", 15));
+        combat.add(c("Goblin", 10));
+        combat.add(c("Ogre", 3));
+
+        assert_eq!(
+            vec!["Aria", "Bob", "Goblin", "Ogre"],
+            combat
+                .combatants
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!("Aria", combat.current().unwrap().name);
+    }
+
+    #[test]
+    fn add_test_preserves_turn() {
+        let mut combat = Combat::default();
+        combat.add(c("Aria", 15));
+        combat.add(c("Bob", 10));
+        combat.next_turn();
+        assert_eq!("Bob", combat.current().unwrap().name);
+
+        combat.add(c("Goblin", 20));
+        assert_eq!("Bob", combat.current().unwrap().name);
+
+        combat.add(c("Ogre", 3));
+        assert_eq!("Bob", combat.current().unwrap().name);
+    }
+
+    #[test]
+    fn next_turn_test() {
+        let mut combat = Combat::default();
+        assert!(!combat.next_turn());
+
+        combat.add(c("Aria", 15));
+        combat.add(c("Bob", 10));
+
+        assert!(!combat.next_turn());
+        assert_eq!(1, combat.round);
+        assert_eq!("Bob", combat.current().unwrap().name);
+
+        assert!(combat.next_turn());
+        assert_eq!(2, combat.round);
+        assert_eq!("Aria", combat.current().unwrap().name);
+    }
+
+    #[test]
+    fn get_mut_test() {
+        let mut combat = Combat::default();
+        combat.add(c("Goblin Archer", 15));
+        combat.add(c("Goblin", 12));
+        combat.add(c("Bob", 10));
+
+        assert_eq!("Goblin", combat.get_mut("GOBLIN").unwrap().name);
+        assert_eq!("Goblin Archer", combat.get_mut("goblin a").unwrap().name);
+        assert_eq!("Bob", combat.get_mut("b").unwrap().name);
+        assert!(combat.get_mut("gob").is_none());
+        assert!(combat.get_mut("Aria").is_none());
+    }
+
+    #[test]
+    fn display_test() {
+        let mut combat = Combat::default();
+        assert_eq!(
+            "# Initiative: round 1\n\n_No one has joined the fight yet. Use `fight [name]` to add a combatant._",
+            combat.to_string(),
+        );
+
+        combat.add(c("Aria", 15));
+        combat.add(Combatant {
+            hp: Some(5),
+            max_hp: Some(12),
+            conditions: vec!["Prone".to_string(), "Stunned".to_string()],
+            ..c("Bob", 10)
+        });
+        combat.add(Combatant {
+            hp: Some(0),
+            ..c("Goblin", 3)
+        });
+
+        assert_eq!(
+            "# Initiative: round 1\n\n* **15:** **Aria** _(current turn)_\n* **10:** Bob (5/12 HP, prone, stunned)\n* **3:** Goblin (down)",
+            combat.to_string(),
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_test() {
+        let mut combat = Combat::default();
+        combat.add(Combatant {
+            uuid: Some(Uuid::from_u128(1)),
+            hp: Some(7),
+            ..c("Bob", 10)
+        });
+
+        let json = serde_json::to_string(&combat).unwrap();
+        assert_eq!(
+            r#"{"round":1,"turn":0,"combatants":[{"name":"Bob","uuid":"00000000-0000-0000-0000-000000000001","initiative":10,"hp":7}]}"#,
+            json,
+        );
+        assert_eq!(combat, serde_json::from_str(&json).unwrap());
+    }
+
+    fn c(name: &str, initiative: i32) -> Combatant {
+        Combatant::new(name.to_string(), None, initiative)
+    }
+}
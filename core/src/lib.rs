@@ -8,16 +8,23 @@
 
 pub mod app;
 
-pub use app::{App, Event};
+pub use app::{App, Event, ExportData};
 pub use storage::backup::BackupData;
+pub use storage::sync::{compact_changes, MemorySyncServer, SyncChange, SyncTransport};
+#[cfg(feature = "test-utils")]
+pub use storage::test_data_store;
 pub use storage::{DataStore, MemoryDataStore, NullDataStore};
 pub use uuid::Uuid;
-pub use world::Thing;
+pub use world::{Thing, ThingQuery};
 
+mod combat;
+mod log;
 mod reference;
 mod storage;
 mod time;
+mod travel;
 mod utils;
+mod weather;
 mod world;
 
 /// Creates a new instance of the application wrapper. The `data_store` is used to save and load
@@ -0,0 +1,245 @@
+use super::{to_markdown, LogEntry};
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Event,
+    Runnable,
+};
+use crate::storage::KeyValue;
+use crate::utils::CaseInsensitiveStr;
+use async_trait::async_trait;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LogCommand {
+    /// Show the log for the current session.
+    Current,
+    Session {
+        session: u32,
+    },
+    Day {
+        day: i32,
+    },
+    Export,
+}
+
+#[async_trait(?Send)]
+impl Runnable for LogCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        let log: Vec<LogEntry> = app_meta
+            .repository
+            .get_key_value(&KeyValue::Log(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .log()
+            .unwrap_or_default();
+        let calendar = app_meta
+            .repository
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.calendar());
+
+        let entries: Vec<LogEntry> = match self {
+            Self::Current => {
+                let session = app_meta.session;
+                let entries: Vec<_> = log
+                    .into_iter()
+                    .filter(|entry| entry.session == session)
+                    .collect();
+
+                if entries.is_empty() {
+                    return Ok(format!(
+                        "# Session {}\n\n*Nothing has happened yet this session.*",
+                        session,
+                    ));
+                }
+
+                entries
+            }
+            Self::Session { session } => {
+                let entries: Vec<_> = log
+                    .into_iter()
+                    .filter(|entry| entry.session == session)
+                    .collect();
+
+                if entries.is_empty() {
+                    return Err(format!(
+                        "There is nothing in the log for session {}.",
+                        session
+                    ));
+                }
+
+                entries
+            }
+            Self::Day { day } => {
+                let entries: Vec<_> = log
+                    .into_iter()
+                    .filter(|entry| entry.time.days() == day)
+                    .collect();
+
+                if entries.is_empty() {
+                    return Err(format!("There is nothing in the log for day {}.", day));
+                }
+
+                entries
+            }
+            Self::Export => {
+                if log.is_empty() {
+                    return Ok(
+                        "The session log is empty, so there's nothing to export yet.".to_string(),
+                    );
+                }
+
+                (app_meta.event_dispatcher)(Event::ExportLog(to_markdown(&log, calendar.as_ref())));
+                return Ok(
+                    "The session log is exporting. Your download should begin shortly.".to_string(),
+                );
+            }
+        };
+
+        let mut output = to_markdown(&entries, calendar.as_ref());
+        output.push_str("\n\n*To download the full log as a campaign recap, use `log export`.*");
+        Ok(output)
+    }
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for LogCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        if input.eq_ci("log") {
+            CommandMatches::new_canonical(Self::Current)
+        } else if input.eq_ci("log export") {
+            CommandMatches::new_canonical(Self::Export)
+        } else if let Some(session) = input
+            .strip_prefix_ci("log session ")
+            .and_then(|s| s.trim().parse().ok())
+        {
+            CommandMatches::new_canonical(Self::Session { session })
+        } else if let Some(day) = input
+            .strip_prefix_ci("log day ")
+            .and_then(|s| s.trim().parse().ok())
+        {
+            CommandMatches::new_canonical(Self::Day { day })
+        } else {
+            CommandMatches::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for LogCommand {
+    async fn autocomplete(input: &str, _app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if input.is_empty() {
+            Vec::new()
+        } else {
+            [
+                ("log", "this session's log"),
+                ("log day [number]", "log for a game day"),
+                ("log export", "download the session log"),
+                ("log session [number]", "log for a past session"),
+            ]
+            .into_iter()
+            .filter(|(term, _)| term.starts_with_ci(input))
+            .map(|(term, summary)| AutocompleteSuggestion::new(term, summary))
+            .collect()
+        }
+    }
+}
+
+impl fmt::Display for LogCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Current => write!(f, "log"),
+            Self::Session { session } => write!(f, "log session {}", session),
+            Self::Day { day } => write!(f, "log day {}", day),
+            Self::Export => write!(f, "log export"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::NullDataStore;
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(LogCommand::Current),
+            block_on(LogCommand::parse_input("LOG", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(LogCommand::Session { session: 3 }),
+            block_on(LogCommand::parse_input("log session 3", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(LogCommand::Day { day: -4 }),
+            block_on(LogCommand::parse_input("log day -4", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(LogCommand::parse_input("log session three", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(LogCommand::parse_input("logs", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let app_meta = app_meta();
+
+        assert_autocomplete(
+            &[
+                ("log", "this session's log"),
+                ("log day [number]", "log for a game day"),
+                ("log export", "download the session log"),
+                ("log session [number]", "log for a past session"),
+            ][..],
+            block_on(LogCommand::autocomplete("lo", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[("log export", "download the session log")][..],
+            block_on(LogCommand::autocomplete("log e", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            LogCommand::Current,
+            LogCommand::Session { session: 2 },
+            LogCommand::Day { day: 14 },
+            LogCommand::Export,
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+            assert_ne!("", command_string);
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(LogCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(NullDataStore::default(), &event_dispatcher)
+    }
+}
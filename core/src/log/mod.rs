@@ -0,0 +1,184 @@
+pub use command::LogCommand;
+
+mod command;
+
+use crate::app::AppMeta;
+use crate::storage::{Change, KeyValue};
+use crate::time::{Calendar, Time};
+use serde::{Deserialize, Serialize};
+
+/// The log is rewritten in its entirety every time a command is run, so it needs an upper bound.
+/// The oldest entries are dropped first.
+const LOG_MAX_LEN: usize = 1000;
+
+/// Summaries longer than this are truncated with an ellipsis.
+const SUMMARY_MAX_LEN: usize = 100;
+
+/// A single command run at the table, as recorded in the session log.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LogEntry {
+    /// The session during which the command was run, counting from 1. A new session begins every
+    /// time the app is started.
+    pub session: u32,
+
+    pub command: String,
+
+    /// The first sentence of the command's output.
+    pub summary: String,
+
+    /// The game time at the moment the command was run.
+    pub time: Time,
+}
+
+/// Get the number of the session that should be used for the next time the app is started.
+pub async fn next_session(app_meta: &AppMeta) -> u32 {
+    app_meta
+        .repository
+        .get_key_value(&KeyValue::Log(None))
+        .await
+        .ok()
+        .and_then(|kv| kv.log())
+        .and_then(|log| log.last().map(|entry| entry.session + 1))
+        .unwrap_or(1)
+}
+
+/// Record a command and its output in the log. Logging is best-effort: failures are ignored, and
+/// the log isn't part of the undo history.
+pub async fn record(app_meta: &mut AppMeta, command: &str, output: &str, time: Time) {
+    let mut log = app_meta
+        .repository
+        .get_key_value(&KeyValue::Log(None))
+        .await
+        .ok()
+        .and_then(|kv| kv.log())
+        .unwrap_or_default();
+
+    if log.len() >= LOG_MAX_LEN {
+        log.drain(..=log.len() - LOG_MAX_LEN);
+    }
+
+    log.push(LogEntry {
+        session: app_meta.session,
+        command: command.to_string(),
+        summary: summarize(output),
+        time,
+    });
+
+    app_meta
+        .repository
+        .modify_without_undo(Change::SetKeyValue {
+            key_value: KeyValue::Log(Some(log)),
+        })
+        .await
+        .ok();
+}
+
+/// Reduce a command's output to its first sentence, eg. "# Journal\n\n..." becomes "Journal" and
+/// "It is now day 1 at 9:00:00 am. Use `undo` to reverse." becomes "It is now day 1 at 9:00:00 am."
+pub fn summarize(output: &str) -> String {
+    let line = output
+        .lines()
+        .map(|line| line.trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    let sentence = line
+        .match_indices(['.', '!', '?'])
+        .map(|(i, _)| i + 1)
+        .find(|&i| line[i..].starts_with(' '))
+        .map_or(line, |i| &line[..i]);
+
+    if sentence.chars().count() > SUMMARY_MAX_LEN {
+        let mut summary: String = sentence.chars().take(SUMMARY_MAX_LEN - 1).collect();
+
+        // Don't leave a dangling code span.
+        if summary.matches('`').count() % 2 == 1 {
+            summary = summary.replace('`', "");
+        }
+
+        summary.push('…');
+        summary
+    } else {
+        sentence.to_string()
+    }
+}
+
+/// Render log entries as markdown, with a heading for each session.
+pub fn to_markdown(entries: &[LogEntry], calendar: Option<&Calendar>) -> String {
+    let mut output = String::new();
+    let mut session = None;
+
+    for entry in entries {
+        if session != Some(entry.session) {
+            if session.is_some() {
+                output.push_str("\n\n");
+            }
+            output.push_str(&format!("# Session {}\n", entry.session));
+            session = Some(entry.session);
+        }
+
+        output.push_str(&format!(
+            "\n* **{}:** `{}`",
+            entry.time.display_long(calendar),
+            entry.command,
+        ));
+
+        if !entry.summary.is_empty() {
+            output.push_str(&format!(" — {}", entry.summary));
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn summarize_test() {
+        assert_eq!("Journal", summarize("# Journal\n\n## NPCs\n\nBob"));
+        assert_eq!(
+            "It is now day 1 at 9:00:00 am.",
+            summarize("\n\nIt is now day 1 at 9:00:00 am.\n\nMore"),
+        );
+        assert_eq!(
+            "Created `Foo`!",
+            summarize("Created `Foo`! Use `undo` to reverse."),
+        );
+        assert_eq!("", summarize(""));
+
+        let summary = summarize(&format!("{} `undo` to reverse.", "a".repeat(95)));
+        assert!(summary.ends_with("a un…"), "{}", summary);
+
+        let summary = summarize(&"a".repeat(200));
+        assert_eq!(SUMMARY_MAX_LEN, summary.chars().count());
+        assert!(summary.ends_with('…'));
+    }
+
+    #[test]
+    fn to_markdown_test() {
+        assert_eq!(
+            "# Session 1\n\n* **day 1 at 8:00:00 am:** `npc` — Elvish Tera\n* **day 1 at 9:00:00 am:** `+1h` — It is now day 1 at 9:00:00 am.\n\n# Session 2\n\n* **day 2 at 8:00:00 am:** `about`",
+            to_markdown(
+                &[
+                    entry(1, "npc", "Elvish Tera", 1, 8),
+                    entry(1, "+1h", "It is now day 1 at 9:00:00 am.", 1, 9),
+                    entry(2, "about", "", 2, 8),
+                ],
+                None,
+            ),
+        );
+
+        assert_eq!("", to_markdown(&[], None));
+    }
+
+    fn entry(session: u32, command: &str, summary: &str, days: i32, hours: u8) -> LogEntry {
+        LogEntry {
+            session,
+            command: command.to_string(),
+            summary: summary.to_string(),
+            time: Time::try_new(days, hours, 0, 0).unwrap(),
+        }
+    }
+}
@@ -12,6 +12,8 @@ reference_enum!(ItemCategory);
 
 reference_enum!(MagicItem);
 
+reference_enum!(Monster);
+
 reference_enum!(Spell);
 
 reference_enum!(Trait);
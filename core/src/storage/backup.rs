@@ -1,23 +1,99 @@
 use super::repository::{Change, Error as RepositoryError, KeyValue, Repository};
-use crate::world::Thing;
+use crate::time::{Calendar, Time};
+use crate::world::npc::Size;
+use crate::world::{Thing, ThingQuery};
+use crate::Uuid;
 use futures::join;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
+/// The version of the backup format written by `export`, as described by
+/// `data/backup.schema.json`. Any change to the format that older versions of the app couldn't
+/// import as-is needs a new version, with a corresponding step added to `MIGRATIONS`.
+pub const BACKUP_VERSION: u64 = 3;
+
+/// The steps needed to bring a backup up to date. The first entry upgrades version 1 to version
+/// 2, the second upgrades version 2 to version 3, and so on.
+const MIGRATIONS: [fn(&mut Map<String, Value>); (BACKUP_VERSION - 1) as usize] =
+    [migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// A backup of the user's journal. Backups in older formats are upgraded to the current version
+/// when deserialized, so a `BackupData` always has a `version` of [`BACKUP_VERSION`].
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(try_from = "Value")]
 pub struct BackupData {
     #[serde(rename(serialize = "_"), skip_deserializing)]
     pub comment: &'static str,
 
+    pub version: u64,
+
     pub things: Vec<Thing>,
 
     #[serde(rename = "keyValue")]
     pub key_value: KeyValueBackup,
 }
 
+/// The fields of a backup that has already been upgraded to the current version.
+#[derive(Deserialize)]
+struct CurrentBackupData {
+    things: Vec<Thing>,
+
+    #[serde(rename = "keyValue")]
+    key_value: KeyValueBackup,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct KeyValueBackup {
+    /// The current time, formatted as a date if there is a calendar (eg. "3 Hammer 1491 DR at
+    /// 8:00:00 am"), or as a day count otherwise (eg. "3:08:00:00").
     pub time: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<Calendar>,
+}
+
+/// Part of the journal to export, rather than the whole thing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExportSelection {
+    /// A single entry, eg. `export Bob`.
+    Name(String),
+
+    /// A place and everything in it, including the contents of the places within it, eg.
+    /// `export Waterdeep and contents`.
+    WithContents(String),
+
+    /// Every entry with a tag, eg. `export #villain`.
+    Tag(String),
+}
+
+/// How to handle entries in a backup that already exist in the journal, either by name or by
+/// UUID.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ImportStrategy {
+    /// Overwrite the existing entry with the one from the backup.
+    #[default]
+    TakeIncoming,
+
+    /// Leave the existing entry as it is and skip the one from the backup.
+    KeepLocal,
+
+    /// Import the entry from the backup under a new name, eg. "Bob (2)".
+    RenameIncoming,
+
+    /// Fill in whatever the existing entry is missing or had generated for it, but keep anything
+    /// that was chosen by the user.
+    Merge,
+}
+
+/// The options given to the `import` command, which take effect once the user has chosen a file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ImportOptions {
+    /// Describe what the import would do without changing anything.
+    pub preview: bool,
+
+    pub strategy: ImportStrategy,
 }
 
 #[derive(Default)]
@@ -25,80 +101,621 @@ pub struct ImportStats {
     npc_stats: ImportStat,
     place_stats: ImportStat,
     key_value_stats: ImportStat,
+
+    /// Why individual entries couldn't be imported, eg. "Row 3: unknown species \"orc\"."
+    errors: Vec<String>,
 }
 
 #[derive(Default)]
 struct ImportStat {
     created: usize,
     updated: usize,
+    skipped: usize,
     failed: usize,
 }
 
+/// The result of `import --preview`: what importing the backup would do, entry by entry.
+pub struct ImportPreview {
+    stats: ImportStats,
+    descriptions: Vec<String>,
+    strategy: ImportStrategy,
+}
+
+/// An entry in a backup and what importing it will do.
+struct PlannedEntry {
+    kind: EntryKind,
+    outcome: Outcome,
+    change: Option<Change>,
+    description: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum EntryKind {
+    Npc,
+    Place,
+    KeyValue,
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum Outcome {
+    Created,
+    Updated,
+    Skipped,
+    Failed,
+}
+
+const BACKUP_COMMENT: &str = "This document is exported from initiative.sh. Its format is described by data/backup.schema.json in the initiative.sh source code, and backups made by older versions of initiative.sh are upgraded when imported.";
+
 pub async fn export(repo: &Repository) -> BackupData {
-    let (things, time) = join!(repo.journal(), repo.get_key_value(&KeyValue::Time(None)));
+    let (things, time, calendar) = join!(
+        repo.journal(),
+        repo.get_key_value(&KeyValue::Time(None)),
+        repo.get_key_value(&KeyValue::Calendar(None)),
+    );
+    let calendar = calendar.ok().and_then(|c| c.calendar());
 
     BackupData {
-        comment: "This document is exported from initiative.sh. Please note that this format is currently undocumented and no guarantees of forward compatibility are provided, although a reasonable effort will be made to ensure that older backups can be safely imported.",
+        comment: BACKUP_COMMENT,
+        version: BACKUP_VERSION,
         things: things.unwrap_or_default(),
         key_value: KeyValueBackup {
-            time: time.ok().and_then(|t| t.time()).map(|t| t.display_short().to_string()),
+            time: time.ok().and_then(|t| t.time()).map(|t| {
+                if calendar.is_some() {
+                    t.display_long(calendar.as_ref()).to_string()
+                } else {
+                    t.display_short().to_string()
+                }
+            }),
+            calendar,
         },
     }
 }
 
+/// Export part of the journal, along with the places containing each selected entry so that
+/// their locations survive being imported into another journal. The time and calendar belong to
+/// the journal as a whole, so they're left out.
+pub async fn export_selection(
+    repo: &Repository,
+    selection: &ExportSelection,
+) -> Result<BackupData, RepositoryError> {
+    let mut things = match selection {
+        ExportSelection::Name(name) | ExportSelection::WithContents(name) => {
+            let thing = repo.get_by_name(name).await?;
+            if thing.uuid().is_none() {
+                return Err(RepositoryError::NotFound);
+            }
+            vec![thing]
+        }
+        ExportSelection::Tag(tag) => {
+            repo.query_journal(&ThingQuery {
+                tags: vec![tag.clone()],
+                ..Default::default()
+            })
+            .await?
+        }
+    };
+
+    let mut seen: HashSet<Uuid> = things.iter().filter_map(|t| t.uuid().cloned()).collect();
+
+    if let (ExportSelection::WithContents(_), Some(thing)) = (selection, things.first()) {
+        let contents = repo.get_contents_recursive(thing).await?;
+        seen.extend(contents.iter().filter_map(|t| t.uuid().cloned()));
+        things.extend(contents);
+    }
+
+    let mut ancestors = Vec::new();
+    for thing in &things {
+        let mut location_uuid = thing.location_uuid().map(|uuid| *uuid.as_ref());
+
+        while let Some(uuid) = location_uuid {
+            if !seen.insert(uuid) {
+                break;
+            }
+
+            match repo.get_by_uuid(&uuid).await {
+                Ok(place) => {
+                    location_uuid = place.location_uuid().map(|uuid| *uuid.as_ref());
+                    ancestors.push(place);
+                }
+                Err(RepositoryError::NotFound) => break,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Put the outermost places first, so that a reader sees Waterdeep before the inns in it.
+    ancestors.reverse();
+    ancestors.append(&mut things);
+
+    Ok(BackupData {
+        comment: BACKUP_COMMENT,
+        version: BACKUP_VERSION,
+        things: ancestors,
+        key_value: KeyValueBackup {
+            time: None,
+            calendar: None,
+        },
+    })
+}
+
+/// Import a backup, handling entries that already exist according to `strategy`. Everything
+/// imported is undone together by a single `undo`.
 pub async fn import(
     repo: &mut Repository,
-    mut data: BackupData,
-) -> Result<ImportStats, RepositoryError> {
+    data: BackupData,
+    strategy: ImportStrategy,
+) -> ImportStats {
     let mut stats = ImportStats::default();
+    let mut changes = Vec::new();
+    let mut pending = Vec::new();
+
+    for entry in plan(repo, data, strategy).await {
+        if let Some(change) = entry.change {
+            changes.push(change);
+            pending.push((entry.kind, entry.outcome));
+        } else {
+            stats.record(entry.kind, entry.outcome);
+        }
+    }
 
-    for thing in data.things.into_iter() {
-        match (
-            match thing {
-                Thing::Npc(_) => &mut stats.npc_stats,
-                Thing::Place(_) => &mut stats.place_stats,
+    for ((kind, outcome), result) in pending.into_iter().zip(repo.modify_each(changes).await) {
+        stats.record(
+            kind,
+            if result.is_ok() {
+                outcome
+            } else {
+                Outcome::Failed
             },
-            repo.modify_without_undo(Change::CreateAndSave { thing })
-                .await,
-        ) {
-            (stat, Ok(_)) => stat.created += 1,
-            (stat, Err((Change::CreateAndSave { thing }, RepositoryError::NameAlreadyExists))) => {
-                let name = thing.name().to_string();
-                match repo
-                    .modify_without_undo(Change::Edit {
-                        name,
-                        uuid: None,
-                        diff: thing,
-                    })
-                    .await
-                {
-                    Ok(_) => stat.updated += 1,
-                    Err(_) => stat.failed += 1,
+        );
+    }
+
+    stats
+}
+
+/// Describe what `import` would do with a backup, without changing anything.
+pub async fn preview(
+    repo: &Repository,
+    data: BackupData,
+    strategy: ImportStrategy,
+) -> ImportPreview {
+    let mut stats = ImportStats::default();
+    let mut descriptions = Vec::new();
+
+    for entry in plan(repo, data, strategy).await {
+        stats.record(entry.kind, entry.outcome);
+        descriptions.push(entry.description);
+    }
+
+    ImportPreview {
+        stats,
+        descriptions,
+        strategy,
+    }
+}
+
+async fn plan(repo: &Repository, data: BackupData, strategy: ImportStrategy) -> Vec<PlannedEntry> {
+    // Locations are stored by UUID, which isn't very helpful when describing what changed.
+    let place_names: HashMap<String, String> = data
+        .things
+        .iter()
+        .chain(repo.journal().await.unwrap_or_default().iter())
+        .filter_map(|thing| {
+            thing.place().and_then(|place| {
+                Some((
+                    place.uuid.as_ref()?.to_string(),
+                    place.name.value()?.clone(),
+                ))
+            })
+        })
+        .collect();
+
+    let mut entries = Vec::with_capacity(data.things.len() + 2);
+    let mut new_uuids: HashMap<Uuid, Uuid> = HashMap::new();
+
+    for thing in data.things {
+        let uuid = thing.uuid().copied();
+        let entry = plan_thing(repo, thing, strategy, &place_names).await;
+
+        if let (Some(uuid), Some(Change::CreateAndSave { thing })) = (uuid, &entry.change) {
+            if let Some(new_uuid) = thing.uuid().filter(|new_uuid| **new_uuid != uuid) {
+                new_uuids.insert(uuid, *new_uuid);
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    // A place that's given a new UUID on the way in takes its contents with it.
+    for entry in &mut entries {
+        if let Some(Change::CreateAndSave { thing }) = &mut entry.change {
+            if let Some(new_uuid) = thing
+                .location_uuid()
+                .and_then(|uuid| new_uuids.get(uuid.as_ref()))
+            {
+                thing.set_location_uuid((*new_uuid).into());
+            }
+        }
+    }
+
+    plan_key_values(repo, data.key_value, strategy, &mut entries).await;
+
+    entries
+}
+
+async fn plan_thing(
+    repo: &Repository,
+    mut thing: Thing,
+    strategy: ImportStrategy,
+    place_names: &HashMap<String, String>,
+) -> PlannedEntry {
+    let kind = match thing {
+        Thing::Npc(_) => EntryKind::Npc,
+        Thing::Place(_) => EntryKind::Place,
+    };
+    let name = thing.name().to_string();
+
+    let local = match thing.uuid() {
+        Some(uuid) => repo.get_by_uuid(uuid).await.ok(),
+        None => None,
+    };
+    let local = match local {
+        Some(local) => local,
+        None => match repo.get_by_name(&name).await {
+            Ok(local) => local,
+            Err(_) => {
+                return PlannedEntry {
+                    kind,
+                    outcome: Outcome::Created,
+                    description: format!("Create {}", name),
+                    change: Some(Change::CreateAndSave { thing }),
+                };
+            }
+        },
+    };
+
+    match strategy {
+        ImportStrategy::KeepLocal => PlannedEntry {
+            kind,
+            outcome: Outcome::Skipped,
+            change: None,
+            description: format!("Skip {}, keeping `{}` as it is", name, local.name()),
+        },
+        ImportStrategy::RenameIncoming => {
+            let mut new_name = String::new();
+            for i in 2.. {
+                new_name = format!("{} ({})", name, i);
+                if repo.get_by_name(&new_name).await.is_err() {
+                    break;
                 }
             }
-            (stat, Err(_)) => stat.failed += 1,
+
+            if thing.uuid() == local.uuid() {
+                thing.clear_uuid();
+                thing.set_uuid(Uuid::new_v4());
+            }
+            thing.set_name(new_name.clone());
+
+            PlannedEntry {
+                kind,
+                outcome: Outcome::Created,
+                description: format!(
+                    "Create {}, since `{}` already exists",
+                    new_name,
+                    local.name()
+                ),
+                change: Some(Change::CreateAndSave { thing }),
+            }
+        }
+        ImportStrategy::TakeIncoming | ImportStrategy::Merge => {
+            let mut diff = thing;
+            if strategy == ImportStrategy::Merge {
+                let mut kept_fields = chosen_fields(&local);
+                kept_fields.extend(empty_fields(&diff));
+                diff.unlock_fields(&kept_fields);
+            }
+
+            let mut merged = local.clone();
+            if merged.try_apply_diff(&mut diff.clone()).is_err() {
+                return PlannedEntry {
+                    kind,
+                    outcome: Outcome::Failed,
+                    change: None,
+                    description: format!(
+                        "Skip {}, since `{}` is a {} rather than a {}",
+                        name,
+                        local.name(),
+                        local.as_str(),
+                        diff.as_str(),
+                    ),
+                };
+            }
+
+            let differences = describe_differences(&local, &merged, place_names);
+
+            PlannedEntry {
+                kind,
+                outcome: Outcome::Updated,
+                description: if differences.is_empty() {
+                    format!("Update `{}` (no changes)", local.name())
+                } else {
+                    format!("Update `{}`: {}", local.name(), differences.join("; "))
+                },
+                change: Some(Change::Edit {
+                    name: local.name().to_string(),
+                    uuid: local.uuid().cloned(),
+                    diff,
+                }),
+            }
         }
     }
+}
 
-    if let Some(time) = data.key_value.time.take().and_then(|s| s.parse().ok()) {
-        match repo
-            .modify_without_undo(Change::SetKeyValue {
-                key_value: KeyValue::Time(Some(time)),
+async fn plan_key_values(
+    repo: &Repository,
+    key_value: KeyValueBackup,
+    strategy: ImportStrategy,
+    entries: &mut Vec<PlannedEntry>,
+) {
+    let (local_calendar, local_time) = join!(
+        repo.get_key_value(&KeyValue::Calendar(None)),
+        repo.get_key_value(&KeyValue::Time(None)),
+    );
+    let local_calendar = local_calendar.ok().and_then(|c| c.calendar());
+    let local_time = local_time.ok().and_then(|t| t.time());
+    let overwrite = strategy == ImportStrategy::TakeIncoming;
+
+    let mut calendar = local_calendar.clone();
+
+    if let Some(incoming) = key_value.calendar {
+        let (outcome, description) = match &local_calendar {
+            None => (
+                Outcome::Created,
+                format!("Set the calendar to {}", incoming.name),
+            ),
+            Some(local) if overwrite => (
+                Outcome::Updated,
+                format!(
+                    "Change the calendar from {} to {}",
+                    local.name, incoming.name,
+                ),
+            ),
+            Some(local) => (
+                Outcome::Skipped,
+                format!("Skip the calendar, keeping {}", local.name),
+            ),
+        };
+
+        let change = if matches!(outcome, Outcome::Skipped) {
+            None
+        } else {
+            calendar = Some(incoming.clone());
+            Some(Change::SetKeyValue {
+                key_value: KeyValue::Calendar(Some(incoming)),
             })
-            .await
-        {
-            Ok(Change::SetKeyValue {
-                key_value: KeyValue::Time(None),
-            }) => stats.key_value_stats.created += 1,
-            Ok(Change::SetKeyValue {
-                key_value: KeyValue::Time(Some(_)),
-            }) => stats.key_value_stats.updated += 1,
-            Ok(_) => unreachable!(),
-            Err(_) => stats.key_value_stats.failed += 1,
-        }
+        };
+
+        entries.push(PlannedEntry {
+            kind: EntryKind::KeyValue,
+            outcome,
+            change,
+            description,
+        });
+    }
+
+    // Dates can only be parsed once we know what calendar is in use.
+    if let Some(time) = key_value.time.and_then(|s| {
+        s.parse()
+            .ok()
+            .or_else(|| Time::parse_long(&s, calendar.as_ref()))
+    }) {
+        let display = |time: &Time| time.display_long(calendar.as_ref()).to_string();
+
+        let (outcome, description) = match &local_time {
+            None => (
+                Outcome::Created,
+                format!("Set the time to {}", display(&time)),
+            ),
+            Some(local) if overwrite => (
+                Outcome::Updated,
+                format!(
+                    "Change the time from {} to {}",
+                    display(local),
+                    display(&time),
+                ),
+            ),
+            Some(local) => (
+                Outcome::Skipped,
+                format!("Skip the time, keeping {}", display(local)),
+            ),
+        };
+
+        entries.push(PlannedEntry {
+            kind: EntryKind::KeyValue,
+            change: if matches!(outcome, Outcome::Skipped) {
+                None
+            } else {
+                Some(Change::SetKeyValue {
+                    key_value: KeyValue::Time(Some(time)),
+                })
+            },
+            outcome,
+            description,
+        });
+    }
+}
+
+/// The fields of `thing` that have a value chosen by the user, as opposed to being empty or
+/// randomly generated. Things loaded from the data store have all of their fields locked, so
+/// only empty fields can be filled in for them.
+fn chosen_fields(thing: &Thing) -> Vec<String> {
+    let unlocked = thing.unlocked_fields();
+
+    match serde_json::to_value(thing) {
+        Ok(Value::Object(fields)) => fields
+            .into_iter()
+            .filter(|(field, value)| !value.is_null() && !unlocked.contains(&field.as_str()))
+            .map(|(field, _)| field)
+            .collect(),
+        _ => Vec::new(),
     }
+}
 
-    Ok(stats)
+/// The fields of `thing` that have no value, which shouldn't blank out an existing value when
+/// merging.
+fn empty_fields(thing: &Thing) -> Vec<String> {
+    match serde_json::to_value(thing) {
+        Ok(Value::Object(fields)) => fields
+            .into_iter()
+            .filter(|(_, value)| value.is_null())
+            .map(|(field, _)| field)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Describe each field that differs between two versions of a thing, eg. "gender: masculine →
+/// feminine".
+fn describe_differences(
+    before: &Thing,
+    after: &Thing,
+    place_names: &HashMap<String, String>,
+) -> Vec<String> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    after
+        .iter()
+        .filter(|(field, _)| !["type", "uuid"].contains(&field.as_str()))
+        .filter_map(|(field, new_value)| {
+            let old_value = before.get(field).unwrap_or(&Value::Null);
+
+            if old_value == new_value {
+                None
+            } else {
+                Some(format!(
+                    "{}: {} → {}",
+                    field.replace('_', " "),
+                    describe_value(field, old_value, place_names),
+                    describe_value(field, new_value, place_names),
+                ))
+            }
+        })
+        .collect()
+}
+
+fn describe_value(field: &str, value: &Value, place_names: &HashMap<String, String>) -> String {
+    match (field, value) {
+        (_, Value::Null) => "none".to_string(),
+        ("location_uuid", Value::String(uuid)) => place_names
+            .get(uuid)
+            .cloned()
+            .unwrap_or_else(|| uuid.clone()),
+        ("size", size) => serde_json::from_value::<Size>(size.clone())
+            .map(|size| size.to_string())
+            .unwrap_or_else(|_| size.to_string()),
+        ("notes", Value::Array(notes)) => format!(
+            "{} note{}",
+            notes.len(),
+            if notes.len() == 1 { "" } else { "s" },
+        ),
+        (_, Value::Array(values)) if values.is_empty() => "none".to_string(),
+        (_, Value::Array(values)) => values
+            .iter()
+            .map(|value| describe_value(field, value, place_names))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Value::String(s)) => s.clone(),
+        (_, value) => value.to_string(),
+    }
+}
+
+/// Upgrade a backup from whatever version it was written in to [`BACKUP_VERSION`], one version
+/// at a time.
+fn migrate(mut value: Value) -> Result<Value, String> {
+    let data = value
+        .as_object_mut()
+        .ok_or_else(|| "expected a JSON object".to_string())?;
+
+    let version = match data.get("version") {
+        Some(version) => version
+            .as_u64()
+            .filter(|&version| version > 0)
+            .ok_or_else(|| format!("invalid backup version {}", version))?,
+        None => legacy_version(data),
+    };
+
+    if version > BACKUP_VERSION {
+        return Err(format!(
+            "this backup is from a newer version of initiative.sh (format version {}, but only versions up to {} are supported)",
+            version, BACKUP_VERSION,
+        ));
+    }
+
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(data);
+    }
+
+    data.insert("version".to_string(), BACKUP_VERSION.into());
+
+    Ok(value)
+}
+
+/// Backups before version 3 don't say what version they are. Version 1 can be recognized by
+/// places having a `parent_uuid` rather than a `location_uuid`.
+fn legacy_version(data: &Map<String, Value>) -> u64 {
+    if things(data).any(|thing| thing.contains_key("parent_uuid")) {
+        1
+    } else {
+        2
+    }
+}
+
+/// Version 2 renamed the `parent_uuid` of places to `location_uuid`, and added `location_uuid` to
+/// characters.
+fn migrate_v1_to_v2(data: &mut Map<String, Value>) {
+    for thing in things_mut(data) {
+        let location_uuid = thing.remove("parent_uuid").unwrap_or(Value::Null);
+        thing.entry("location_uuid").or_insert(location_uuid);
+    }
+}
+
+/// Version 3 added the `version` field itself, along with optional notes, tags, and calendar,
+/// none of which need any changes to older backups.
+fn migrate_v2_to_v3(_data: &mut Map<String, Value>) {}
+
+fn things(data: &Map<String, Value>) -> impl Iterator<Item = &Map<String, Value>> {
+    data.get("things")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+}
+
+fn things_mut(data: &mut Map<String, Value>) -> impl Iterator<Item = &mut Map<String, Value>> {
+    data.get_mut("things")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+impl TryFrom<Value> for BackupData {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        // Deserialize from a reference, since some fields can only be read from borrowed strings.
+        let data = CurrentBackupData::deserialize(&migrate(value)?).map_err(|e| e.to_string())?;
+
+        Ok(BackupData {
+            comment: "",
+            version: BACKUP_VERSION,
+            things: data.things,
+            key_value: data.key_value,
+        })
+    }
 }
 
 impl fmt::Display for ImportStats {
@@ -126,17 +743,105 @@ impl fmt::Display for ImportStats {
             first = false;
         }
 
-        if first {
+        if first && self.errors.is_empty() {
             write!(f, "Nothing to import.")?;
         }
 
+        if !self.errors.is_empty() {
+            if !first {
+                write!(f, "\n\n")?;
+            }
+            writeln!(f, "Some entries couldn't be imported:")?;
+
+            for error in &self.errors {
+                write!(f, "\n* {}", error)?;
+            }
+        }
+
         Ok(())
     }
 }
 
+impl ImportStats {
+    pub(super) fn record(&mut self, kind: EntryKind, outcome: Outcome) {
+        let stat = match kind {
+            EntryKind::Npc => &mut self.npc_stats,
+            EntryKind::Place => &mut self.place_stats,
+            EntryKind::KeyValue => &mut self.key_value_stats,
+        };
+
+        match outcome {
+            Outcome::Created => stat.created += 1,
+            Outcome::Updated => stat.updated += 1,
+            Outcome::Skipped => stat.skipped += 1,
+            Outcome::Failed => stat.failed += 1,
+        }
+    }
+
+    /// Record an entry that couldn't be imported, along with the reason why.
+    pub(super) fn record_error(&mut self, kind: Option<EntryKind>, error: String) {
+        if let Some(kind) = kind {
+            self.record(kind, Outcome::Failed);
+        }
+        self.errors.push(error);
+    }
+}
+
 impl ImportStat {
     fn is_empty(&self) -> bool {
-        self.created == 0 && self.updated == 0 && self.failed == 0
+        self.created == 0 && self.updated == 0 && self.skipped == 0 && self.failed == 0
+    }
+}
+
+impl ImportStrategy {
+    pub const ALL: [Self; 4] = [
+        Self::TakeIncoming,
+        Self::KeepLocal,
+        Self::RenameIncoming,
+        Self::Merge,
+    ];
+
+    /// The option to pass to the `import` command to choose this strategy.
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Self::TakeIncoming => "--take-incoming",
+            Self::KeepLocal => "--keep-local",
+            Self::RenameIncoming => "--rename-incoming",
+            Self::Merge => "--merge",
+        }
+    }
+}
+
+impl fmt::Display for ExportSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Name(name) => write!(f, "{}", name),
+            Self::WithContents(name) => write!(f, "{} and contents", name),
+            Self::Tag(tag) => write!(f, "#{}", tag),
+        }
+    }
+}
+
+impl fmt::Display for ImportPreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "# Import preview\n\n{}", self.stats)?;
+
+        if !self.descriptions.is_empty() {
+            writeln!(f)?;
+        }
+
+        for description in &self.descriptions {
+            write!(f, "\n* {}", description)?;
+        }
+
+        write!(f, "\n\n_Nothing has been imported yet. Use `import")?;
+        if self.strategy != ImportStrategy::default() {
+            write!(f, " {}", self.strategy.flag())?;
+        }
+        write!(
+            f,
+            "` to go ahead. Existing entries can be kept as they are with `import --keep-local`, imported alongside under a new name with `import --rename-incoming`, or filled in without overwriting your changes with `import --merge`._",
+        )
     }
 }
 
@@ -157,6 +862,14 @@ impl fmt::Display for ImportStat {
             first = false;
         }
 
+        if self.skipped != 0 {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} skipped", self.skipped)?;
+            first = false;
+        }
+
         if self.failed != 0 {
             if !first {
                 write!(f, ", ")?;
@@ -167,3 +880,77 @@ impl fmt::Display for ImportStat {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrate_test_v1() {
+        assert_eq!(
+            Ok(json!({
+                "version": BACKUP_VERSION,
+                "things": [
+                    {"type": "Place", "location_uuid": "abc"},
+                    {"type": "Place", "location_uuid": null},
+                    {"type": "Npc", "location_uuid": null},
+                ],
+            })),
+            migrate(json!({
+                "things": [
+                    {"type": "Place", "parent_uuid": "abc"},
+                    {"type": "Place", "parent_uuid": null},
+                    {"type": "Npc"},
+                ],
+            })),
+        );
+    }
+
+    #[test]
+    fn migrate_test_v2() {
+        let things = json!([
+            {"type": "Place", "location_uuid": "abc"},
+            {"type": "Npc"},
+        ]);
+
+        assert_eq!(
+            Ok(json!({"version": BACKUP_VERSION, "things": things.clone()})),
+            migrate(json!({"things": things.clone()})),
+        );
+        assert_eq!(
+            Ok(json!({"version": BACKUP_VERSION, "things": things.clone()})),
+            migrate(json!({"version": 2, "things": things})),
+        );
+        assert_eq!(Ok(json!({"version": BACKUP_VERSION})), migrate(json!({})));
+    }
+
+    #[test]
+    fn migrate_test_current() {
+        let data = json!({
+            "version": BACKUP_VERSION,
+            "things": [{"type": "Npc", "parent_uuid": "not a v1 backup"}],
+        });
+
+        assert_eq!(Ok(data.clone()), migrate(data));
+    }
+
+    #[test]
+    fn migrate_test_invalid() {
+        assert!(migrate(json!({"version": BACKUP_VERSION + 1}))
+            .unwrap_err()
+            .starts_with("this backup is from a newer version of initiative.sh"));
+        assert_eq!(
+            Err("invalid backup version 0".to_string()),
+            migrate(json!({"version": 0})),
+        );
+        assert_eq!(
+            Err("invalid backup version \"3\"".to_string()),
+            migrate(json!({"version": "3"})),
+        );
+        assert_eq!(
+            Err("expected a JSON object".to_string()),
+            migrate(json!([])),
+        );
+    }
+}
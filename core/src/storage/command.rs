@@ -1,11 +1,12 @@
-use super::backup::export;
+use super::backup::{export, export_selection, ExportSelection, ImportOptions, ImportStrategy};
+use super::document::{export_document, to_html, DocumentFormat};
 use super::{Change, RepositoryError};
 use crate::app::{
     AppMeta, Autocomplete, AutocompleteSuggestion, CommandAlias, CommandMatches, ContextAwareParse,
-    Event, Runnable,
+    Event, ExportData, Runnable,
 };
-use crate::utils::CaseInsensitiveStr;
-use crate::world::Thing;
+use crate::utils::{quoted_words, CaseInsensitiveStr};
+use crate::world::{journal_tags, Npc, ParsedQuery, Place, Thing, ThingQuery};
 use async_trait::async_trait;
 use futures::join;
 use std::cmp::Ordering;
@@ -14,28 +15,98 @@ use std::iter::repeat;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum StorageCommand {
-    Delete { name: String },
-    Export,
-    Import,
-    Journal,
-    Load { name: String },
-    Redo,
-    Save { name: String },
-    Undo,
+    Delete {
+        name: String,
+    },
+
+    /// Delete a place that other journal entries are located in, either along with everything in
+    /// it (`delete Waterdeep and contents`) or moving its contents to its own location
+    /// (`delete Waterdeep keep contents`).
+    DeleteWithContents {
+        name: String,
+        keep_contents: bool,
+    },
+    Export {
+        /// Only export part of the journal, eg. `export Waterdeep and contents` or
+        /// `export #villain`.
+        selection: Option<ExportSelection>,
+    },
+
+    /// Render the journal as a readable document, eg. `export markdown`.
+    ExportDocument {
+        format: DocumentFormat,
+    },
+
+    /// Import a backup, or with `--preview`, describe what importing it would do. Entries that
+    /// already exist are handled according to the strategy, eg. `import --keep-local`.
+    Import {
+        preview: bool,
+        strategy: ImportStrategy,
+    },
+    Journal {
+        /// Only list matching entries, eg. `journal inns in Waterdeep` or `journal #villain`.
+        query: Option<Box<ParsedQuery>>,
+    },
+    Load {
+        name: String,
+    },
+    Redo {
+        count: usize,
+    },
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    Save {
+        name: String,
+    },
+
+    /// Exchange changes to the journal with other devices through a sync server.
+    Sync,
+    Undo {
+        count: usize,
+    },
+    UndoHistory,
 }
 
 #[async_trait(?Send)]
 impl Runnable for StorageCommand {
-    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+    async fn run(self, input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
         match self {
-            Self::Journal => {
-                let mut output = "# Journal".to_string();
+            Self::Journal { query } => {
+                let mut output = if let Some(query) = &query {
+                    format!("# Journal: {}", query)
+                } else {
+                    "# Journal".to_string()
+                };
                 let [mut npcs, mut places] = [Vec::new(), Vec::new()];
 
-                let record_count = app_meta
-                    .repository
-                    .journal()
-                    .await
+                let things = if let Some(parsed) = &query {
+                    let mut thing_query = parsed.query.clone();
+
+                    if let Some(location) = &parsed.location {
+                        thing_query.location_uuid = match app_meta
+                            .repository
+                            .get_by_name(location)
+                            .await
+                        {
+                            Ok(Thing::Place(place)) if place.uuid.is_some() => place.uuid,
+                            Ok(_) | Err(RepositoryError::NotFound) => {
+                                return Err(format!(
+                                    r#"There is no place named "{}" in your journal."#,
+                                    location,
+                                ));
+                            }
+                            Err(_) => return Err("Couldn't access the journal.".to_string()),
+                        };
+                    }
+
+                    app_meta.repository.query_journal(&thing_query).await
+                } else {
+                    app_meta.repository.journal().await
+                };
+
+                let record_count = things
                     .map_err(|_| "Couldn't access the journal.".to_string())?
                     .into_iter()
                     .map(|thing| match thing {
@@ -71,7 +142,12 @@ impl Runnable for StorageCommand {
                 add_section("NPCs", npcs);
                 add_section("Places", places);
 
-                if record_count == 0 {
+                if let (0, Some(query)) = (record_count, &query) {
+                    output.push_str(&format!(
+                        "\n\n*Nothing in your journal matches \"{}\".*",
+                        query,
+                    ));
+                } else if record_count == 0 {
                     output.push_str("\n\n*Your journal is currently empty.*");
                 } else {
                     output.push_str("\n\n*To export the contents of your journal, use `export`.*");
@@ -80,13 +156,43 @@ impl Runnable for StorageCommand {
                 Ok(output)
             }
             Self::Delete { name } => {
-                let name = app_meta
-                        .repository
-                        .get_by_name(&name)
+                let thing = app_meta.repository.get_by_name(&name).await.ok();
+                let name = thing
+                    .as_ref()
+                    .and_then(|t| t.name().value().map(|s| s.to_string()))
+                    .unwrap_or(name);
+
+                if let Some(thing) = &thing {
+                    let mut contents = get_contents(app_meta, thing)
                         .await
-                        .map(|t| t.name().value().map(|s| s.to_string()))
-                        .unwrap_or(None)
-                        .unwrap_or(name);
+                        .map_err(|_| format!("Couldn't delete `{}`.", name))?;
+
+                    if !contents.is_empty() {
+                        contents.sort_unstable_by(|a, b| {
+                            a.name().to_string().cmp_ci(b.name().to_string())
+                        });
+
+                        let mut output = format!(
+                            "{} can't be deleted because the following entries are in it:\n",
+                            name,
+                        );
+
+                        for thing in contents {
+                            output.push_str(&format!("\n* {}", thing.display_summary()));
+                        }
+
+                        output.push_str(&format!(
+                            "\n\nUse `delete {name} and contents` to delete them as well, or `delete {name} keep contents` to {keep}.",
+                            name = name,
+                            keep = match get_parent(app_meta, thing).await {
+                                Some(parent) => format!("move them to {}", parent.name()),
+                                None => "keep them without a location".to_string(),
+                            },
+                        ));
+
+                        return Err(output);
+                    }
+                }
 
                 app_meta
                         .repository
@@ -104,6 +210,102 @@ impl Runnable for StorageCommand {
                             }
                         })
             }
+            Self::DeleteWithContents {
+                name,
+                keep_contents,
+            } => {
+                let thing = match app_meta.repository.get_by_name(&name).await {
+                    Ok(thing) => thing,
+                    Err(RepositoryError::NotFound) => {
+                        return Err(format!("There is no entity named \"{}\".", name));
+                    }
+                    Err(_) => return Err(format!("Couldn't delete `{}`.", name)),
+                };
+                let name = thing.name().to_string();
+
+                let contents = get_contents(app_meta, &thing)
+                    .await
+                    .map_err(|_| format!("Couldn't delete `{}`.", name))?;
+
+                if contents.is_empty() {
+                    return Self::Delete { name }.run(input, app_meta).await;
+                }
+
+                let place_uuid = thing.uuid().cloned();
+                let (mut changes, output): (Vec<Change>, String) = if keep_contents {
+                    let parent = get_parent(app_meta, &thing).await;
+                    let parent_uuid = parent
+                        .as_ref()
+                        .and_then(|parent| parent.place())
+                        .and_then(|place| place.uuid.clone());
+
+                    let output = format!(
+                        "{} was successfully deleted, and {} {}. Use `undo` to reverse this.",
+                        name,
+                        describe_count(contents.len()),
+                        match &parent {
+                            Some(parent) => format!("in it were moved to {}", parent.name()),
+                            None => "in it no longer have a location".to_string(),
+                        },
+                    );
+
+                    let changes = contents
+                        .into_iter()
+                        .map(|thing| Change::Edit {
+                            name: thing.name().to_string(),
+                            uuid: thing.uuid().cloned(),
+                            diff: match thing {
+                                Thing::Npc(_) => Npc {
+                                    location_uuid: parent_uuid.clone().into(),
+                                    ..Default::default()
+                                }
+                                .into(),
+                                Thing::Place(_) => Place {
+                                    location_uuid: parent_uuid.clone().into(),
+                                    ..Default::default()
+                                }
+                                .into(),
+                            },
+                        })
+                        .collect();
+
+                    (changes, output)
+                } else {
+                    let descendants = app_meta
+                        .repository
+                        .get_contents_recursive(&thing)
+                        .await
+                        .map_err(|_| format!("Couldn't delete `{}`.", name))?;
+
+                    let output = format!(
+                        "{} and {} in it were successfully deleted. Use `undo` to reverse this.",
+                        name,
+                        describe_count(descendants.len()),
+                    );
+
+                    let changes = descendants
+                        .into_iter()
+                        .map(|thing| Change::Delete {
+                            name: thing.name().to_string(),
+                            uuid: thing.uuid().cloned(),
+                        })
+                        .collect();
+
+                    (changes, output)
+                };
+
+                changes.push(Change::Delete {
+                    name: name.clone(),
+                    uuid: place_uuid,
+                });
+
+                app_meta
+                    .repository
+                    .modify(Change::Batch { changes })
+                    .await
+                    .map(|_| output)
+                    .map_err(|_| format!("Couldn't delete `{}`.", name))
+            }
             Self::Save { name } => {
                 let name = app_meta
                     .repository
@@ -129,11 +331,134 @@ impl Runnable for StorageCommand {
                         }
                     })
             }
-            Self::Export => {
-                (app_meta.event_dispatcher)(Event::Export(export(&app_meta.repository).await));
+            Self::Rename { name, new_name } => {
+                let name = app_meta
+                    .repository
+                    .get_by_name(&name)
+                    .await
+                    .map(|t| t.name().value().map(|s| s.to_string()))
+                    .unwrap_or(None)
+                    .unwrap_or(name);
+
+                match app_meta
+                    .repository
+                    .modify(Change::Rename {
+                        name: name.clone(),
+                        uuid: None,
+                        new_name: new_name.clone(),
+                    })
+                    .await
+                {
+                    Ok(thing) => {
+                        let renamed_aliases: Vec<CommandAlias> = app_meta
+                            .command_aliases
+                            .iter()
+                            .filter_map(|alias| alias.with_renamed_thing(&name, &new_name))
+                            .collect();
+
+                        for alias in renamed_aliases {
+                            app_meta.command_aliases.replace(alias);
+                        }
+
+                        let mut output = format!(
+                            "{} was successfully renamed to {}. Use `undo` to reverse this.",
+                            name, new_name,
+                        );
+
+                        if let Some(thing) = thing.filter(|thing| thing.uuid().is_none()) {
+                            output.push_str(&format!(
+                                "\n\n_{} has not yet been saved. Use ~save~ to save {} to your `journal`._",
+                                new_name,
+                                thing.gender().them(),
+                            ));
+
+                            app_meta.command_aliases.replace(CommandAlias::literal(
+                                "save",
+                                format!("save {}", new_name),
+                                StorageCommand::Save { name: new_name }.into(),
+                            ));
+                        }
+
+                        Ok(output)
+                    }
+                    Err((_, RepositoryError::NotFound)) => {
+                        Err(format!("There is no entity named \"{}\".", name))
+                    }
+                    Err((_, RepositoryError::NameAlreadyExists)) => {
+                        if let Ok(other_thing) = app_meta.repository.get_by_name(&new_name).await {
+                            Err(format!(
+                                "That name is already in use by {}.",
+                                other_thing.display_summary(),
+                            ))
+                        } else {
+                            Err("That name is already in use.".to_string())
+                        }
+                    }
+                    Err((_, RepositoryError::DataStoreFailed))
+                    | Err((_, RepositoryError::MissingName)) => {
+                        Err(format!("Couldn't rename `{}`.", name))
+                    }
+                }
+            }
+            Self::Export { selection: None } => {
+                (app_meta.event_dispatcher)(Event::Export(ExportData::Backup(
+                    export(&app_meta.repository).await,
+                )));
                 Ok("The journal is exporting. Your download should begin shortly.".to_string())
             }
-            Self::Import => {
+            Self::Export {
+                selection: Some(selection),
+            } => {
+                let data = match export_selection(&app_meta.repository, &selection).await {
+                    Ok(data) => data,
+                    Err(RepositoryError::NotFound) => {
+                        let (ExportSelection::Name(name)
+                        | ExportSelection::WithContents(name)
+                        | ExportSelection::Tag(name)) = &selection;
+
+                        return Err(format!(
+                            r#"There is nothing named "{}" in your journal."#,
+                            name,
+                        ));
+                    }
+                    Err(_) => return Err("Couldn't access the journal.".to_string()),
+                };
+
+                if data.things.is_empty() {
+                    return Err(format!("Nothing in your journal is tagged {}.", selection));
+                }
+
+                let count = data.things.len();
+                (app_meta.event_dispatcher)(Event::Export(ExportData::Backup(data)));
+                Ok(format!(
+                    "{} from your journal {} exporting. Your download should begin shortly.",
+                    if count == 1 {
+                        "1 entry".to_string()
+                    } else {
+                        format!("{} entries", count)
+                    },
+                    if count == 1 { "is" } else { "are" },
+                ))
+            }
+            Self::ExportDocument { format } => {
+                let markdown = match export_document(&app_meta.repository).await {
+                    Ok(Some(markdown)) => markdown,
+                    Ok(None) => {
+                        return Ok(
+                            "The journal is empty, so there's nothing to export yet.".to_string()
+                        );
+                    }
+                    Err(_) => return Err("Couldn't access the journal.".to_string()),
+                };
+
+                (app_meta.event_dispatcher)(Event::Export(match format {
+                    DocumentFormat::Markdown => ExportData::Markdown(markdown),
+                    DocumentFormat::Html => ExportData::Html(to_html(&markdown)),
+                }));
+                Ok("The journal is exporting as a campaign document. Your download should begin shortly.".to_string())
+            }
+            Self::Import { preview, strategy } => {
+                app_meta.import_options = ImportOptions { preview, strategy };
                 (app_meta.event_dispatcher)(Event::Import);
                 Ok("The file upload popup should appear momentarily. Please select a compatible JSON file, such as that produced by the `export` command.".to_string())
             }
@@ -167,51 +492,39 @@ impl Runnable for StorageCommand {
 
                 output
             }
-            Self::Redo => match app_meta.repository.redo().await {
-                Some(Ok(thing)) => {
-                    let action = app_meta
-                        .repository
-                        .undo_history()
-                        .next()
-                        .unwrap()
-                        .display_undo();
+            Self::Redo { count } => undo_or_redo(app_meta, count, false).await,
+            Self::Sync => {
+                (app_meta.event_dispatcher)(Event::Sync);
+                Ok("Syncing your journal with the server...".to_string())
+            }
+            Self::Undo { count } => undo_or_redo(app_meta, count, true).await,
+            Self::UndoHistory => {
+                let mut output = "# Undo history\n".to_string();
 
-                    if let Some(thing) = thing {
-                        Ok(format!(
-                            "{}\n\n_Successfully redid {}. Use `undo` to reverse this._",
-                            thing.display_details(app_meta.repository.load_relations(&thing).await.unwrap_or_default()),
-                            action,
-                        ))
-                    } else {
-                        Ok(format!(
-                            "Successfully redid {}. Use `undo` to reverse this.",
-                            action,
-                        ))
-                    }
+                if app_meta.repository.undo_history().next().is_none() {
+                    output.push_str("\n*Nothing to undo.*");
                 }
-                Some(Err(_)) => Err("Failed to redo.".to_string()),
-                None => Err("Nothing to redo.".to_string()),
-            },
-            Self::Undo => match app_meta.repository.undo().await {
-                Some(Ok(thing)) => {
-                    let action = app_meta.repository.get_redo().unwrap().display_redo();
 
-                    if let Some(thing) = thing {
-                        Ok(format!(
-                            "{}\n\n_Successfully undid {}. Use `redo` to reverse this._",
-                            thing.display_details(app_meta.repository.load_relations(&thing).await.unwrap_or_default()),
-                            action,
-                        ))
-                    } else {
-                        Ok(format!(
-                            "Successfully undid {}. Use `redo` to reverse this.",
-                            action,
-                        ))
+                for (i, change) in app_meta.repository.undo_history().enumerate() {
+                    output.push_str(&format!("\n{}. {}", i + 1, change.display_undo()));
+                }
+
+                if app_meta.repository.redo_history().next().is_some() {
+                    output.push_str("\n\n## Redo\n");
+
+                    for (i, change) in app_meta.repository.redo_history().enumerate() {
+                        output.push_str(&format!("\n{}. {}", i + 1, change.display_redo()));
                     }
                 }
-                Some(Err(_)) => Err("Failed to undo.".to_string()),
-                None => Err("Nothing to undo.".to_string()),
-            },
+
+                if app_meta.repository.undo_history().nth(1).is_some()
+                    || app_meta.repository.redo_history().nth(1).is_some()
+                {
+                    output.push_str("\n\n*Use `undo [number]` or `redo [number]` to reverse several changes at once.*");
+                }
+
+                Ok(output)
+            }
         }
         .map(|mut s| {
             if !app_meta.repository.data_store_enabled() {
@@ -234,27 +547,110 @@ impl ContextAwareParse for StorageCommand {
         }
 
         if let Some(name) = input.strip_prefix_ci("delete ") {
-            matches.push_canonical(Self::Delete {
-                name: name.to_string(),
-            });
+            if let Some(name) = name.strip_suffix_ci(" and contents") {
+                matches.push_canonical(Self::DeleteWithContents {
+                    name: name.to_string(),
+                    keep_contents: false,
+                });
+            } else if let Some(name) = name.strip_suffix_ci(" keep contents") {
+                matches.push_canonical(Self::DeleteWithContents {
+                    name: name.to_string(),
+                    keep_contents: true,
+                });
+            } else {
+                matches.push_canonical(Self::Delete {
+                    name: name.to_string(),
+                });
+            }
         } else if let Some(name) = input.strip_prefix_ci("load ") {
             matches.push_canonical(Self::Load {
                 name: name.to_string(),
             });
+        } else if let Some(input) = input.strip_prefix_ci("rename ") {
+            if let Some((name, new_name)) = split_rename(input, app_meta).await {
+                matches.push_canonical(Self::Rename { name, new_name });
+            }
         } else if let Some(name) = input.strip_prefix_ci("save ") {
             matches.push_canonical(Self::Save {
                 name: name.to_string(),
             });
         } else if input.eq_ci("journal") {
-            matches.push_canonical(Self::Journal);
+            matches.push_canonical(Self::Journal { query: None });
+        } else if let Some(query) = input
+            .strip_prefix_ci("journal ")
+            .and_then(|s| s.parse().ok())
+        {
+            matches.push_canonical(Self::Journal {
+                query: Some(Box::new(query)),
+            });
+        } else if input.eq_ci("sync") {
+            matches.push_canonical(Self::Sync);
         } else if input.eq_ci("undo") {
-            matches.push_canonical(Self::Undo);
+            matches.push_canonical(Self::Undo { count: 1 });
+        } else if input.eq_ci("undo history") {
+            matches.push_canonical(Self::UndoHistory);
+        } else if let Some(count) = input
+            .strip_prefix_ci("undo ")
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&count| count > 0)
+        {
+            matches.push_canonical(Self::Undo { count });
         } else if input.eq_ci("redo") {
-            matches.push_canonical(Self::Redo);
+            matches.push_canonical(Self::Redo { count: 1 });
+        } else if let Some(count) = input
+            .strip_prefix_ci("redo ")
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&count| count > 0)
+        {
+            matches.push_canonical(Self::Redo { count });
         } else if input.eq_ci("export") {
-            matches.push_canonical(Self::Export);
-        } else if input.eq_ci("import") {
-            matches.push_canonical(Self::Import);
+            matches.push_canonical(Self::Export { selection: None });
+        } else if input.eq_ci("export markdown") {
+            matches.push_canonical(Self::ExportDocument {
+                format: DocumentFormat::Markdown,
+            });
+        } else if input.eq_ci("export html") {
+            matches.push_canonical(Self::ExportDocument {
+                format: DocumentFormat::Html,
+            });
+        } else if let Some(name) = input.strip_prefix_ci("export ") {
+            let selection = if let Some(name) = name.strip_suffix_ci(" and contents") {
+                ExportSelection::WithContents(name.to_string())
+            } else if let Some(tag) = name.strip_prefix('#').filter(|tag| {
+                !tag.is_empty() && !tag.contains(|c: char| c == '#' || c.is_whitespace())
+            }) {
+                ExportSelection::Tag(tag.to_lowercase())
+            } else {
+                ExportSelection::Name(name.to_string())
+            };
+
+            matches.push_canonical(Self::Export {
+                selection: Some(selection),
+            });
+        } else if let Some(options) = input
+            .strip_prefix_ci("import")
+            .filter(|s| s.is_empty() || s.starts_with(char::is_whitespace))
+        {
+            let (mut preview, mut strategy) = (false, None);
+
+            for option in options.split_whitespace() {
+                if option.eq_ci("--preview") && !preview {
+                    preview = true;
+                } else if let Some(option_strategy) = ImportStrategy::ALL
+                    .into_iter()
+                    .find(|s| s.flag().eq_ci(option))
+                    .filter(|_| strategy.is_none())
+                {
+                    strategy = Some(option_strategy);
+                } else {
+                    return matches;
+                }
+            }
+
+            matches.push_canonical(Self::Import {
+                preview,
+                strategy: strategy.unwrap_or_default(),
+            });
         }
 
         matches
@@ -267,10 +663,29 @@ impl Autocomplete for StorageCommand {
         let mut suggestions: Vec<AutocompleteSuggestion> = [
             ("delete", "delete [name]", "remove an entry from journal"),
             ("export", "export", "export the journal contents"),
+            ("export", "export [name]", "export part of the journal"),
+            (
+                "export html",
+                "export html",
+                "export the journal as a web page",
+            ),
+            (
+                "export markdown",
+                "export markdown",
+                "export the journal as a markdown document",
+            ),
             ("import", "import", "import a journal backup"),
             ("journal", "journal", "list journal contents"),
+            ("journal", "journal [search]", "search journal contents"),
             ("load", "load [name]", "load an entry"),
+            ("rename", "rename [name] to [new name]", "rename an entry"),
             ("save", "save [name]", "save an entry to journal"),
+            ("sync", "sync", "sync the journal with your other devices"),
+            (
+                "undo history",
+                "undo history",
+                "list changes that can be undone",
+            ),
         ]
         .into_iter()
         .filter(|(s, _, _)| s.starts_with_ci(input))
@@ -301,8 +716,48 @@ impl Autocomplete for StorageCommand {
         )
         .collect();
 
+        if input.starts_with_ci("import ") {
+            suggestions.extend(
+                [
+                    ("import --preview", "preview a journal backup"),
+                    ("import --keep-local", "import, skipping existing entries"),
+                    ("import --rename-incoming", "import, renaming duplicates"),
+                    ("import --merge", "import, filling in existing entries"),
+                ]
+                .into_iter()
+                .filter(|(term, _)| term.starts_with_ci(input))
+                .map(|(term, summary)| AutocompleteSuggestion::new(term, summary)),
+            );
+        }
+
+        if let Some(partial_tag) = input
+            .strip_prefix_ci("journal ")
+            .and_then(|s| s.rsplit(char::is_whitespace).next())
+            .or_else(|| {
+                input
+                    .strip_prefix_ci("export ")
+                    .filter(|s| !s.contains(char::is_whitespace))
+            })
+            .and_then(|word| word.strip_prefix('#'))
+        {
+            let prefix = &input[..input.len() - partial_tag.len()];
+
+            suggestions.extend(
+                journal_tags(&app_meta.repository)
+                    .await
+                    .into_iter()
+                    .filter(|tag| tag.starts_with_ci(partial_tag))
+                    .map(|tag| {
+                        AutocompleteSuggestion::new(
+                            format!("{}{}", prefix, tag),
+                            format!("entries tagged #{}", tag),
+                        )
+                    }),
+            );
+        }
+
         let ((full_matches, partial_matches), prefix) = if let Some((prefix, name)) =
-            ["delete ", "load ", "save "]
+            ["delete ", "export ", "load ", "save "]
                 .iter()
                 .find_map(|prefix| input.strip_prefix_ci(prefix).map(|name| (*prefix, name)))
         {
@@ -336,7 +791,7 @@ impl Autocomplete for StorageCommand {
         {
             if matches!(
                 (prefix, thing.uuid()),
-                ("save ", Some(_)) | ("delete ", None)
+                ("save ", Some(_)) | ("delete " | "export ", None)
             ) {
                 continue;
             }
@@ -349,6 +804,7 @@ impl Autocomplete for StorageCommand {
                     suggestion_term,
                     match command {
                         Self::Delete { .. } => format!("remove {} from journal", thing.as_str()),
+                        Self::Export { .. } => format!("export {} from journal", thing.as_str()),
                         Self::Save { .. } => format!("save {} to journal", thing.as_str()),
                         Self::Load { .. } => {
                             if thing.uuid().is_some() {
@@ -371,15 +827,204 @@ impl fmt::Display for StorageCommand {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
             Self::Delete { name } => write!(f, "delete {}", name),
-            Self::Export => write!(f, "export"),
-            Self::Import => write!(f, "import"),
-            Self::Journal => write!(f, "journal"),
+            Self::DeleteWithContents {
+                name,
+                keep_contents: false,
+            } => write!(f, "delete {} and contents", name),
+            Self::DeleteWithContents {
+                name,
+                keep_contents: true,
+            } => write!(f, "delete {} keep contents", name),
+            Self::Export { selection: None } => write!(f, "export"),
+            Self::Export {
+                selection: Some(selection),
+            } => write!(f, "export {}", selection),
+            Self::ExportDocument {
+                format: DocumentFormat::Markdown,
+            } => write!(f, "export markdown"),
+            Self::ExportDocument {
+                format: DocumentFormat::Html,
+            } => write!(f, "export html"),
+            Self::Import { preview, strategy } => {
+                write!(f, "import")?;
+                if *preview {
+                    write!(f, " --preview")?;
+                }
+                if *strategy != ImportStrategy::default() {
+                    write!(f, " {}", strategy.flag())?;
+                }
+                Ok(())
+            }
+            Self::Journal { query: None } => write!(f, "journal"),
+            Self::Journal { query: Some(query) } => write!(f, "journal {}", query),
             Self::Load { name } => write!(f, "load {}", name),
-            Self::Redo => write!(f, "redo"),
+            Self::Redo { count: 1 } => write!(f, "redo"),
+            Self::Redo { count } => write!(f, "redo {}", count),
+            Self::Rename { name, new_name } => write!(f, "rename {} to {}", name, new_name),
             Self::Save { name } => write!(f, "save {}", name),
-            Self::Undo => write!(f, "undo"),
+            Self::Undo { count: 1 } => write!(f, "undo"),
+            Self::Undo { count } => write!(f, "undo {}", count),
+            Self::Sync => write!(f, "sync"),
+            Self::UndoHistory => write!(f, "undo history"),
+        }
+    }
+}
+
+/// The journal entries located directly within a place, eg. the characters and buildings in a
+/// town. Things that aren't saved places can't contain anything.
+async fn get_contents(app_meta: &AppMeta, thing: &Thing) -> Result<Vec<Thing>, RepositoryError> {
+    if let Some(uuid) = thing.place().and_then(|place| place.uuid.clone()) {
+        app_meta
+            .repository
+            .query_journal(&ThingQuery {
+                location_uuid: Some(uuid),
+                ..Default::default()
+            })
+            .await
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// The place in which a thing is located, if it's in the journal.
+async fn get_parent(app_meta: &AppMeta, thing: &Thing) -> Option<Thing> {
+    let location_uuid = match thing {
+        Thing::Npc(npc) => npc.location_uuid.value(),
+        Thing::Place(place) => place.location_uuid.value(),
+    }?;
+
+    app_meta
+        .repository
+        .get_by_uuid(location_uuid.as_ref())
+        .await
+        .ok()
+}
+
+fn describe_count(count: usize) -> String {
+    if count == 1 {
+        "the 1 entry".to_string()
+    } else {
+        format!("the {} entries", count)
+    }
+}
+
+/// Split "Bob to Robert" into its old and new names. Since names can contain the word "to" (eg.
+/// "Road to Nowhere"), prefer a split where the old name is something that exists.
+async fn split_rename(input: &str, app_meta: &AppMeta) -> Option<(String, String)> {
+    let splits: Vec<(&str, &str)> = quoted_words(input)
+        .skip(1)
+        .filter(|word| word.as_str().eq_ci("to"))
+        .map(|word| {
+            (
+                input[..word.range().start].trim(),
+                input[word.range().end..].trim(),
+            )
+        })
+        .filter(|(_, new_name)| !new_name.is_empty())
+        .collect();
+
+    for &(name, new_name) in &splits {
+        if app_meta.repository.get_by_name(name).await.is_ok() {
+            return Some((name.to_string(), new_name.to_string()));
         }
     }
+
+    splits
+        .first()
+        .map(|(name, new_name)| (name.to_string(), new_name.to_string()))
+}
+
+/// Undo or redo up to `count` changes, stopping early if there's nothing left or a change fails.
+async fn undo_or_redo(app_meta: &mut AppMeta, count: usize, undo: bool) -> Result<String, String> {
+    let (verb, reverse) = if undo {
+        ("undid", "redo")
+    } else {
+        ("redid", "undo")
+    };
+    let mut actions = Vec::new();
+    let mut last_thing = None;
+    let mut failed = false;
+
+    for _ in 0..count {
+        let result = if undo {
+            app_meta.repository.undo().await
+        } else {
+            app_meta.repository.redo().await
+        };
+
+        match result {
+            Some(Ok(thing)) => {
+                actions.push(if undo {
+                    app_meta
+                        .repository
+                        .get_redo()
+                        .unwrap()
+                        .display_redo()
+                        .to_string()
+                } else {
+                    app_meta
+                        .repository
+                        .undo_history()
+                        .next()
+                        .unwrap()
+                        .display_undo()
+                        .to_string()
+                });
+                last_thing = thing;
+            }
+            Some(Err(_)) => {
+                failed = true;
+                break;
+            }
+            None => break,
+        }
+    }
+
+    let mut output = match (actions.len(), last_thing) {
+        (0, _) if failed => {
+            return Err(format!("Failed to {}.", if undo { "undo" } else { "redo" }))
+        }
+        (0, _) => {
+            return Err(format!(
+                "Nothing to {}.",
+                if undo { "undo" } else { "redo" }
+            ))
+        }
+        (1, Some(thing)) => format!(
+            "{}\n\n_Successfully {} {}. Use `{}` to reverse this._",
+            thing.display_details(
+                app_meta
+                    .repository
+                    .load_relations(&thing)
+                    .await
+                    .unwrap_or_default()
+            ),
+            verb,
+            actions[0],
+            reverse,
+        ),
+        (1, None) => format!(
+            "Successfully {} {}. Use `{}` to reverse this.",
+            verb, actions[0], reverse,
+        ),
+        (len, _) => format!(
+            "Successfully {} {} changes: {}. Use `{} {}` to reverse this.",
+            verb,
+            len,
+            actions.join(", "),
+            reverse,
+            len,
+        ),
+    };
+
+    if failed {
+        output.push_str(&format!(
+            "\n\n! Failed to {} the remaining changes.",
+            if undo { "undo" } else { "redo" },
+        ));
+    }
+
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -422,6 +1067,28 @@ mod test {
             )),
         );
 
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::DeleteWithContents {
+                name: "Waterdeep".to_string(),
+                keep_contents: false,
+            }),
+            block_on(StorageCommand::parse_input(
+                "delete Waterdeep AND CONTENTS",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::DeleteWithContents {
+                name: "Waterdeep".to_string(),
+                keep_contents: true,
+            }),
+            block_on(StorageCommand::parse_input(
+                "delete Waterdeep keep contents",
+                &app_meta
+            )),
+        );
+
         assert_eq!(
             CommandMatches::new_canonical(StorageCommand::Save {
                 name: "Gandalf the Grey".to_string(),
@@ -465,15 +1132,115 @@ mod test {
         );
 
         assert_eq!(
-            CommandMatches::new_canonical(StorageCommand::Journal),
+            CommandMatches::new_canonical(StorageCommand::Rename {
+                name: "Gandalf the Grey".to_string(),
+                new_name: "Gandalf the White".to_string(),
+            }),
+            block_on(StorageCommand::parse_input(
+                "rename Gandalf the Grey to Gandalf the White",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(StorageCommand::parse_input("rename Gandalf", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Journal { query: None }),
             block_on(StorageCommand::parse_input("journal", &app_meta)),
         );
 
         assert_eq!(
-            CommandMatches::new_canonical(StorageCommand::Journal),
+            CommandMatches::new_canonical(StorageCommand::Journal { query: None }),
             block_on(StorageCommand::parse_input("JOURNAL", &app_meta)),
         );
 
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Journal {
+                query: Some(Box::new("women over 60".parse().unwrap())),
+            }),
+            block_on(StorageCommand::parse_input(
+                "journal women over 60",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(StorageCommand::parse_input("journal potatoes", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Undo { count: 3 }),
+            block_on(StorageCommand::parse_input("UNDO 3", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(StorageCommand::parse_input("undo 0", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Import {
+                preview: true,
+                strategy: ImportStrategy::Merge,
+            }),
+            block_on(StorageCommand::parse_input(
+                "IMPORT --MERGE --PREVIEW",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Export {
+                selection: Some(ExportSelection::Name("Potato Johnson".to_string())),
+            }),
+            block_on(StorageCommand::parse_input(
+                "export Potato Johnson",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Export {
+                selection: Some(ExportSelection::WithContents("Waterdeep".to_string())),
+            }),
+            block_on(StorageCommand::parse_input(
+                "EXPORT Waterdeep AND CONTENTS",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::ExportDocument {
+                format: DocumentFormat::Html,
+            }),
+            block_on(StorageCommand::parse_input("EXPORT HTML", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(StorageCommand::Export {
+                selection: Some(ExportSelection::Tag("villain".to_string())),
+            }),
+            block_on(StorageCommand::parse_input("export #Villain", &app_meta)),
+        );
+
+        for input in [
+            "import --potato",
+            "import --merge --keep-local",
+            "import --preview --preview",
+            "importer",
+        ] {
+            assert_eq!(
+                CommandMatches::default(),
+                block_on(StorageCommand::parse_input(input, &app_meta)),
+                "{}",
+                input,
+            );
+        }
+
         assert_eq!(
             CommandMatches::default(),
             block_on(StorageCommand::parse_input("potato", &app_meta)),
@@ -522,6 +1289,7 @@ mod test {
         .unwrap();
 
         assert!(block_on(StorageCommand::autocomplete("delete P", &app_meta)).is_empty());
+        assert!(block_on(StorageCommand::autocomplete("export P", &app_meta)).is_empty());
 
         assert_autocomplete(
             &[
@@ -572,32 +1340,60 @@ mod test {
         );
 
         assert_autocomplete(
-            &[("save [name]", "save an entry to journal")][..],
+            &[
+                ("save [name]", "save an entry to journal"),
+                ("sync", "sync the journal with your other devices"),
+            ][..],
             block_on(StorageCommand::autocomplete("s", &app_meta)),
         );
 
         assert_autocomplete(
-            &[("save [name]", "save an entry to journal")][..],
+            &[
+                ("save [name]", "save an entry to journal"),
+                ("sync", "sync the journal with your other devices"),
+            ][..],
             block_on(StorageCommand::autocomplete("S", &app_meta)),
         );
 
         assert_autocomplete(
-            &[("journal", "list journal contents")][..],
+            &[
+                ("journal", "list journal contents"),
+                ("journal [search]", "search journal contents"),
+            ][..],
             block_on(StorageCommand::autocomplete("j", &app_meta)),
         );
 
         assert_autocomplete(
-            &[("journal", "list journal contents")][..],
+            &[
+                ("journal", "list journal contents"),
+                ("journal [search]", "search journal contents"),
+            ][..],
             block_on(StorageCommand::autocomplete("J", &app_meta)),
         );
 
         assert_autocomplete(
-            &[("export", "export the journal contents")][..],
+            &[
+                ("export", "export the journal contents"),
+                ("export [name]", "export part of the journal"),
+                ("export html", "export the journal as a web page"),
+                (
+                    "export markdown",
+                    "export the journal as a markdown document",
+                ),
+            ][..],
             block_on(StorageCommand::autocomplete("e", &app_meta)),
         );
 
         assert_autocomplete(
-            &[("export", "export the journal contents")][..],
+            &[
+                ("export", "export the journal contents"),
+                ("export [name]", "export part of the journal"),
+                ("export html", "export the journal as a web page"),
+                (
+                    "export markdown",
+                    "export the journal as a markdown document",
+                ),
+            ][..],
             block_on(StorageCommand::autocomplete("E", &app_meta)),
         );
 
@@ -611,6 +1407,21 @@ mod test {
             block_on(StorageCommand::autocomplete("I", &app_meta)),
         );
 
+        assert_autocomplete(
+            &[
+                ("import --keep-local", "import, skipping existing entries"),
+                ("import --merge", "import, filling in existing entries"),
+                ("import --preview", "preview a journal backup"),
+                ("import --rename-incoming", "import, renaming duplicates"),
+            ][..],
+            block_on(StorageCommand::autocomplete("import ", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[("import --preview", "preview a journal backup")][..],
+            block_on(StorageCommand::autocomplete("import --p", &app_meta)),
+        );
+
         assert_autocomplete(
             &[
                 ("Potato & Meat", "inn (unsaved)"),
@@ -636,7 +1447,10 @@ mod test {
         );
 
         assert_autocomplete(
-            &[("undo", "undo creating Potato & Meat")][..],
+            &[
+                ("undo", "undo creating Potato & Meat"),
+                ("undo history", "list changes that can be undone"),
+            ][..],
             block_on(StorageCommand::autocomplete("undo", &app_meta)),
         );
 
@@ -653,7 +1467,10 @@ mod test {
         );
 
         assert_autocomplete(
-            &[("undo", "Nothing to undo.")][..],
+            &[
+                ("undo", "Nothing to undo."),
+                ("undo history", "list changes that can be undone"),
+            ][..],
             block_on(StorageCommand::autocomplete(
                 "undo",
                 &AppMeta::new(MemoryDataStore::default(), &event_dispatcher),
@@ -672,9 +1489,67 @@ mod test {
             StorageCommand::Save {
                 name: "Potato Johnson".to_string(),
             },
-            StorageCommand::Export,
-            StorageCommand::Import,
-            StorageCommand::Journal,
+            StorageCommand::DeleteWithContents {
+                name: "Potato Johnson".to_string(),
+                keep_contents: false,
+            },
+            StorageCommand::DeleteWithContents {
+                name: "Potato Johnson".to_string(),
+                keep_contents: true,
+            },
+            StorageCommand::Rename {
+                name: "Potato Johnson".to_string(),
+                new_name: "Potato Jones".to_string(),
+            },
+            StorageCommand::Export { selection: None },
+            StorageCommand::Export {
+                selection: Some(ExportSelection::Name("Bob".to_string())),
+            },
+            StorageCommand::Export {
+                selection: Some(ExportSelection::WithContents("Waterdeep".to_string())),
+            },
+            StorageCommand::Export {
+                selection: Some(ExportSelection::Tag("villain".to_string())),
+            },
+            StorageCommand::ExportDocument {
+                format: DocumentFormat::Markdown,
+            },
+            StorageCommand::ExportDocument {
+                format: DocumentFormat::Html,
+            },
+            StorageCommand::Import {
+                preview: false,
+                strategy: ImportStrategy::TakeIncoming,
+            },
+            StorageCommand::Import {
+                preview: true,
+                strategy: ImportStrategy::TakeIncoming,
+            },
+            StorageCommand::Import {
+                preview: false,
+                strategy: ImportStrategy::KeepLocal,
+            },
+            StorageCommand::Import {
+                preview: true,
+                strategy: ImportStrategy::RenameIncoming,
+            },
+            StorageCommand::Import {
+                preview: false,
+                strategy: ImportStrategy::Merge,
+            },
+            StorageCommand::Sync,
+            StorageCommand::Undo { count: 1 },
+            StorageCommand::Undo { count: 3 },
+            StorageCommand::UndoHistory,
+            StorageCommand::Redo { count: 1 },
+            StorageCommand::Redo { count: 3 },
+            StorageCommand::Journal { query: None },
+            StorageCommand::Journal {
+                query: Some(Box::new("#villain #waterdeep".parse().unwrap())),
+            },
+            StorageCommand::Journal {
+                query: Some(Box::new("elves in Waterdeep".parse().unwrap())),
+            },
             StorageCommand::Load {
                 name: "Potato Johnson".to_string(),
             },
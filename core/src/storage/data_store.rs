@@ -1,5 +1,5 @@
 use crate::utils::CaseInsensitiveStr;
-use crate::{Thing, Uuid};
+use crate::{Thing, ThingQuery, Uuid};
 use async_trait::async_trait;
 use std::collections::HashMap;
 
@@ -46,6 +46,10 @@ impl DataStore for NullDataStore {
         Err(())
     }
 
+    async fn get_things_by_query(&self, _query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+        Err(())
+    }
+
     async fn save_thing(&mut self, _thing: &Thing) -> Result<(), ()> {
         Err(())
     }
@@ -123,6 +127,16 @@ impl DataStore for MemoryDataStore {
             .collect())
     }
 
+    async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+        Ok(self
+            .things
+            .borrow()
+            .values()
+            .filter(|thing| query.matches(thing))
+            .cloned()
+            .collect())
+    }
+
     async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()> {
         if let Some(uuid) = thing.uuid() {
             let mut things = self.things.borrow_mut();
@@ -176,6 +190,11 @@ pub trait DataStore {
         limit: Option<usize>,
     ) -> Result<Vec<Thing>, ()>;
 
+    /// Get every thing matching the query. This should narrow the search as much as the
+    /// underlying storage allows rather than loading everything, then apply `query.matches()` to
+    /// whatever remains.
+    async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()>;
+
     async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()>;
 
     async fn set_value(&mut self, key: &str, value: &str) -> Result<(), ()>;
@@ -183,6 +202,13 @@ pub trait DataStore {
     async fn get_value(&self, key: &str) -> Result<Option<String>, ()>;
 
     async fn delete_value(&mut self, key: &str) -> Result<(), ()>;
+
+    /// Write any changes still held in memory through to permanent storage. This is called after
+    /// every command, so a data store that writes slowly can save up the changes a command makes
+    /// and write them all at once. Data stores that write immediately needn't implement it.
+    async fn flush(&mut self) -> Result<(), ()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -193,6 +219,11 @@ mod test {
 
     const TEST_UUID: Uuid = Uuid::from_u128(u128::MAX);
 
+    #[test]
+    fn memory_conformance_test() {
+        block_on(crate::storage::test_data_store(MemoryDataStore::default()));
+    }
+
     #[test]
     fn memory_delete_thing_by_uuid_test() {
         let mut ds = MemoryDataStore::default();
@@ -275,6 +306,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn memory_get_things_by_query_test() {
+        let mut ds = MemoryDataStore::default();
+
+        block_on(ds.save_thing(&person(Uuid::new_v4()))).unwrap();
+        block_on(ds.save_thing(&place(Uuid::new_v4()))).unwrap();
+
+        let query = ThingQuery {
+            thing: Some(Npc::default().into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["character"],
+            block_on(ds.get_things_by_query(&query))
+                .unwrap()
+                .iter()
+                .map(|thing| thing.as_str())
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(
+            2,
+            block_on(ds.get_things_by_query(&ThingQuery::default()))
+                .unwrap()
+                .len(),
+        );
+    }
+
     #[test]
     fn memory_edit_thing_test() {
         let mut ds = MemoryDataStore::default();
@@ -0,0 +1,277 @@
+use super::repository::{Error as RepositoryError, KeyValue, Repository};
+use crate::utils::CaseInsensitiveStr;
+use crate::world::Thing;
+use crate::Uuid;
+use futures::join;
+use pulldown_cmark::{html, Parser};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The formats that the journal can be rendered in as a readable campaign document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocumentFormat {
+    Markdown,
+    Html,
+}
+
+/// Render the whole journal as a campaign document, formatted as markdown. Each place is followed
+/// by the characters and places located in it, shown just as `load` would show them, and names
+/// link to the corresponding section of the document.
+///
+/// Returns `None` if the journal is empty.
+pub async fn export_document(repo: &Repository) -> Result<Option<String>, RepositoryError> {
+    let (things, time, calendar) = join!(
+        repo.journal(),
+        repo.get_key_value(&KeyValue::Time(None)),
+        repo.get_key_value(&KeyValue::Calendar(None)),
+    );
+    let mut things = things?;
+    let calendar = calendar.ok().and_then(|c| c.calendar());
+    let time = time.ok().and_then(|t| t.time()).unwrap_or_default();
+
+    if things.is_empty() {
+        return Ok(None);
+    }
+
+    things.sort_by(|a, b| match (a, b) {
+        (Thing::Place(_), Thing::Npc(_)) => Ordering::Less,
+        (Thing::Npc(_), Thing::Place(_)) => Ordering::Greater,
+        _ => match (a.name().value(), b.name().value()) {
+            (Some(a), Some(b)) => a.cmp_ci(b),
+            _ => Ordering::Equal,
+        },
+    });
+
+    let anchors = anchors(&things);
+    let uuids: HashSet<&Uuid> = things.iter().filter_map(|thing| thing.uuid()).collect();
+    let mut contents: HashMap<&Uuid, Vec<&Thing>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for thing in &things {
+        match thing.location_uuid().map(|uuid| uuid.as_ref()) {
+            Some(location_uuid) if uuids.contains(location_uuid) => {
+                contents.entry(location_uuid).or_default().push(thing)
+            }
+            _ => roots.push(thing),
+        }
+    }
+
+    let mut output = format!(
+        "# Journal\n\n*As of {}.*",
+        time.display_long(calendar.as_ref()),
+    );
+
+    // Walk the tree depth-first, keeping track of what's been written in case a mistake in the
+    // data has left some entries located inside each other and unreachable from the top.
+    let mut written = HashSet::new();
+    let mut stack: Vec<(&Thing, usize)> = roots.into_iter().rev().map(|t| (t, 0)).collect();
+
+    loop {
+        let (thing, depth) = if let Some(entry) = stack.pop() {
+            entry
+        } else if let Some(thing) = things.iter().find(|t| !written.contains(&t.uuid())) {
+            (thing, 0)
+        } else {
+            break;
+        };
+
+        if !written.insert(thing.uuid()) {
+            continue;
+        }
+
+        let relations = repo.load_relations(thing).await.unwrap_or_default();
+        output.push_str("\n\n");
+        output.push_str(&section(
+            &thing.display_details(relations).to_string(),
+            depth,
+            thing
+                .name()
+                .value()
+                .and_then(|name| anchors.get(&name.to_lowercase())),
+            &anchors,
+        ));
+
+        if let Some(children) = thing.uuid().and_then(|uuid| contents.get(uuid)) {
+            stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+
+    Ok(Some(output))
+}
+
+/// Convert a document produced by [`export_document`] into a standalone web page.
+pub fn to_html(markdown: &str) -> String {
+    let mut body = String::new();
+    html::push_html(&mut body, Parser::new(markdown));
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Journal</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        body,
+    )
+}
+
+/// Pick an anchor for each named thing, keyed by its lowercased name, eg. "the-yawning-portal".
+fn anchors(things: &[Thing]) -> HashMap<String, String> {
+    let mut used = HashSet::new();
+
+    things
+        .iter()
+        .filter_map(|thing| thing.name().value())
+        .map(|name| {
+            let slug: String = name
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join("-");
+
+            let mut anchor = slug.clone();
+            let mut i = 2;
+            while !used.insert(anchor.clone()) {
+                anchor = format!("{}-{}", slug, i);
+                i += 1;
+            }
+
+            (name.to_lowercase(), anchor)
+        })
+        .collect()
+}
+
+/// Adapt the details of a thing for its place in the document: nest its headings `depth` levels
+/// down, give it an anchor to link to, and turn the names of other entries into links.
+fn section(
+    details: &str,
+    depth: usize,
+    anchor: Option<&String>,
+    anchors: &HashMap<String, String>,
+) -> String {
+    let mut output = String::with_capacity(details.len());
+
+    for (i, line) in details.lines().enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+
+        if i == 0 {
+            if let (Some(anchor), Some(start)) = (anchor, line.strip_suffix('>')) {
+                output.push_str(&format!("{} id=\"{}\">", start, anchor));
+                continue;
+            }
+        }
+
+        if line.starts_with('#') {
+            let level = line.chars().take_while(|&c| c == '#').count();
+            output.push_str(&"#".repeat((level + depth + 1).min(6)));
+            output.push_str(&line[level..]);
+        } else {
+            output.push_str(&LinkedNames(line, anchors).to_string());
+        }
+    }
+
+    output
+}
+
+/// A line of markdown with the names of journal entries, which appear in `code` formatting,
+/// replaced with links to them.
+struct LinkedNames<'a>(&'a str, &'a HashMap<String, String>);
+
+impl<'a> fmt::Display for LinkedNames<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self(line, anchors) = self;
+
+        if line.matches('`').count() % 2 != 0 {
+            return write!(f, "{}", line);
+        }
+
+        // Every second part of the line falls between a pair of backticks.
+        for (i, part) in line.split('`').enumerate() {
+            if i % 2 == 0 {
+                write!(f, "{}", part)?;
+            } else if let Some(anchor) = anchors.get(&part.to_lowercase()) {
+                write!(f, "[{}](#{})", part, anchor)?;
+            } else {
+                write!(f, "`{}`", part)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn section_test() {
+        let anchors = HashMap::from([
+            ("waterdeep".to_string(), "waterdeep".to_string()),
+            (
+                "the yawning portal".to_string(),
+                "the-yawning-portal".to_string(),
+            ),
+        ]);
+
+        assert_eq!(
+            "<div class=\"thing-box place\" id=\"the-yawning-portal\">\n\n### The Yawning Portal\n*inn*\n\n**Location:** 🏙 [Waterdeep](#waterdeep) (city), `Neverwinter`\n\n#### Notes\n\n</div>",
+            section(
+                "<div class=\"thing-box place\">\n\n# The Yawning Portal\n*inn*\n\n**Location:** 🏙 `Waterdeep` (city), `Neverwinter`\n\n## Notes\n\n</div>",
+                1,
+                anchors.get("the yawning portal"),
+                &anchors,
+            ),
+        );
+    }
+
+    #[test]
+    fn section_test_deep() {
+        assert_eq!(
+            "###### Foo\n###### Notes",
+            section("# Foo\n## Notes", 5, None, &HashMap::new()),
+        );
+    }
+
+    #[test]
+    fn anchors_test() {
+        let things: Vec<Thing> = [
+            "The Yawning Portal",
+            "Yawning Portal, The",
+            "Foo's",
+            "Foo S",
+        ]
+        .iter()
+        .map(|name| {
+            crate::world::Place {
+                name: name.to_string().into(),
+                ..Default::default()
+            }
+            .into()
+        })
+        .collect();
+
+        assert_eq!(
+            HashMap::from([
+                (
+                    "the yawning portal".to_string(),
+                    "the-yawning-portal".to_string()
+                ),
+                (
+                    "yawning portal, the".to_string(),
+                    "yawning-portal-the".to_string()
+                ),
+                ("foo's".to_string(), "foo-s".to_string()),
+                ("foo s".to_string(), "foo-s-2".to_string()),
+            ]),
+            anchors(&things),
+        );
+    }
+
+    #[test]
+    fn to_html_test() {
+        assert_eq!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Journal</title>\n</head>\n<body>\n<h1>Journal</h1>\n<p><em>As of day 1.</em></p>\n</body>\n</html>\n",
+            to_html("# Journal\n\n*As of day 1.*"),
+        );
+    }
+}
@@ -1,9 +1,16 @@
 pub mod backup;
+pub mod spreadsheet;
+pub mod sync;
 
 pub use command::StorageCommand;
 pub use data_store::{DataStore, MemoryDataStore, NullDataStore};
 pub use repository::{Change, Error as RepositoryError, KeyValue, Repository};
+#[cfg(any(test, feature = "test-utils"))]
+pub use test_utils::test_data_store;
 
 mod command;
 mod data_store;
+mod document;
 mod repository;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
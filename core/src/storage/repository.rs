@@ -1,34 +1,54 @@
+use crate::combat::Combat;
+use crate::log::LogEntry;
+use crate::storage::sync::{self, Operation, SyncChange, SyncState, SYNC_STATE_KEY};
 use crate::storage::{DataStore, MemoryDataStore};
-use crate::time::Time;
+use crate::time::{Bookmark, Calendar, Effect, ScheduledEvent, Time};
 use crate::utils::CaseInsensitiveStr;
-use crate::world::{Npc, NpcRelations, Place, PlaceRelations, Thing, ThingRelations};
+use crate::weather::Weather;
+use crate::world::{
+    Npc, NpcRelations, Place, PlaceRelations, PlaceUuid, Thing, ThingQuery, ThingRelations,
+};
 use crate::Uuid;
 use futures::join;
-use std::collections::VecDeque;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 
 const RECENT_MAX_LEN: usize = 100;
 const UNDO_HISTORY_LEN: usize = 10;
 
+/// The key under which the undo and redo history is persisted in the data store, if enabled.
+const UNDO_HISTORY_KEY: &str = "undo_history";
+
 pub struct Repository {
     data_store: Box<dyn DataStore>,
     data_store_enabled: bool,
+    persist_undo_history: bool,
     recent: VecDeque<Thing>,
-    redo_change: Option<Change>,
+    redo_history: Vec<Change>,
+    sync: Option<SyncState>,
     undo_history: VecDeque<Change>,
+    undo_history_len: usize,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Change {
     /// Create a new thing and store it in recent entries.
     ///
     /// Reverse: Delete { uuid: None, .. }
-    Create { thing: Thing },
+    Create {
+        #[serde(with = "thing_with_locks")]
+        thing: Thing,
+    },
 
     /// Create a new thing and store it in the journal.
     ///
     /// Reverse: Delete { uuid: Some(_), .. }
-    CreateAndSave { thing: Thing },
+    CreateAndSave {
+        #[serde(with = "thing_with_locks")]
+        thing: Thing,
+    },
 
     /// Delete a thing from recent or journal.
     ///
@@ -41,6 +61,8 @@ pub enum Change {
     Edit {
         name: String,
         uuid: Option<Uuid>,
+
+        #[serde(with = "thing_with_locks")]
         diff: Thing,
     },
 
@@ -50,6 +72,8 @@ pub enum Change {
     EditAndUnsave {
         name: String,
         uuid: Uuid,
+
+        #[serde(with = "thing_with_locks")]
         diff: Thing,
     },
 
@@ -63,10 +87,34 @@ pub enum Change {
     /// Reverse: Save
     Unsave { name: String, uuid: Uuid },
 
+    /// Rename a thing in recent or journal. Unlike editing the name, this refuses to reuse a name
+    /// that's already taken.
+    ///
+    /// Reverse: Rename
+    Rename {
+        name: String,
+        uuid: Option<Uuid>,
+        new_name: String,
+    },
+
     /// Set a value in the key-value store.
     ///
     /// Reverse: SetKeyValue
     SetKeyValue { key_value: KeyValue },
+
+    /// Apply several changes as a unit, eg. a town along with its inn and innkeeper. If any of
+    /// them fails, the ones already applied are rolled back. A batch occupies a single entry in
+    /// the undo history.
+    ///
+    /// Reverse: Batch, containing the reverse of each change in reverse order
+    Batch { changes: Vec<Change> },
+}
+
+/// The undo and redo history as persisted in the data store.
+#[derive(Deserialize, Serialize)]
+struct UndoHistory {
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
 }
 
 pub struct DisplayUndo<'a>(&'a Change);
@@ -81,9 +129,16 @@ pub enum Error {
     NotFound,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum KeyValue {
+    Bookmarks(Option<Vec<Bookmark>>),
+    Calendar(Option<Calendar>),
+    Combat(Option<Combat>),
+    Effects(Option<Vec<Effect>>),
+    Log(Option<Vec<LogEntry>>),
+    Schedule(Option<Vec<ScheduledEvent>>),
     Time(Option<Time>),
+    Weather(Option<Weather>),
 }
 
 impl Repository {
@@ -91,20 +146,51 @@ impl Repository {
         Self {
             data_store: Box::new(data_store),
             data_store_enabled: false,
+            persist_undo_history: false,
             recent: VecDeque::default(),
-            redo_change: None,
+            redo_history: Vec::new(),
+            sync: None,
             undo_history: VecDeque::default(),
+            undo_history_len: UNDO_HISTORY_LEN,
         }
     }
 
     pub async fn init(&mut self) {
         if self.data_store.health_check().await.is_ok() {
             self.data_store_enabled = true;
+
+            if self.persist_undo_history {
+                self.load_undo_history().await;
+            }
+
+            self.sync = self
+                .data_store
+                .get_value(SYNC_STATE_KEY)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|value| serde_json::from_str(&value).ok());
         } else {
             self.data_store = Box::<MemoryDataStore>::default();
         }
     }
 
+    /// Set the number of changes that can be undone, which must be at least 1. Older changes are
+    /// forgotten.
+    pub fn set_undo_history_len(&mut self, len: usize) {
+        self.undo_history_len = len.max(1);
+
+        while self.undo_history.len() > self.undo_history_len {
+            self.undo_history.pop_front();
+        }
+    }
+
+    /// Keep the undo and redo history in the data store so that it survives a reload. This must
+    /// be set before calling `init()`.
+    pub fn set_persist_undo_history(&mut self, persist: bool) {
+        self.persist_undo_history = persist;
+    }
+
     pub async fn get_by_change(&self, change: &Change) -> Result<Thing, Error> {
         let (name, uuid) = match change {
             Change::Create { thing } | Change::CreateAndSave { thing } => {
@@ -120,11 +206,23 @@ impl Repository {
             }
             | Change::Edit {
                 uuid: Some(uuid), ..
-            } => (None, Some(uuid)),
-            Change::Delete { name, .. } | Change::Edit { name, .. } | Change::Save { name } => {
-                (Some(name), None)
             }
+            | Change::Rename {
+                uuid: Some(uuid), ..
+            } => (None, Some(uuid)),
+            Change::Delete { name, .. }
+            | Change::Edit { name, .. }
+            | Change::Rename { name, .. }
+            | Change::Save { name } => (Some(name), None),
             Change::SetKeyValue { .. } => (None, None),
+            Change::Batch { changes } => {
+                for change in changes {
+                    if let Ok(thing) = Box::pin(self.get_by_change(change)).await {
+                        return Ok(thing);
+                    }
+                }
+                (None, None)
+            }
         };
 
         if let Some(uuid) = uuid {
@@ -182,13 +280,21 @@ impl Repository {
             }
         };
 
+        let calendar = self
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.calendar());
+
         match thing {
             Thing::Npc(Npc { .. }) => Ok(NpcRelations {
                 location: locations,
+                calendar,
             }
             .into()),
             Thing::Place(Place { .. }) => Ok(PlaceRelations {
                 location: locations,
+                calendar,
             }
             .into()),
         }
@@ -230,6 +336,43 @@ impl Repository {
             .map_err(|_| Error::DataStoreFailed)
     }
 
+    pub async fn query_journal(&self, query: &ThingQuery) -> Result<Vec<Thing>, Error> {
+        self.data_store
+            .get_things_by_query(query)
+            .await
+            .map_err(|_| Error::DataStoreFailed)
+    }
+
+    /// Get everything in the journal located within a place, including the contents of the
+    /// places within it, from the outside in. Anything other than a place has no contents.
+    pub async fn get_contents_recursive(&self, thing: &Thing) -> Result<Vec<Thing>, Error> {
+        let mut seen: HashSet<Uuid> = thing.uuid().into_iter().copied().collect();
+        let mut queue: VecDeque<PlaceUuid> = thing
+            .place()
+            .and_then(|place| place.uuid.clone())
+            .into_iter()
+            .collect();
+        let mut contents = Vec::new();
+
+        while let Some(location_uuid) = queue.pop_front() {
+            let things = self
+                .query_journal(&ThingQuery {
+                    location_uuid: Some(location_uuid),
+                    ..Default::default()
+                })
+                .await?;
+
+            for thing in things {
+                if thing.uuid().is_some_and(|uuid| seen.insert(*uuid)) {
+                    queue.extend(thing.place().and_then(|place| place.uuid.clone()));
+                    contents.push(thing);
+                }
+            }
+        }
+
+        Ok(contents)
+    }
+
     pub async fn get_by_name(&self, name: &str) -> Result<Thing, Error> {
         let (saved_thing, recent_thing) = join!(self.data_store.get_thing_by_name(name), async {
             self.recent()
@@ -255,59 +398,156 @@ impl Repository {
         }
     }
 
+    /// Apply a change and add its reverse to the undo history. Making a new change forgets any
+    /// changes that could have been redone.
     pub async fn modify(&mut self, change: Change) -> Result<Option<Thing>, (Change, Error)> {
         let undo_change = self.modify_without_undo(change).await?;
         let thing = self.get_by_change(&undo_change).await.ok();
 
-        while self.undo_history.len() >= UNDO_HISTORY_LEN {
-            self.undo_history.pop_front();
-        }
-        self.undo_history.push_back(undo_change);
+        self.push_undo(undo_change);
+        self.redo_history.clear();
+        self.save_undo_history().await;
 
         Ok(thing)
     }
 
+    /// Apply several changes independently and add the reverse of those that succeeded to the
+    /// undo history as a single entry. Unlike `Change::Batch`, a change that fails doesn't prevent
+    /// the others from being applied.
+    pub async fn modify_each(&mut self, changes: Vec<Change>) -> Vec<Result<(), (Change, Error)>> {
+        let mut undo_changes = Vec::new();
+        let mut results = Vec::with_capacity(changes.len());
+
+        for change in changes {
+            results.push(
+                self.modify_without_undo(change)
+                    .await
+                    .map(|undo_change| undo_changes.push(undo_change)),
+            );
+        }
+
+        if !undo_changes.is_empty() {
+            undo_changes.reverse();
+            self.push_undo(Change::Batch {
+                changes: undo_changes,
+            });
+            self.redo_history.clear();
+            self.save_undo_history().await;
+        }
+
+        results
+    }
+
     pub async fn undo(&mut self) -> Option<Result<Option<Thing>, Error>> {
         if let Some(change) = self.undo_history.pop_back() {
-            match self.modify_without_undo(change).await {
+            let result = match self.modify_without_undo(change).await {
                 Ok(redo_change) => {
                     let thing = self.get_by_change(&redo_change).await.ok();
-                    self.redo_change = Some(redo_change);
+                    self.redo_history.push(redo_change);
                     Some(Ok(thing))
                 }
                 Err((undo_change, e)) => {
                     self.undo_history.push_back(undo_change);
                     Some(Err(e))
                 }
-            }
+            };
+
+            self.save_undo_history().await;
+            result
         } else {
             None
         }
     }
 
+    /// The changes that can be undone, most recent first.
     pub fn undo_history(&self) -> impl Iterator<Item = &Change> {
         self.undo_history.iter().rev()
     }
 
     pub async fn redo(&mut self) -> Option<Result<Option<Thing>, Error>> {
-        if let Some(change) = self.redo_change.take() {
-            match self.modify(change).await {
-                Ok(option_thing) => Some(Ok(option_thing)),
+        if let Some(change) = self.redo_history.pop() {
+            let result = match self.modify_without_undo(change).await {
+                Ok(undo_change) => {
+                    let thing = self.get_by_change(&undo_change).await.ok();
+                    self.push_undo(undo_change);
+                    Some(Ok(thing))
+                }
                 Err((redo_change, e)) => {
-                    self.redo_change = Some(redo_change);
+                    self.redo_history.push(redo_change);
                     Some(Err(e))
                 }
-            }
+            };
+
+            self.save_undo_history().await;
+            result
         } else {
             None
         }
     }
 
+    /// The next change to be redone, if any.
     pub fn get_redo(&self) -> Option<&Change> {
-        self.redo_change.as_ref()
+        self.redo_history.last()
+    }
+
+    /// The changes that can be redone, next first.
+    pub fn redo_history(&self) -> impl Iterator<Item = &Change> {
+        self.redo_history.iter().rev()
+    }
+
+    fn push_undo(&mut self, change: Change) {
+        while self.undo_history.len() >= self.undo_history_len {
+            self.undo_history.pop_front();
+        }
+        self.undo_history.push_back(change);
+    }
+
+    async fn load_undo_history(&mut self) {
+        if let Some(history) = self
+            .data_store
+            .get_value(UNDO_HISTORY_KEY)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|value| serde_json::from_str::<UndoHistory>(&value).ok())
+        {
+            self.undo_history = history.undo;
+            self.redo_history = history.redo;
+            self.set_undo_history_len(self.undo_history_len);
+        }
     }
 
+    /// Persisting the history is best-effort: if it fails, the worst case is that it's not
+    /// available after a reload.
+    async fn save_undo_history(&mut self) {
+        if !self.persist_undo_history || !self.data_store_enabled {
+            return;
+        }
+
+        if let Ok(value) = serde_json::to_string(&UndoHistory {
+            undo: self.undo_history.clone(),
+            redo: self.redo_history.clone(),
+        }) {
+            self.data_store
+                .set_value(UNDO_HISTORY_KEY, &value)
+                .await
+                .ok();
+        }
+    }
+
+    /// Apply a change without touching the undo history, returning its reverse. If syncing is set
+    /// up, the change is queued to be sent to the server.
     pub async fn modify_without_undo(&mut self, change: Change) -> Result<Change, (Change, Error)> {
+        let result = self.apply_change(change).await;
+
+        if self.sync.as_mut().is_some_and(|state| state.commit()) {
+            self.save_sync_state().await;
+        }
+
+        result
+    }
+
+    async fn apply_change(&mut self, change: Change) -> Result<Change, (Change, Error)> {
         match change {
             Change::Create { thing } => self
                 .create_thing(thing)
@@ -436,11 +676,59 @@ impl Repository {
                 .await
                 .map(|name| Change::Save { name })
                 .map_err(|(_, e)| (Change::Unsave { name, uuid }, e)),
+            Change::Rename {
+                name,
+                uuid,
+                new_name,
+            } => match self.rename_thing(&name, uuid.as_ref(), &new_name).await {
+                Ok(old_name) => Ok(Change::Rename {
+                    name: new_name,
+                    uuid,
+                    new_name: old_name,
+                }),
+                Err(e) => Err((
+                    Change::Rename {
+                        name,
+                        uuid,
+                        new_name,
+                    },
+                    e,
+                )),
+            },
             Change::SetKeyValue { key_value } => self
                 .set_key_value(&key_value)
                 .await
                 .map(|old_kv| Change::SetKeyValue { key_value: old_kv })
                 .map_err(|e| (Change::SetKeyValue { key_value }, e)),
+            Change::Batch { changes } => {
+                let original_changes = changes.clone();
+                let mut undo_changes = Vec::with_capacity(changes.len());
+
+                for change in changes {
+                    match Box::pin(self.apply_change(change)).await {
+                        Ok(undo_change) => undo_changes.push(undo_change),
+                        Err((_, e)) => {
+                            // Roll back in reverse order. There's nothing more to be done if the
+                            // rollback itself fails, so report the original error either way.
+                            while let Some(undo_change) = undo_changes.pop() {
+                                Box::pin(self.apply_change(undo_change)).await.ok();
+                            }
+
+                            return Err((
+                                Change::Batch {
+                                    changes: original_changes,
+                                },
+                                e,
+                            ));
+                        }
+                    }
+                }
+
+                undo_changes.reverse();
+                Ok(Change::Batch {
+                    changes: undo_changes,
+                })
+            }
         }
     }
 
@@ -448,6 +736,13 @@ impl Repository {
         let value_str = self.data_store.get_value(key.key_raw()).await;
 
         match key {
+            KeyValue::Bookmarks(_) => value_str.and_then(decode).map(KeyValue::Bookmarks),
+            KeyValue::Calendar(_) => value_str.and_then(decode).map(KeyValue::Calendar),
+            KeyValue::Combat(_) => value_str.and_then(decode).map(KeyValue::Combat),
+            KeyValue::Effects(_) => value_str.and_then(decode).map(KeyValue::Effects),
+            KeyValue::Log(_) => value_str.and_then(decode).map(KeyValue::Log),
+            KeyValue::Schedule(_) => value_str.and_then(decode).map(KeyValue::Schedule),
+            KeyValue::Weather(_) => value_str.and_then(decode).map(KeyValue::Weather),
             KeyValue::Time(_) => value_str
                 .and_then(|o| o.map(|s| s.parse()).transpose())
                 .map(KeyValue::Time),
@@ -459,6 +754,153 @@ impl Repository {
         self.data_store_enabled
     }
 
+    /// Write any changes the data store is holding in memory through to permanent storage.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.data_store
+            .flush()
+            .await
+            .map_err(|_| Error::DataStoreFailed)
+    }
+
+    /// Whether changes to the journal are being recorded to be synced with other devices.
+    pub fn sync_enabled(&self) -> bool {
+        self.sync.is_some()
+    }
+
+    pub(super) fn sync_state(&self) -> Option<&SyncState> {
+        self.sync.as_ref()
+    }
+
+    pub(super) fn sync_state_mut(&mut self) -> Option<&mut SyncState> {
+        self.sync.as_mut()
+    }
+
+    /// Start recording changes to be synced, beginning with the existing contents of the journal
+    /// and key-value store.
+    pub(super) async fn enable_sync(&mut self) -> Result<(), Error> {
+        let mut state = SyncState::new();
+
+        for thing in self.journal().await? {
+            state.record_put(None, &thing);
+        }
+
+        for key in [
+            KeyValue::Bookmarks(None),
+            KeyValue::Calendar(None),
+            KeyValue::Combat(None),
+            KeyValue::Effects(None),
+            KeyValue::Schedule(None),
+            KeyValue::Time(None),
+            KeyValue::Weather(None),
+        ] {
+            let key_value = self.get_key_value(&key).await?;
+            if key_value.key_value_raw().1.is_some() {
+                state.record_key_value(&key_value);
+            }
+        }
+
+        state.commit();
+        self.sync = Some(state);
+        self.save_sync_state().await;
+        Ok(())
+    }
+
+    /// Apply a change received from another device, skipping whatever has since been overwritten
+    /// on this one. Changes from other devices can't be undone.
+    ///
+    /// The change is resolved against a copy of the sync state, which only replaces the original
+    /// once every operation has been written. If writing fails partway, the change is received
+    /// again in full on the next sync.
+    pub(super) async fn apply_sync_change(&mut self, change: SyncChange) -> Result<(), Error> {
+        let Some(mut state) = self.sync.clone() else {
+            return Ok(());
+        };
+        let operations = state.receive(change);
+
+        for operation in operations {
+            match operation {
+                Operation::Put { uuid, fields } => {
+                    let existing = match self.get_by_uuid(&uuid).await {
+                        Ok(thing) => Some(thing),
+                        Err(Error::NotFound) => None,
+                        Err(e) => return Err(e),
+                    };
+
+                    let Some(thing) = sync::apply_fields(&uuid, existing.as_ref(), &fields)? else {
+                        continue;
+                    };
+                    let thing = self.rename_if_taken(thing).await?;
+
+                    if existing.is_some() {
+                        self.data_store.edit_thing(&thing).await
+                    } else {
+                        self.data_store.save_thing(&thing).await
+                    }
+                    .map_err(|()| Error::DataStoreFailed)?;
+                }
+                Operation::Delete { uuid } => match self.get_by_uuid(&uuid).await {
+                    Ok(_) => self
+                        .data_store
+                        .delete_thing_by_uuid(&uuid)
+                        .await
+                        .map_err(|()| Error::DataStoreFailed)?,
+                    Err(Error::NotFound) => {}
+                    Err(e) => return Err(e),
+                },
+                Operation::SetKeyValue { key, value } => match value {
+                    Some(value) => self.data_store.set_value(&key, &value).await,
+                    None => self.data_store.delete_value(&key).await,
+                }
+                .map_err(|()| Error::DataStoreFailed)?,
+            }
+        }
+
+        self.sync = Some(state);
+        Ok(())
+    }
+
+    /// Give an entry received from another device a name like "Bob (2)" if a different entry in
+    /// the journal already has its name. The new name stays on this device: each device settles
+    /// its own clashes, since sending the renames back and forth might never come to rest.
+    async fn rename_if_taken(&self, mut thing: Thing) -> Result<Thing, Error> {
+        let Some(name) = thing.name().value().cloned() else {
+            return Ok(thing);
+        };
+
+        let mut new_name = name.clone();
+        for i in 2.. {
+            match self.data_store.get_thing_by_name(&new_name).await {
+                Ok(Some(other)) if other.uuid() != thing.uuid() => {
+                    new_name = format!("{} ({})", name, i);
+                }
+                Ok(_) => break,
+                Err(()) => return Err(Error::DataStoreFailed),
+            }
+        }
+
+        if new_name != name {
+            thing.set_name(new_name);
+        }
+
+        Ok(thing)
+    }
+
+    /// Like the undo history, the sync state is saved on a best-effort basis. If it fails, changes
+    /// made since it was last saved won't be sent.
+    pub(super) async fn save_sync_state(&mut self) {
+        if !self.data_store_enabled {
+            return;
+        }
+
+        if let Some(value) = self
+            .sync
+            .as_ref()
+            .and_then(|state| serde_json::to_string(state).ok())
+        {
+            self.data_store.set_value(SYNC_STATE_KEY, &value).await.ok();
+        }
+    }
+
     async fn set_key_value(&mut self, key_value: &KeyValue) -> Result<KeyValue, Error> {
         let old_key_value = self.get_key_value(key_value).await?;
 
@@ -466,8 +908,13 @@ impl Repository {
             (key, Some(value)) => self.data_store.set_value(key, &value).await,
             (key, None) => self.data_store.delete_value(key).await,
         }
-        .map(|_| old_key_value)
-        .map_err(|_| Error::DataStoreFailed)
+        .map_err(|_| Error::DataStoreFailed)?;
+
+        if let Some(state) = &mut self.sync {
+            state.record_key_value(key_value);
+        }
+
+        Ok(old_key_value)
     }
 
     fn push_recent(&mut self, thing: Thing) {
@@ -542,7 +989,12 @@ impl Repository {
             self.data_store.get_thing_by_uuid(uuid).await,
             self.data_store.delete_thing_by_uuid(uuid).await,
         ) {
-            (Ok(Some(thing)), Ok(())) => Ok(thing),
+            (Ok(Some(thing)), Ok(())) => {
+                if let Some(state) = &mut self.sync {
+                    state.record_delete(uuid);
+                }
+                Ok(thing)
+            }
             (Ok(Some(thing)), Err(())) => Err((Some(thing), Error::DataStoreFailed)),
             (Ok(None), _) => Err((None, Error::NotFound)),
             (Err(_), _) => Err((None, Error::DataStoreFailed)),
@@ -571,7 +1023,12 @@ impl Repository {
         };
 
         match self.data_store.save_thing(&thing).await {
-            Ok(()) => Ok(uuid),
+            Ok(()) => {
+                if let Some(state) = &mut self.sync {
+                    state.record_put(None, &thing);
+                }
+                Ok(uuid)
+            }
             Err(()) => {
                 thing.clear_uuid();
                 Err((thing, Error::DataStoreFailed))
@@ -600,6 +1057,65 @@ impl Repository {
         }
     }
 
+    /// Rename a thing, returning its previous name. Changing the case of a thing's own name is
+    /// allowed, but taking the name of a different thing is not.
+    async fn rename_thing(
+        &mut self,
+        name: &str,
+        uuid: Option<&Uuid>,
+        new_name: &str,
+    ) -> Result<String, Error> {
+        if new_name.trim().is_empty() {
+            return Err(Error::MissingName);
+        }
+
+        let mut thing = if let Some(uuid) = uuid {
+            self.get_by_uuid(uuid).await?
+        } else {
+            self.get_by_name(name).await?
+        };
+
+        let old_name = thing.name().to_string();
+
+        match self.get_by_name(new_name).await {
+            Ok(other) if !other.name().value().is_some_and(|s| s.eq_ci(&old_name)) => {
+                return Err(Error::NameAlreadyExists);
+            }
+            Ok(_) | Err(Error::NotFound) => {}
+            Err(e) => return Err(e),
+        }
+
+        let before = thing.clone();
+        thing.set_name(new_name.to_string());
+
+        if thing.uuid().is_some() {
+            self.edit_journal_thing(&before, &thing)
+                .await
+                .map_err(|_| Error::DataStoreFailed)?;
+        } else if let Some(recent_thing) = self
+            .recent
+            .iter_mut()
+            .find(|t| t.name().value().is_some_and(|s| s.eq_ci(&old_name)))
+        {
+            *recent_thing = thing;
+        } else {
+            return Err(Error::NotFound);
+        }
+
+        Ok(old_name)
+    }
+
+    /// Write an edited journal entry to the data store, noting what changed for syncing.
+    async fn edit_journal_thing(&mut self, before: &Thing, thing: &Thing) -> Result<(), ()> {
+        self.data_store.edit_thing(thing).await?;
+
+        if let Some(state) = &mut self.sync {
+            state.record_put(Some(before), thing);
+        }
+
+        Ok(())
+    }
+
     async fn edit_thing_by_uuid(
         &mut self,
         uuid: &Uuid,
@@ -607,13 +1123,14 @@ impl Repository {
     ) -> Result<Thing, (Thing, Error)> {
         match self.data_store.get_thing_by_uuid(uuid).await {
             Ok(Some(mut thing)) => {
+                let before = thing.clone();
                 if thing.try_apply_diff(&mut diff).is_err() {
                     // This fails when the thing types don't match, eg. applying an Npc diff to a
                     // Place.
                     return Err((diff, Error::NotFound));
                 }
 
-                match self.data_store.edit_thing(&thing).await {
+                match self.edit_journal_thing(&before, &thing).await {
                     Ok(()) => Ok(diff),
                     Err(()) => Err((diff, Error::DataStoreFailed)),
                 }
@@ -630,11 +1147,12 @@ impl Repository {
     ) -> Result<Change, (Thing, Error)> {
         let data_store_failed = match self.data_store.get_thing_by_name(name).await {
             Ok(Some(mut thing)) => {
+                let before = thing.clone();
                 if thing.try_apply_diff(&mut diff).is_err() {
                     return Err((diff, Error::NotFound));
                 }
 
-                return match self.data_store.edit_thing(&thing).await {
+                return match self.edit_journal_thing(&before, &thing).await {
                     Ok(()) => Ok(Change::Edit {
                         name: thing.name().to_string(),
                         uuid: thing.uuid().cloned(),
@@ -686,7 +1204,14 @@ impl Repository {
 impl KeyValue {
     pub const fn key_raw(&self) -> &'static str {
         match self {
+            Self::Bookmarks(_) => "bookmarks",
+            Self::Calendar(_) => "calendar",
+            Self::Combat(_) => "combat",
+            Self::Effects(_) => "effects",
+            Self::Log(_) => "log",
+            Self::Schedule(_) => "schedule",
             Self::Time(_) => "time",
+            Self::Weather(_) => "weather",
         }
     }
 
@@ -694,19 +1219,91 @@ impl KeyValue {
         (
             self.key_raw(),
             match self {
+                Self::Bookmarks(bookmarks) => bookmarks
+                    .as_ref()
+                    .and_then(|b| serde_json::to_string(b).ok()),
+                Self::Calendar(calendar) => calendar
+                    .as_ref()
+                    .and_then(|c| serde_json::to_string(c).ok()),
+                Self::Combat(combat) => combat.as_ref().and_then(|c| serde_json::to_string(c).ok()),
+                Self::Effects(effects) => {
+                    effects.as_ref().and_then(|e| serde_json::to_string(e).ok())
+                }
+                Self::Log(log) => log.as_ref().and_then(|l| serde_json::to_string(l).ok()),
+                Self::Schedule(events) => {
+                    events.as_ref().and_then(|e| serde_json::to_string(e).ok())
+                }
                 Self::Time(time) => time.as_ref().map(|t| t.display_short().to_string()),
+                Self::Weather(weather) => {
+                    weather.as_ref().and_then(|w| serde_json::to_string(w).ok())
+                }
             },
         )
     }
 
-    pub const fn time(self) -> Option<Time> {
-        #[allow(irrefutable_let_patterns)]
+    pub fn bookmarks(self) -> Option<Vec<Bookmark>> {
+        if let Self::Bookmarks(bookmarks) = self {
+            bookmarks
+        } else {
+            None
+        }
+    }
+
+    pub fn calendar(self) -> Option<Calendar> {
+        if let Self::Calendar(calendar) = self {
+            calendar
+        } else {
+            None
+        }
+    }
+
+    pub fn combat(self) -> Option<Combat> {
+        if let Self::Combat(combat) = self {
+            combat
+        } else {
+            None
+        }
+    }
+
+    pub fn effects(self) -> Option<Vec<Effect>> {
+        if let Self::Effects(effects) = self {
+            effects
+        } else {
+            None
+        }
+    }
+
+    pub fn log(self) -> Option<Vec<LogEntry>> {
+        if let Self::Log(log) = self {
+            log
+        } else {
+            None
+        }
+    }
+
+    pub fn schedule(self) -> Option<Vec<ScheduledEvent>> {
+        if let Self::Schedule(events) = self {
+            events
+        } else {
+            None
+        }
+    }
+
+    pub fn time(self) -> Option<Time> {
         if let Self::Time(time) = self {
             time
         } else {
             None
         }
     }
+
+    pub fn weather(self) -> Option<Weather> {
+        if let Self::Weather(weather) = self {
+            weather
+        } else {
+            None
+        }
+    }
 }
 
 impl Change {
@@ -724,9 +1321,14 @@ impl Change {
             Self::Delete { name, .. }
             | Self::Edit { name, .. }
             | Self::EditAndUnsave { name, .. }
+            | Self::Rename { name, .. }
             | Self::Save { name }
             | Self::Unsave { name, .. } => name.to_owned(),
             Self::SetKeyValue { key_value } => key_value.key_raw().to_string(),
+            Self::Batch { changes } => changes
+                .first()
+                .map(|change| change.name())
+                .unwrap_or_default(),
         }
     }
 }
@@ -744,11 +1346,23 @@ impl<'a> fmt::Display for DisplayUndo<'a> {
             Change::Delete { name, .. } => write!(f, "creating {}", name),
             Change::Save { name } => write!(f, "removing {} from journal", name),
             Change::Unsave { name, .. } => write!(f, "saving {} to journal", name),
+            Change::Rename { name, new_name, .. } => {
+                write!(f, "renaming {} to {}", new_name, name)
+            }
 
             // These changes are symmetric, so we can provide the same output in both cases.
             Change::Edit { .. } | Change::EditAndUnsave { .. } | Change::SetKeyValue { .. } => {
                 write!(f, "{}", DisplayRedo(change))
             }
+
+            // The reverse of a batch is stored in reverse order, so flip it back.
+            Change::Batch { changes } => write_list(
+                f,
+                changes
+                    .iter()
+                    .rev()
+                    .map(|change| change.display_undo().to_string()),
+            ),
         }
     }
 }
@@ -766,30 +1380,119 @@ impl<'a> fmt::Display for DisplayRedo<'a> {
             }
             Change::Save { name } => write!(f, "saving {} to journal", name),
             Change::Unsave { name, .. } => write!(f, "removing {} from journal", name),
+            Change::Rename { name, new_name, .. } => {
+                write!(f, "renaming {} to {}", name, new_name)
+            }
             Change::SetKeyValue { key_value } => match key_value {
+                KeyValue::Bookmarks(_) => write!(f, "changing bookmarks"),
+                KeyValue::Calendar(_) => write!(f, "changing the calendar"),
+                KeyValue::Combat(_) => write!(f, "changing the fight"),
+                KeyValue::Effects(_) => write!(f, "changing active effects"),
+                KeyValue::Log(_) => write!(f, "changing the session log"),
+                KeyValue::Schedule(_) => write!(f, "changing the schedule"),
                 KeyValue::Time(_) => write!(f, "changing the time"),
+                KeyValue::Weather(_) => write!(f, "changing the weather"),
             },
+            Change::Batch { changes } => write_list(
+                f,
+                changes
+                    .iter()
+                    .map(|change| change.display_redo().to_string()),
+            ),
         }
     }
 }
 
-impl fmt::Debug for Repository {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
+/// Decode a value that's kept in the data store as JSON.
+fn decode<T: DeserializeOwned>(value: Option<String>) -> Result<Option<T>, ()> {
+    value
+        .map(|s| serde_json::from_str(&s).map_err(|_| ()))
+        .transpose()
+}
+
+/// Write a list of descriptions as a sentence fragment, eg. "creating Foo, creating Bar, and
+/// editing Baz". Long lists, such as those produced by an import, are cut short: "creating Foo,
+/// creating Bar, creating Baz, and 12 more changes".
+fn write_list(
+    f: &mut fmt::Formatter,
+    descriptions: impl Iterator<Item = String>,
+) -> Result<(), fmt::Error> {
+    const MAX_LEN: usize = 5;
+    const SHORTENED_LEN: usize = 3;
+
+    let descriptions: Vec<String> = descriptions.collect();
+
+    match descriptions.as_slice() {
+        [] => Ok(()),
+        [only] => write!(f, "{}", only),
+        [first, second] => write!(f, "{} and {}", first, second),
+        list if list.len() > MAX_LEN => write!(
             f,
-            "Repository {{ data_store_enabled: {:?}, recent: {:?} }}",
-            self.data_store_enabled, self.recent,
-        )
+            "{}, and {} more changes",
+            list[..SHORTENED_LEN].join(", "),
+            list.len() - SHORTENED_LEN,
+        ),
+        [rest @ .., last] => write!(f, "{}, and {}", rest.join(", "), last),
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::storage::data_store::{MemoryDataStore, NullDataStore};
-    use crate::world::npc::{Npc, Species};
-    use crate::world::{Place, PlaceUuid};
-    use async_trait::async_trait;
+/// `Field` doesn't serialize its lock state, but it matters for the things stored in changes: an
+/// unlocked field in a diff is left alone, whereas a locked one is overwritten. The unlocked
+/// fields are stored alongside the thing so that persisted changes behave the same after a reload.
+mod thing_with_locks {
+    use crate::world::Thing;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct ThingWithLocksRef<'a> {
+        thing: &'a Thing,
+        unlocked: Vec<&'static str>,
+    }
+
+    #[derive(Deserialize)]
+    struct ThingWithLocks {
+        thing: Thing,
+
+        #[serde(default)]
+        unlocked: Vec<String>,
+    }
+
+    pub fn serialize<S: Serializer>(thing: &Thing, serializer: S) -> Result<S::Ok, S::Error> {
+        ThingWithLocksRef {
+            thing,
+            unlocked: thing.unlocked_fields(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Thing, D::Error> {
+        let ThingWithLocks {
+            mut thing,
+            unlocked,
+        } = ThingWithLocks::deserialize(deserializer)?;
+        thing.unlock_fields(&unlocked);
+        Ok(thing)
+    }
+}
+
+impl fmt::Debug for Repository {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Repository {{ data_store_enabled: {:?}, recent: {:?} }}",
+            self.data_store_enabled, self.recent,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::storage::data_store::{MemoryDataStore, NullDataStore};
+    use crate::storage::sync::MemorySyncServer;
+    use crate::world::npc::{Npc, Species};
+    use crate::world::{Place, PlaceUuid};
+    use async_trait::async_trait;
     use std::cell::RefCell;
     use std::rc::Rc;
     use tokio_test::block_on;
@@ -986,7 +1689,7 @@ mod test {
                     name: "Odysseus".to_string(),
                     uuid: None,
                 }),
-                repo.redo_change,
+                repo.get_redo().cloned(),
             );
             assert!(block_on(repo.get_by_name("odysseus")).is_ok());
             assert_eq!(1, repo.recent().count());
@@ -1037,7 +1740,7 @@ mod test {
                     name: "Olympus".to_string(),
                     uuid: Some(OLYMPUS_UUID),
                 }),
-                repo.redo_change,
+                repo.get_redo().cloned(),
             );
             assert!(block_on(repo.get_by_uuid(&OLYMPUS_UUID)).is_ok());
             assert_eq!(4, block_on(repo.journal()).unwrap().len());
@@ -1691,7 +2394,7 @@ mod test {
 
     #[test]
     fn change_test_edit_and_unsave_data_store_failed() {
-        let mut repo = Repository::new(TimeBombDataStore::new(7));
+        let mut repo = Repository::new(TimeBombDataStore::new(8));
         populate_repo(&mut repo);
 
         let change = Change::EditAndUnsave {
@@ -1764,7 +2467,7 @@ mod test {
                     }
                     .into(),
                 }),
-                repo.redo_change,
+                repo.get_redo().cloned(),
             );
             assert_eq!(0, repo.recent().count());
         }
@@ -1854,7 +2557,7 @@ mod test {
                 Some(Change::Save {
                     name: "Odysseus".to_string(),
                 }),
-                repo.redo_change,
+                repo.get_redo().cloned(),
             );
             assert_eq!(4, block_on(repo.journal()).unwrap().len());
             assert_eq!(4, block_on(data_store.get_all_the_things()).unwrap().len());
@@ -1965,7 +2668,7 @@ mod test {
                 v => panic!("{:?}", v),
             }
 
-            if let Some(Change::Unsave { ref name, uuid }) = repo.redo_change {
+            if let Some(Change::Unsave { ref name, uuid }) = repo.get_redo().cloned() {
                 assert_eq!("Olympus", name);
                 assert_ne!(OLYMPUS_UUID, uuid);
                 assert!(block_on(repo.get_by_uuid(&uuid)).is_ok());
@@ -2034,7 +2737,7 @@ mod test {
                     }
                     .into(),
                 }),
-                repo.redo_change,
+                repo.get_redo().cloned(),
             );
             assert_eq!(0, block_on(repo.journal()).unwrap().len());
             assert_eq!(0, block_on(data_store.get_all_the_things()).unwrap().len());
@@ -2188,6 +2891,7 @@ mod test {
         match block_on(repo.load_relations(&odysseus)) {
             Ok(ThingRelations::Npc(NpcRelations {
                 location: Some((parent, None)),
+                ..
             })) => {
                 assert_eq!("River Styx", parent.name.value().unwrap());
             }
@@ -2203,6 +2907,7 @@ mod test {
         match block_on(repo.load_relations(&olympus)) {
             Ok(ThingRelations::Place(PlaceRelations {
                 location: Some((parent, Some(grandparent))),
+                ..
             })) => {
                 assert_eq!("Thessaly", parent.name.value().unwrap());
                 assert_eq!("Greece", grandparent.name.value().unwrap());
@@ -2211,6 +2916,59 @@ mod test {
         }
     }
 
+    #[test]
+    fn get_contents_recursive_test() {
+        let repo = repo();
+        let names = |thing: &Thing| -> Vec<String> {
+            block_on(repo.get_contents_recursive(thing))
+                .unwrap()
+                .iter()
+                .map(|thing| thing.name().to_string())
+                .collect()
+        };
+
+        let greece = block_on(repo.get_by_uuid(&GREECE_UUID)).unwrap();
+        assert_eq!(vec!["Thessaly", "Olympus"], names(&greece));
+
+        let olympus = block_on(repo.get_by_uuid(&OLYMPUS_UUID)).unwrap();
+        assert!(names(&olympus).is_empty());
+
+        let odysseus = block_on(repo.get_by_name("Odysseus")).unwrap();
+        assert!(names(&odysseus).is_empty());
+    }
+
+    #[test]
+    fn apply_sync_change_test_data_store_failure() {
+        let journal = |repo: &Repository| {
+            let mut things = block_on(repo.journal()).unwrap();
+            things.sort_by_key(|thing| thing.uuid().cloned());
+            serde_json::to_value(things).unwrap()
+        };
+
+        let server = MemorySyncServer::default();
+        let mut laptop = repo();
+        block_on(sync::sync(&mut laptop, &mut server.clone())).unwrap();
+
+        // Wherever the data store gives out, the next sync fills in whatever was missed.
+        for fuse in 0.. {
+            let data_store = TimeBombDataStore::new(usize::MAX);
+            let t_minus = data_store.t_minus.clone();
+            let mut phone = Repository::new(data_store);
+            block_on(phone.init());
+
+            *t_minus.borrow_mut() = fuse;
+            let result = block_on(sync::sync(&mut phone, &mut server.clone()));
+            *t_minus.borrow_mut() = usize::MAX;
+
+            if result.is_ok() {
+                break;
+            }
+
+            block_on(sync::sync(&mut phone, &mut server.clone())).unwrap();
+            assert_eq!(journal(&laptop), journal(&phone), "{}", fuse);
+        }
+    }
+
     #[test]
     fn debug_test() {
         assert_eq!(
@@ -2233,6 +2991,420 @@ mod test {
         assert_eq!(false, repo.data_store_enabled());
     }
 
+    #[test]
+    fn undo_redo_test_multiple() {
+        let mut repo = empty_repo();
+
+        for name in ["Odysseus", "Penelope", "Telemachus"] {
+            block_on(
+                repo.modify(Change::Create {
+                    thing: Npc {
+                        name: name.into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                }),
+            )
+            .unwrap();
+        }
+
+        assert!(block_on(repo.undo()).unwrap().is_ok());
+        assert!(block_on(repo.undo()).unwrap().is_ok());
+        assert_eq!(1, repo.recent().count());
+        assert_eq!(2, repo.redo_history().count());
+        assert_eq!(
+            "creating Penelope",
+            repo.get_redo().unwrap().display_redo().to_string(),
+        );
+
+        assert!(block_on(repo.redo()).unwrap().is_ok());
+        assert!(block_on(repo.redo()).unwrap().is_ok());
+        assert!(block_on(repo.redo()).is_none());
+        assert_eq!(3, repo.recent().count());
+        assert_eq!(3, repo.undo_history().count());
+
+        block_on(repo.undo()).unwrap().unwrap();
+        block_on(
+            repo.modify(Change::Create {
+                thing: Npc {
+                    name: "Argos".into(),
+                    ..Default::default()
+                }
+                .into(),
+            }),
+        )
+        .unwrap();
+        assert!(repo.get_redo().is_none());
+    }
+
+    #[test]
+    fn change_test_batch_success() {
+        let mut repo = empty_repo();
+
+        let result = block_on(repo.modify(Change::Batch {
+            changes: vec![
+                Change::CreateAndSave {
+                    thing: Place {
+                        name: "Ithaca".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+                Change::Create {
+                    thing: Npc {
+                        name: "Penelope".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+                Change::Create {
+                    thing: Npc {
+                        name: "Telemachus".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+            ],
+        }));
+
+        assert!(result.unwrap().is_some());
+        assert_eq!(1, block_on(repo.journal()).unwrap().len());
+        assert_eq!(2, repo.recent().count());
+        assert_eq!(1, repo.undo_history().count());
+        assert_eq!(
+            "creating Ithaca, creating Penelope, and creating Telemachus",
+            repo.undo_history()
+                .next()
+                .unwrap()
+                .display_undo()
+                .to_string(),
+        );
+
+        assert!(block_on(repo.undo()).unwrap().is_ok());
+        assert_eq!(0, block_on(repo.journal()).unwrap().len());
+        assert_eq!(0, repo.recent().count());
+        assert_eq!(
+            "creating Ithaca, creating Penelope, and creating Telemachus",
+            repo.get_redo().unwrap().display_redo().to_string(),
+        );
+
+        assert!(block_on(repo.redo()).unwrap().is_ok());
+        assert_eq!(1, block_on(repo.journal()).unwrap().len());
+        assert_eq!(2, repo.recent().count());
+    }
+
+    #[test]
+    fn modify_each_test() {
+        let mut repo = repo();
+
+        let results = block_on(repo.modify_each(vec![
+            Change::CreateAndSave {
+                thing: Npc {
+                    name: "Penelope".into(),
+                    ..Default::default()
+                }
+                .into(),
+            },
+            Change::CreateAndSave {
+                thing: Npc {
+                    name: "Odysseus".into(),
+                    ..Default::default()
+                }
+                .into(),
+            },
+            Change::Delete {
+                name: "Olympus".to_string(),
+                uuid: Some(OLYMPUS_UUID),
+            },
+        ]));
+
+        assert!(results[0].is_ok());
+        assert_eq!(
+            Some(&Error::NameAlreadyExists),
+            results[1].as_ref().err().map(|(_, e)| e),
+        );
+        assert!(results[2].is_ok());
+
+        assert!(block_on(repo.get_by_name("Penelope")).is_ok());
+        assert_eq!(Err(Error::NotFound), block_on(repo.get_by_name("Olympus")));
+        assert_eq!(1, repo.undo_history().count());
+        assert_eq!(
+            "creating Penelope and deleting Olympus",
+            repo.undo_history()
+                .next()
+                .unwrap()
+                .display_undo()
+                .to_string(),
+        );
+
+        assert!(block_on(repo.undo()).unwrap().is_ok());
+        assert_eq!(Err(Error::NotFound), block_on(repo.get_by_name("Penelope")));
+        assert!(block_on(repo.get_by_name("Olympus")).is_ok());
+        assert_eq!(0, repo.undo_history().count());
+    }
+
+    #[test]
+    fn modify_each_test_nothing_succeeded() {
+        let mut repo = repo();
+
+        let results = block_on(repo.modify_each(vec![Change::Delete {
+            name: "Nobody".to_string(),
+            uuid: None,
+        }]));
+
+        assert!(results[0].is_err());
+        assert_eq!(0, repo.undo_history().count());
+    }
+
+    #[test]
+    fn change_test_batch_display_long() {
+        let batch = Change::Batch {
+            changes: ["A", "B", "C", "D", "E", "F"]
+                .iter()
+                .map(|name| Change::Save {
+                    name: name.to_string(),
+                })
+                .collect(),
+        };
+
+        assert_eq!(
+            "saving A to journal, saving B to journal, saving C to journal, and 3 more changes",
+            batch.display_redo().to_string(),
+        );
+    }
+
+    #[test]
+    fn change_test_batch_rolls_back_on_failure() {
+        let mut repo = repo();
+
+        let change = Change::Batch {
+            changes: vec![
+                Change::Create {
+                    thing: Npc {
+                        name: "Penelope".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+                Change::Save {
+                    name: "Penelope".into(),
+                },
+                Change::Create {
+                    thing: Npc {
+                        name: "Odysseus".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            Err((change.clone(), Error::NameAlreadyExists)),
+            block_on(repo.modify(change)),
+        );
+        assert_eq!(Err(Error::NotFound), block_on(repo.get_by_name("Penelope")));
+        assert_eq!(4, block_on(repo.journal()).unwrap().len());
+        assert_eq!(1, repo.recent().count());
+        assert_eq!(0, repo.undo_history().count());
+    }
+
+    #[test]
+    fn change_test_batch_data_store_failed() {
+        let mut repo = null_repo();
+
+        let change = Change::Batch {
+            changes: vec![
+                Change::Create {
+                    thing: Npc {
+                        name: "Penelope".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+                Change::CreateAndSave {
+                    thing: Place {
+                        name: "Ithaca".into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            Err((change.clone(), Error::DataStoreFailed)),
+            block_on(repo.modify(change)),
+        );
+        assert_eq!(0, repo.recent().count());
+        assert_eq!(0, repo.undo_history().count());
+    }
+
+    #[test]
+    fn change_test_rename_success() {
+        let (mut repo, data_store) = repo_data_store();
+
+        let thing = block_on(repo.modify(Change::Rename {
+            name: "olympus".into(),
+            uuid: None,
+            new_name: "Mount Olympus".into(),
+        }))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!("Mount Olympus", thing.name().to_string());
+        assert_eq!(
+            "Mount Olympus",
+            block_on(data_store.get_thing_by_uuid(&OLYMPUS_UUID))
+                .unwrap()
+                .unwrap()
+                .name()
+                .to_string(),
+        );
+        assert_eq!(
+            "renaming Olympus to Mount Olympus",
+            repo.undo_history()
+                .next()
+                .unwrap()
+                .display_undo()
+                .to_string(),
+        );
+
+        block_on(repo.undo()).unwrap().unwrap();
+        assert!(block_on(repo.get_by_name("Olympus")).is_ok());
+
+        block_on(repo.modify(Change::Rename {
+            name: "Odysseus".into(),
+            uuid: None,
+            new_name: "Ulysses".into(),
+        }))
+        .unwrap();
+        assert_eq!("Ulysses", repo.recent().next().unwrap().name().to_string());
+    }
+
+    #[test]
+    fn change_test_rename_already_exists() {
+        let mut repo = repo();
+
+        let change = Change::Rename {
+            name: "Odysseus".into(),
+            uuid: None,
+            new_name: "olympus".into(),
+        };
+
+        assert_eq!(
+            Err((change.clone(), Error::NameAlreadyExists)),
+            block_on(repo.modify(change)),
+        );
+
+        let change = Change::Rename {
+            name: "Odysseus".into(),
+            uuid: None,
+            new_name: "ODYSSEUS".into(),
+        };
+        assert!(block_on(repo.modify(change)).is_ok());
+    }
+
+    #[test]
+    fn change_test_rename_not_found() {
+        let mut repo = repo();
+
+        let change = Change::Rename {
+            name: "Nobody".into(),
+            uuid: None,
+            new_name: "Somebody".into(),
+        };
+
+        assert_eq!(
+            Err((change.clone(), Error::NotFound)),
+            block_on(repo.modify(change)),
+        );
+    }
+
+    #[test]
+    fn undo_history_len_test() {
+        let mut repo = empty_repo();
+        repo.set_undo_history_len(2);
+
+        for name in ["Odysseus", "Penelope", "Telemachus"] {
+            block_on(
+                repo.modify(Change::Create {
+                    thing: Npc {
+                        name: name.into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                }),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            vec!["creating Telemachus", "creating Penelope"],
+            repo.undo_history()
+                .map(|change| change.display_undo().to_string())
+                .collect::<Vec<_>>(),
+        );
+
+        repo.set_undo_history_len(0);
+        assert_eq!(1, repo.undo_history().count());
+    }
+
+    #[test]
+    fn undo_history_persist_test() {
+        let data_store = MemoryDataStore::default();
+
+        {
+            let mut repo = Repository::new(data_store.clone());
+            repo.set_persist_undo_history(true);
+            block_on(repo.init());
+
+            block_on(
+                repo.modify(Change::CreateAndSave {
+                    thing: Npc {
+                        name: "Odysseus".into(),
+                        species: Species::Human.into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                }),
+            )
+            .unwrap();
+
+            block_on(
+                repo.modify(Change::Edit {
+                    name: "Odysseus".to_string(),
+                    uuid: None,
+                    diff: Npc {
+                        species: Species::Elf.into(),
+                        ..Default::default()
+                    }
+                    .into(),
+                }),
+            )
+            .unwrap();
+        }
+
+        let mut repo = Repository::new(data_store.clone());
+        repo.set_persist_undo_history(true);
+        block_on(repo.init());
+        assert_eq!(2, repo.undo_history().count());
+
+        block_on(repo.undo()).unwrap().unwrap();
+
+        let npc = block_on(repo.get_by_name("Odysseus"))
+            .unwrap()
+            .into_npc()
+            .unwrap();
+        assert_eq!(Some(&Species::Human), npc.species.value());
+        assert!(npc.uuid.is_some());
+
+        // Without persistence, nothing is loaded.
+        let mut repo = Repository::new(data_store);
+        block_on(repo.init());
+        assert_eq!(0, repo.undo_history().count());
+    }
+
     fn repo() -> Repository {
         repo_data_store().0
     }
@@ -2385,6 +3557,11 @@ mod test {
             self.data_store.get_things_by_name_start(name, limit).await
         }
 
+        async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+            self.tick()?;
+            self.data_store.get_things_by_query(query).await
+        }
+
         async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()> {
             self.tick()?;
             self.data_store.save_thing(thing).await
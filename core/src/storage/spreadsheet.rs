@@ -0,0 +1,474 @@
+use super::backup::{EntryKind, ImportStats, Outcome};
+use super::repository::{Change, Error as RepositoryError, Repository};
+use crate::utils::CaseInsensitiveStr;
+use crate::world::npc::{Age, Ethnicity, Gender, Species};
+use crate::world::place::PlaceType;
+use crate::world::{parse_tags, Npc, Place, PlaceUuid, Thing};
+use crate::Uuid;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// The columns that can appear in a spreadsheet, identified by the header row. Columns with other
+/// headers are ignored.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Column {
+    /// Either "character" or "place", or the kind of place, eg. "inn". If left out, rows with a
+    /// subtype or description are places and everything else is a character.
+    Type,
+    Name,
+    Subtype,
+    Description,
+    Species,
+    Ethnicity,
+    Gender,
+
+    /// Either an age category (eg. "adult") or a number of years.
+    Age,
+
+    /// The name of the place where the entry is located, either elsewhere in the spreadsheet or
+    /// already in the journal.
+    Location,
+    Tags,
+}
+
+/// A row that has been read from the spreadsheet, but whose location hasn't been looked up yet.
+struct Row {
+    number: usize,
+    thing: Thing,
+    location: Option<String>,
+}
+
+/// Import characters and places from a spreadsheet in CSV format, with a header row naming the
+/// columns. Rows that can't be imported are reported by their row number in the spreadsheet, and
+/// everything that was imported is undone together by a single `undo`.
+pub async fn import_csv(repo: &mut Repository, input: &str) -> Result<ImportStats, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+
+    let columns: Vec<Option<Column>> = reader
+        .headers()
+        .map_err(|e| {
+            format!(
+                "The file you tried to import is not valid. The parser error was {}.",
+                e
+            )
+        })?
+        .iter()
+        .map(Column::parse)
+        .collect();
+
+    if !columns.contains(&Some(Column::Name)) {
+        return Err(
+            "The file you tried to import needs a header row with a \"name\" column.".to_string(),
+        );
+    }
+
+    let mut stats = ImportStats::default();
+    let mut rows = Vec::new();
+
+    for (i, record) in reader.records().enumerate() {
+        // The header is row 1, so the first entry is row 2.
+        let number = i + 2;
+
+        match record {
+            Ok(record) if record.iter().all(str::is_empty) => {}
+            Ok(record) => match parse_row(number, &columns, &record) {
+                Ok(row) => rows.push(row),
+                Err((kind, error)) => {
+                    stats.record_error(kind, format!("Row {}: {}.", number, error))
+                }
+            },
+            Err(e) => stats.record_error(None, format!("Row {}: {}.", number, e)),
+        }
+    }
+
+    // Places can be located in other places from the same spreadsheet, in any order. Only places
+    // that will actually be created count: a name that's already taken refers to the place in the
+    // journal instead, and a place whose own location can't be found is left out, along with
+    // anything located in it.
+    let mut place_uuids: HashMap<String, Uuid> = HashMap::new();
+    for row in &rows {
+        if let (Thing::Place(_), Some(name), Some(uuid)) =
+            (&row.thing, row.thing.name().value(), row.thing.uuid())
+        {
+            if !place_uuids.contains_key(&name.to_lowercase())
+                && repo.get_by_name(name).await.is_err()
+            {
+                place_uuids.insert(name.to_lowercase(), *uuid);
+            }
+        }
+    }
+
+    loop {
+        let mut dropped = Vec::new();
+
+        for row in &rows {
+            if let (Some(uuid), Some(location)) = (row.thing.uuid(), &row.location) {
+                let name = row.thing.name().to_string().to_lowercase();
+
+                if place_uuids.get(&name) == Some(uuid)
+                    && !place_uuids.contains_key(&location.to_lowercase())
+                    && journal_place_uuid(repo, location).await.is_none()
+                {
+                    dropped.push(name);
+                }
+            }
+        }
+
+        if dropped.is_empty() {
+            break;
+        }
+
+        for name in dropped {
+            place_uuids.remove(&name);
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut pending = Vec::new();
+
+    for mut row in rows {
+        let kind = entry_kind(&row.thing);
+
+        if let Some(location) = &row.location {
+            let location_uuid = match place_uuids.get(&location.to_lowercase()) {
+                Some(uuid) => Some(*uuid),
+                None => journal_place_uuid(repo, location).await,
+            };
+
+            let Some(location_uuid) = location_uuid else {
+                stats.record_error(
+                    Some(kind),
+                    format!(
+                        r#"Row {}: there is no place named "{}"."#,
+                        row.number, location,
+                    ),
+                );
+                continue;
+            };
+
+            let location_uuid = PlaceUuid::from(location_uuid).into();
+            match &mut row.thing {
+                Thing::Npc(npc) => npc.location_uuid = location_uuid,
+                Thing::Place(place) => place.location_uuid = location_uuid,
+            }
+        }
+
+        pending.push((row.number, kind, row.thing.name().to_string()));
+        changes.push(Change::CreateAndSave { thing: row.thing });
+    }
+
+    for ((number, kind, name), result) in pending.into_iter().zip(repo.modify_each(changes).await) {
+        match result {
+            Ok(()) => stats.record(kind, Outcome::Created),
+            Err((_, RepositoryError::NameAlreadyExists)) => stats.record_error(
+                Some(kind),
+                format!("Row {}: `{}` is already in your journal.", number, name),
+            ),
+            Err(_) => stats.record_error(
+                Some(kind),
+                format!("Row {}: `{}` couldn't be saved.", number, name),
+            ),
+        }
+    }
+
+    Ok(stats)
+}
+
+/// The UUID of a place already in the journal, if there is one with that name.
+async fn journal_place_uuid(repo: &Repository, name: &str) -> Option<Uuid> {
+    match repo.get_by_name(name).await {
+        Ok(Thing::Place(Place {
+            uuid: Some(uuid), ..
+        })) => Some(*uuid.as_ref()),
+        _ => None,
+    }
+}
+
+/// Turn a row of the spreadsheet into a character or place, or explain why it can't be.
+fn parse_row(
+    number: usize,
+    columns: &[Option<Column>],
+    record: &csv::StringRecord,
+) -> Result<Row, (Option<EntryKind>, String)> {
+    let values: HashMap<Column, &str> = columns
+        .iter()
+        .zip(record.iter())
+        .filter_map(|(column, value)| Some(((*column)?, value)))
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+
+    let subtype = match (values.get(&Column::Type), values.get(&Column::Subtype)) {
+        (Some(value), _) if value.in_ci(&["character", "npc", "person"]) => None,
+        (Some(value), subtype) if value.eq_ci("place") => Some(subtype.copied()),
+        (Some(value), None) => match value.parse::<PlaceType>() {
+            Ok(_) => Some(Some(*value)),
+            Err(()) => return Err((None, format!(r#"unknown type "{}""#, value))),
+        },
+        (Some(value), Some(_)) => return Err((None, format!(r#"unknown type "{}""#, value))),
+        (None, subtype) if subtype.is_some() || values.contains_key(&Column::Description) => {
+            Some(subtype.copied())
+        }
+        (None, _) => None,
+    };
+
+    let mut thing: Thing = if let Some(subtype) = subtype {
+        let kind = Some(EntryKind::Place);
+        let mut place = Place::default();
+
+        for (column, value) in &values {
+            match column {
+                Column::Type | Column::Location => {}
+                Column::Name => place.name = value.to_string().into(),
+                Column::Subtype => {}
+                Column::Description => place.description = value.to_string().into(),
+                Column::Tags => place.tags = parse_tags(value).into(),
+                Column::Species | Column::Ethnicity | Column::Gender | Column::Age => {
+                    return Err((kind, format!("places don't have a {}", column.as_str())));
+                }
+            }
+        }
+
+        if let Some(subtype) = subtype {
+            place.subtype = parse_value::<PlaceType>(subtype, Column::Subtype, kind)?.into();
+        }
+
+        place.into()
+    } else {
+        let kind = Some(EntryKind::Npc);
+        let mut npc = Npc::default();
+
+        for (column, value) in &values {
+            match column {
+                Column::Type | Column::Location => {}
+                Column::Name => npc.name = value.to_string().into(),
+                Column::Species => {
+                    npc.species = parse_value::<Species>(value, *column, kind)?.into()
+                }
+                Column::Ethnicity => {
+                    npc.ethnicity = parse_value::<Ethnicity>(value, *column, kind)?.into()
+                }
+                Column::Gender => npc.gender = parse_value::<Gender>(value, *column, kind)?.into(),
+                Column::Age => {
+                    if let Ok(years) = value.parse::<u16>() {
+                        npc.age_years = years.into();
+                    } else {
+                        npc.age = parse_value::<Age>(value, *column, kind)?.into();
+                    }
+                }
+                Column::Tags => npc.tags = parse_tags(value).into(),
+                Column::Subtype | Column::Description => {
+                    return Err((kind, format!("characters don't have a {}", column.as_str())));
+                }
+            }
+        }
+
+        npc.into()
+    };
+
+    if thing.name().is_none() {
+        return Err((Some(entry_kind(&thing)), "missing a name".to_string()));
+    }
+
+    thing.set_uuid(Uuid::new_v4());
+
+    Ok(Row {
+        number,
+        thing,
+        location: values.get(&Column::Location).map(|s| s.to_string()),
+    })
+}
+
+fn parse_value<T: FromStr<Err = ()>>(
+    value: &str,
+    column: Column,
+    kind: Option<EntryKind>,
+) -> Result<T, (Option<EntryKind>, String)> {
+    value
+        .parse()
+        .map_err(|()| (kind, format!(r#"unknown {} "{}""#, column.as_str(), value)))
+}
+
+fn entry_kind(thing: &Thing) -> EntryKind {
+    match thing {
+        Thing::Npc(_) => EntryKind::Npc,
+        Thing::Place(_) => EntryKind::Place,
+    }
+}
+
+impl Column {
+    fn parse(header: &str) -> Option<Self> {
+        match header.to_lowercase().as_str() {
+            "type" => Some(Self::Type),
+            "name" => Some(Self::Name),
+            "subtype" => Some(Self::Subtype),
+            "description" => Some(Self::Description),
+            "species" | "race" => Some(Self::Species),
+            "ethnicity" => Some(Self::Ethnicity),
+            "gender" => Some(Self::Gender),
+            "age" => Some(Self::Age),
+            "location" => Some(Self::Location),
+            "tags" => Some(Self::Tags),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Name => "name",
+            Self::Subtype => "subtype",
+            Self::Description => "description",
+            Self::Species => "species",
+            Self::Ethnicity => "ethnicity",
+            Self::Gender => "gender",
+            Self::Age => "age",
+            Self::Location => "location",
+            Self::Tags => "tags",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::storage::MemoryDataStore;
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_row_test_npc() {
+        let row = parse(
+            "name,species,gender,age,tags",
+            "Durnan,human,man,72,\"villain, waterdeep\"",
+        )
+        .unwrap();
+        let npc = row.thing.npc().unwrap();
+
+        assert_eq!(Some(&"Durnan".to_string()), npc.name.value());
+        assert_eq!(Some(&Species::Human), npc.species.value());
+        assert_eq!(Some(&Gender::Masculine), npc.gender.value());
+        assert_eq!(Some(&72), npc.age_years.value());
+        assert_eq!(
+            Some(&vec!["villain".to_string(), "waterdeep".to_string()]),
+            npc.tags.value(),
+        );
+        assert!(npc.uuid.is_some());
+        assert_eq!(None, row.location);
+    }
+
+    #[test]
+    fn parse_row_test_place() {
+        for (header, record) in [
+            ("name,type,location", "The Yawning Portal,inn,Waterdeep"),
+            (
+                "name,type,subtype,location",
+                "The Yawning Portal,place,inn,Waterdeep",
+            ),
+            ("name,subtype,location", "The Yawning Portal,inn,Waterdeep"),
+        ] {
+            let row = parse(header, record).unwrap();
+            let place = row.thing.place().unwrap();
+
+            assert_eq!(Some(&"The Yawning Portal".to_string()), place.name.value());
+            assert_eq!(Some("inn"), place.subtype.value().map(|s| s.as_str()));
+            assert_eq!(Some("Waterdeep".to_string()), row.location, "{}", header);
+        }
+    }
+
+    #[test]
+    fn parse_row_test_errors() {
+        for (header, record, error) in [
+            ("name,species", "Bob,orc", r#"unknown species "orc""#),
+            ("name,age", "Bob,immortal", r#"unknown age "immortal""#),
+            ("name,type", "Bob,potato", r#"unknown type "potato""#),
+            (
+                "name,type,subtype",
+                "Bob,npc,inn",
+                "characters don't have a subtype",
+            ),
+            (
+                "name,subtype,gender",
+                "Bob,inn,woman",
+                "places don't have a gender",
+            ),
+            ("name,species", ",elf", "missing a name"),
+        ] {
+            assert_eq!(
+                Some(error.to_string()),
+                parse(header, record).err().map(|(_, error)| error),
+                "{}",
+                record,
+            );
+        }
+    }
+
+    #[test]
+    fn import_csv_test() {
+        let mut repo = Repository::new(MemoryDataStore::default());
+
+        let stats = block_on(import_csv(
+            &mut repo,
+            "Name,Type,Race,Location,HP\n\
+            Durnan,npc,human,The Yawning Portal,30\n\
+            ,,,,\n\
+            Volo,,orc,,\n\
+            The Yawning Portal,inn,,Waterdeep,\n\
+            Waterdeep,city,,,\n\
+            Elminster,,human,Neverwinter,\n",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            "Places: 2 created \\\nCharacters: 1 created, 2 failed\n\nSome entries couldn't be imported:\n\n* Row 4: unknown species \"orc\".\n* Row 7: there is no place named \"Neverwinter\".",
+            stats.to_string(),
+        );
+
+        let portal_uuid = block_on(repo.get_by_name("The Yawning Portal"))
+            .unwrap()
+            .uuid()
+            .cloned()
+            .unwrap();
+        let durnan = block_on(repo.get_by_name("Durnan")).unwrap();
+        assert_eq!(
+            Some(&portal_uuid),
+            durnan.location_uuid().map(|uuid| uuid.as_ref()),
+        );
+
+        // Importing the same spreadsheet again doesn't create duplicates.
+        let stats = block_on(import_csv(&mut repo, "name\nDurnan")).unwrap();
+        assert_eq!(
+            "Characters: 1 failed\n\nSome entries couldn't be imported:\n\n* Row 2: `Durnan` is already in your journal.",
+            stats.to_string(),
+        );
+    }
+
+    #[test]
+    fn import_csv_test_no_name() {
+        let mut repo = Repository::new(MemoryDataStore::default());
+
+        assert_eq!(
+            Some(
+                "The file you tried to import needs a header row with a \"name\" column."
+                    .to_string()
+            ),
+            block_on(import_csv(&mut repo, "species\nelf")).err(),
+        );
+    }
+
+    fn parse(header: &str, record: &str) -> Result<Row, (Option<EntryKind>, String)> {
+        let input = format!("{}\n{}", header, record);
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(input.as_bytes());
+        let columns: Vec<_> = reader
+            .headers()
+            .unwrap()
+            .iter()
+            .map(Column::parse)
+            .collect();
+        let record = reader.records().next().unwrap().unwrap();
+        parse_row(2, &columns, &record)
+    }
+}
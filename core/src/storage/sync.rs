@@ -0,0 +1,732 @@
+//! Keep journals on several devices in sync by exchanging logs of changes through a server.
+//!
+//! Each change made to the journal is recorded as a [`SyncChange`]: the fields, deletions, and
+//! key-values that it touched, stamped with a Lamport timestamp. Changes are pushed to and pulled
+//! from the server by a [`SyncTransport`]. Since timestamps are totally ordered, every device
+//! resolves conflicts the same way: the most recent write to each field of an entry wins, a
+//! deletion wins over concurrent edits, and only recreating an entry (eg. by undoing the deletion)
+//! brings it back.
+
+use super::repository::{Error as RepositoryError, KeyValue, Repository};
+use crate::world::Thing;
+use crate::Uuid;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+/// The key under which the sync state is persisted in the data store.
+pub(super) const SYNC_STATE_KEY: &str = "sync";
+
+/// The field of a serialized thing that records its type. It's only present when the thing is
+/// created, which distinguishes creating a thing from editing it.
+const TYPE_FIELD: &str = "type";
+
+/// A Lamport timestamp. The counter is one more than the highest counter seen by the device at the
+/// time of the change, and ties are broken by the device ID, so timestamps are ordered the same
+/// way on every device.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub struct Timestamp {
+    pub counter: u64,
+    pub device: Uuid,
+}
+
+/// Everything that a single change to the journal wrote, as sent to and received from the server.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SyncChange {
+    pub timestamp: Timestamp,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Operation {
+    /// Set fields of a journal entry, serialized as they would be in a backup. Creating an entry
+    /// sets all of its fields, including its type.
+    Put {
+        uuid: Uuid,
+        fields: Map<String, Value>,
+    },
+
+    /// Delete a journal entry.
+    Delete { uuid: Uuid },
+
+    /// Set or clear a value in the key-value store, in its raw form.
+    SetKeyValue { key: String, value: Option<String> },
+}
+
+/// Sends changes made on this device to the server and fetches those made elsewhere. The server
+/// keeps every change it has been sent in the order it received them; a position in that log is
+/// called a cursor.
+#[async_trait(?Send)]
+pub trait SyncTransport {
+    /// Append changes to the server's log.
+    async fn push(&mut self, changes: &[SyncChange]) -> Result<(), ()>;
+
+    /// Fetch the changes in the server's log after `cursor`, along with the cursor to use next
+    /// time. The result may include changes sent by this device.
+    async fn pull(&mut self, cursor: u64) -> Result<(Vec<SyncChange>, u64), ()>;
+}
+
+/// An in-process stand-in for a sync server, keeping the log in memory. Clones share the same
+/// log, so several devices can sync through it.
+#[derive(Clone, Default)]
+pub struct MemorySyncServer {
+    pub changes: Rc<RefCell<Vec<SyncChange>>>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SyncError {
+    DataStoreFailed,
+    TransportFailed,
+}
+
+/// What a successful sync did.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct SyncReport {
+    pub sent: usize,
+    pub received: usize,
+}
+
+/// The sync bookkeeping of a device, persisted in the data store once the device first syncs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(super) struct SyncState {
+    device: Uuid,
+    clock: u64,
+    cursor: u64,
+
+    /// Changes made on this device that haven't reached the server yet.
+    outbox: Vec<SyncChange>,
+
+    /// When each field of each entry was last written.
+    fields: HashMap<Uuid, HashMap<String, Timestamp>>,
+
+    /// When each deleted entry was deleted.
+    deleted: HashMap<Uuid, Timestamp>,
+
+    /// When each key-value was last written.
+    key_values: HashMap<String, Timestamp>,
+
+    /// Operations recorded since the last call to `commit`.
+    #[serde(skip)]
+    pending: Vec<Operation>,
+}
+
+/// Sync the journal with the server: apply the changes made on other devices since the last sync,
+/// then send the changes made on this device.
+///
+/// The first sync on a device sends the whole journal, so that entries made before syncing was set
+/// up aren't left behind.
+pub async fn sync(
+    repo: &mut Repository,
+    transport: &mut dyn SyncTransport,
+) -> Result<SyncReport, SyncError> {
+    if !repo.sync_enabled() {
+        repo.enable_sync()
+            .await
+            .map_err(|_| SyncError::DataStoreFailed)?;
+    }
+
+    let cursor = repo.sync_state().map_or(0, |state| state.cursor);
+    let (changes, cursor) = transport
+        .pull(cursor)
+        .await
+        .map_err(|()| SyncError::TransportFailed)?;
+
+    let mut report = SyncReport::default();
+
+    for change in changes {
+        if repo
+            .sync_state()
+            .is_some_and(|state| state.device == change.timestamp.device)
+        {
+            continue;
+        }
+
+        report.received += 1;
+        repo.apply_sync_change(change)
+            .await
+            .map_err(|_| SyncError::DataStoreFailed)?;
+    }
+
+    let outbox = repo
+        .sync_state_mut()
+        .map(|state| {
+            state.cursor = cursor;
+            state.outbox.clone()
+        })
+        .unwrap_or_default();
+
+    let pushed = transport.push(&outbox).await;
+
+    if pushed.is_ok() {
+        report.sent = outbox.len();
+
+        if let Some(state) = repo.sync_state_mut() {
+            state.outbox.drain(..outbox.len());
+        }
+    }
+
+    repo.save_sync_state().await;
+    pushed.map_err(|()| SyncError::TransportFailed)?;
+
+    Ok(report)
+}
+
+/// Shorten a log of changes by dropping everything that has since been overwritten, for servers
+/// to store in place of the full log. Applying the result has the same effect as applying every
+/// change in the original, as long as the changes that come after it are applied as well.
+pub fn compact_changes(mut changes: Vec<SyncChange>) -> Vec<SyncChange> {
+    changes.sort_by_key(|change| change.timestamp);
+
+    let mut written: HashMap<Uuid, HashSet<String>> = HashMap::new();
+    let mut replaced = HashSet::new();
+    let mut deleted = HashSet::new();
+    let mut key_values = HashSet::new();
+
+    // Working backwards, anything written to a field, entry, or key that has already been seen
+    // was overwritten later.
+    for change in changes.iter_mut().rev() {
+        change.operations = std::mem::take(&mut change.operations)
+            .into_iter()
+            .rev()
+            .filter_map(|operation| match operation {
+                Operation::Put { uuid, fields } => {
+                    if replaced.contains(&uuid) {
+                        return None;
+                    }
+
+                    // Creating an entry sets every field, so it's kept whole.
+                    let is_creation = fields.contains_key(TYPE_FIELD);
+                    if is_creation {
+                        replaced.insert(uuid);
+                    }
+
+                    let written = written.entry(uuid).or_default();
+                    let fields: Map<String, Value> = fields
+                        .into_iter()
+                        .filter(|(field, _)| written.insert(field.clone()) || is_creation)
+                        .collect();
+
+                    (!fields.is_empty()).then_some(Operation::Put { uuid, fields })
+                }
+                Operation::Delete { uuid } => {
+                    // An entry that was deleted and later recreated keeps the deletion, so that
+                    // devices that still have the original start over from scratch.
+                    replaced.insert(uuid);
+                    deleted.insert(uuid).then_some(Operation::Delete { uuid })
+                }
+                Operation::SetKeyValue { key, value } => key_values
+                    .insert(key.clone())
+                    .then_some(Operation::SetKeyValue { key, value }),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+    }
+
+    changes.retain(|change| !change.operations.is_empty());
+    changes
+}
+
+impl SyncState {
+    pub(super) fn new() -> Self {
+        Self {
+            device: Uuid::new_v4(),
+            ..Default::default()
+        }
+    }
+
+    /// Record that a journal entry was created (`before` is `None`) or edited.
+    pub(super) fn record_put(&mut self, before: Option<&Thing>, after: &Thing) {
+        let Some(uuid) = after.uuid() else {
+            return;
+        };
+
+        let mut fields = to_fields(after);
+
+        if let Some(before) = before {
+            let before = to_fields(before);
+
+            // Empty fields are left out when serialized, so a field that was cleared is sent as
+            // null.
+            for field in before.keys() {
+                fields.entry(field.clone()).or_insert(Value::Null);
+            }
+
+            fields = fields
+                .into_iter()
+                .filter(|(field, value)| {
+                    field != TYPE_FIELD && before.get(field).unwrap_or(&Value::Null) != value
+                })
+                .collect();
+        }
+
+        if !fields.is_empty() {
+            self.pending.push(Operation::Put {
+                uuid: *uuid,
+                fields,
+            });
+        }
+    }
+
+    pub(super) fn record_delete(&mut self, uuid: &Uuid) {
+        self.pending.push(Operation::Delete { uuid: *uuid });
+    }
+
+    pub(super) fn record_key_value(&mut self, key_value: &KeyValue) {
+        // The log describes what happened on each device, so merging the logs of several devices
+        // would make no sense.
+        if matches!(key_value, KeyValue::Log(_)) {
+            return;
+        }
+
+        let (key, value) = key_value.key_value_raw();
+        self.pending.push(Operation::SetKeyValue {
+            key: key.to_string(),
+            value,
+        });
+    }
+
+    /// Stamp the operations recorded since the last commit as a single change and queue it to be
+    /// sent. Returns `false` if there was nothing to commit.
+    pub(super) fn commit(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+
+        self.clock += 1;
+        let timestamp = Timestamp {
+            counter: self.clock,
+            device: self.device,
+        };
+        let operations = std::mem::take(&mut self.pending);
+
+        self.resolve(&SyncChange {
+            timestamp,
+            operations: operations.clone(),
+        });
+        self.outbox.push(SyncChange {
+            timestamp,
+            operations,
+        });
+
+        true
+    }
+
+    /// Bring a change from another device up to date with what this device knows, dropping any
+    /// operations and fields that have since been overwritten, and note what it wrote.
+    pub(super) fn receive(&mut self, change: SyncChange) -> Vec<Operation> {
+        self.clock = self.clock.max(change.timestamp.counter);
+        self.resolve(&change)
+    }
+
+    fn resolve(&mut self, change: &SyncChange) -> Vec<Operation> {
+        let timestamp = change.timestamp;
+        let mut result = Vec::with_capacity(change.operations.len());
+
+        for operation in &change.operations {
+            match operation {
+                Operation::Put { uuid, fields } => {
+                    if self.deleted.get(uuid).is_some_and(|t| *t > timestamp) {
+                        continue;
+                    }
+
+                    if fields.contains_key(TYPE_FIELD) {
+                        self.deleted.remove(uuid);
+                    }
+
+                    let versions = self.fields.entry(*uuid).or_default();
+                    let fields: Map<String, Value> = fields
+                        .iter()
+                        .filter(|(field, _)| {
+                            versions.get(field.as_str()).is_none_or(|t| *t < timestamp)
+                        })
+                        .map(|(field, value)| (field.clone(), value.clone()))
+                        .collect();
+
+                    for field in fields.keys() {
+                        versions.insert(field.clone(), timestamp);
+                    }
+
+                    if !fields.is_empty() {
+                        result.push(Operation::Put {
+                            uuid: *uuid,
+                            fields,
+                        });
+                    }
+                }
+                Operation::Delete { uuid } => {
+                    let created = self
+                        .fields
+                        .get(uuid)
+                        .and_then(|versions| versions.get(TYPE_FIELD));
+
+                    if created.is_some_and(|t| *t > timestamp) {
+                        continue;
+                    }
+
+                    self.fields.remove(uuid);
+                    let deleted = self.deleted.entry(*uuid).or_insert(timestamp);
+                    *deleted = timestamp.max(*deleted);
+                    result.push(operation.clone());
+                }
+                Operation::SetKeyValue { key, .. } => {
+                    if self.key_values.get(key).is_some_and(|t| *t > timestamp) {
+                        continue;
+                    }
+
+                    self.key_values.insert(key.clone(), timestamp);
+                    result.push(operation.clone());
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Overwrite some of the fields of a journal entry, or create it if `existing` is `None` and the
+/// fields describe a whole entry.
+pub(super) fn apply_fields(
+    uuid: &Uuid,
+    existing: Option<&Thing>,
+    fields: &Map<String, Value>,
+) -> Result<Option<Thing>, RepositoryError> {
+    let mut merged = match existing {
+        Some(thing) => to_fields(thing),
+        None if fields.contains_key(TYPE_FIELD) => Map::new(),
+        None => return Ok(None),
+    };
+
+    merged.extend(fields.clone());
+    merged.insert("uuid".to_string(), Value::String(uuid.to_string()));
+
+    // Some fields can only be deserialized from borrowed strings, which a `Value` can't provide.
+    serde_json::to_string(&merged)
+        .and_then(|json| serde_json::from_str(&json))
+        .map(Some)
+        .map_err(|_| RepositoryError::DataStoreFailed)
+}
+
+/// A thing as a map of field names to values, without its UUID.
+fn to_fields(thing: &Thing) -> Map<String, Value> {
+    match serde_json::to_value(thing) {
+        Ok(Value::Object(mut fields)) => {
+            fields.remove("uuid");
+            fields
+        }
+        _ => Map::new(),
+    }
+}
+
+#[async_trait(?Send)]
+impl SyncTransport for MemorySyncServer {
+    async fn push(&mut self, changes: &[SyncChange]) -> Result<(), ()> {
+        self.changes.borrow_mut().extend_from_slice(changes);
+        Ok(())
+    }
+
+    async fn pull(&mut self, cursor: u64) -> Result<(Vec<SyncChange>, u64), ()> {
+        let changes = self.changes.borrow();
+        let cursor = usize::try_from(cursor).map_err(|_| ())?.min(changes.len());
+        Ok((changes[cursor..].to_vec(), changes.len() as u64))
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "change" } else { "changes" };
+
+        write!(
+            f,
+            "Your journal is in sync. Sent {} {} and received {} {}.",
+            self.sent,
+            plural(self.sent),
+            self.received,
+            plural(self.received),
+        )
+    }
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DataStoreFailed => write!(
+                f,
+                "Couldn't sync because your journal couldn't be read or saved."
+            ),
+            Self::TransportFailed => write!(
+                f,
+                "Couldn't reach the sync server. Your changes will be sent next time."
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world::npc::{Npc, Species};
+
+    fn durnan() -> Thing {
+        Npc {
+            uuid: Some(Uuid::from_u128(1).into()),
+            name: "Durnan".into(),
+            species: Species::Human.into(),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn timestamp(counter: u64, device: u128) -> Timestamp {
+        Timestamp {
+            counter,
+            device: Uuid::from_u128(device),
+        }
+    }
+
+    fn put(fields: &[(&str, &str)]) -> Operation {
+        Operation::Put {
+            uuid: Uuid::from_u128(1),
+            fields: fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), Value::String(value.to_string())))
+                .collect(),
+        }
+    }
+
+    fn change(counter: u64, device: u128, operations: Vec<Operation>) -> SyncChange {
+        SyncChange {
+            timestamp: timestamp(counter, device),
+            operations,
+        }
+    }
+
+    #[test]
+    fn record_put_test() {
+        let mut state = SyncState::new();
+        let mut elf = durnan();
+        if let Thing::Npc(npc) = &mut elf {
+            npc.species = Species::Elf.into();
+        }
+
+        state.record_put(None, &durnan());
+        state.record_put(Some(&durnan()), &elf);
+        state.record_put(Some(&elf), &elf);
+
+        assert_eq!(2, state.pending.len());
+        assert!(matches!(
+            &state.pending[0],
+            Operation::Put { fields, .. } if fields.get("type") == Some(&Value::from("Npc")),
+        ));
+        assert_eq!(put(&[("species", "elf")]), state.pending[1]);
+    }
+
+    #[test]
+    fn record_put_test_cleared_field() {
+        let mut state = SyncState::new();
+        let mut tagged = durnan();
+        if let Thing::Npc(npc) = &mut tagged {
+            npc.tags = Some(vec!["villain".to_string()]).into();
+        }
+
+        state.record_put(Some(&tagged), &durnan());
+
+        let Operation::Put { fields, .. } = &state.pending[0] else {
+            panic!("{:?}", state.pending);
+        };
+        assert_eq!(Some(&Value::Null), fields.get("tags"));
+
+        let untagged = apply_fields(&Uuid::from_u128(1), Some(&tagged), fields)
+            .unwrap()
+            .unwrap();
+        assert_eq!(to_fields(&durnan()), to_fields(&untagged));
+    }
+
+    #[test]
+    fn commit_test() {
+        let mut state = SyncState::new();
+        assert!(!state.commit());
+
+        state.record_key_value(&KeyValue::Log(Some(Vec::new())));
+        assert!(!state.commit());
+
+        state.record_delete(&Uuid::from_u128(1));
+        state.record_delete(&Uuid::from_u128(2));
+        assert!(state.commit());
+
+        state.receive(change(5, 0, Vec::new()));
+        state.record_delete(&Uuid::from_u128(3));
+        assert!(state.commit());
+
+        assert_eq!(
+            vec![(1, 2), (6, 1)],
+            state
+                .outbox
+                .iter()
+                .map(|change| (change.timestamp.counter, change.operations.len()))
+                .collect::<Vec<_>>(),
+        );
+        assert!(state
+            .outbox
+            .iter()
+            .all(|c| c.timestamp.device == state.device));
+    }
+
+    #[test]
+    fn receive_test_fields() {
+        let mut state = SyncState::default();
+
+        state.receive(change(2, 1, vec![put(&[("name", "Bob")])]));
+        state.receive(change(3, 2, vec![put(&[("species", "elf")])]));
+
+        assert_eq!(
+            vec![put(&[("name", "Rob")])],
+            state.receive(change(
+                3,
+                1,
+                vec![put(&[("name", "Rob"), ("species", "dwarf")])]
+            )),
+            "the species was written more recently by device 2",
+        );
+        assert_eq!(
+            Vec::<Operation>::new(),
+            state.receive(change(1, 3, vec![put(&[("name", "Bobby")])])),
+        );
+        assert_eq!(
+            vec![put(&[("species", "human")])],
+            state.receive(change(4, 3, vec![put(&[("species", "human")])])),
+        );
+    }
+
+    #[test]
+    fn receive_test_delete() {
+        let uuid = Uuid::from_u128(1);
+        let create = put(&[("type", "Npc"), ("name", "Bob")]);
+        let mut state = SyncState::default();
+
+        state.receive(change(1, 1, vec![create.clone()]));
+        assert_eq!(
+            vec![Operation::Delete { uuid }],
+            state.receive(change(3, 1, vec![Operation::Delete { uuid }])),
+        );
+        assert_eq!(
+            Vec::<Operation>::new(),
+            state.receive(change(2, 2, vec![put(&[("name", "Rob")])])),
+            "edits made before the deletion are dropped",
+        );
+        assert_eq!(
+            vec![create.clone()],
+            state.receive(change(4, 2, vec![create])),
+            "the entry is recreated",
+        );
+        assert_eq!(
+            Vec::<Operation>::new(),
+            state.receive(change(3, 3, vec![Operation::Delete { uuid }])),
+            "deletions made before the entry was recreated are dropped",
+        );
+    }
+
+    #[test]
+    fn receive_test_key_value() {
+        let mut state = SyncState::default();
+        let set_time = |value: &str| Operation::SetKeyValue {
+            key: "time".to_string(),
+            value: Some(value.to_string()),
+        };
+
+        assert_eq!(
+            vec![set_time("2:00:00:00")],
+            state.receive(change(2, 1, vec![set_time("2:00:00:00")])),
+        );
+        assert_eq!(
+            Vec::<Operation>::new(),
+            state.receive(change(1, 1, vec![set_time("1:00:00:00")])),
+        );
+    }
+
+    #[test]
+    fn compact_changes_test() {
+        let uuid = Uuid::from_u128(1);
+        let create = put(&[("type", "Npc"), ("name", "Bob")]);
+        let set_time = |value: &str| Operation::SetKeyValue {
+            key: "time".to_string(),
+            value: Some(value.to_string()),
+        };
+
+        assert_eq!(
+            vec![
+                change(1, 1, vec![create.clone()]),
+                change(3, 1, vec![put(&[("species", "elf")])]),
+                change(4, 2, vec![set_time("2:00:00:00")]),
+                change(5, 2, vec![put(&[("name", "Rob")])]),
+            ],
+            compact_changes(vec![
+                change(1, 1, vec![create.clone(), set_time("1:00:00:00")]),
+                change(5, 2, vec![put(&[("name", "Rob")])]),
+                change(2, 1, vec![put(&[("name", "Robert"), ("species", "human")])]),
+                change(4, 2, vec![set_time("2:00:00:00")]),
+                change(3, 1, vec![put(&[("species", "elf")])]),
+            ]),
+        );
+
+        assert_eq!(
+            vec![change(3, 1, vec![Operation::Delete { uuid }])],
+            compact_changes(vec![
+                change(1, 1, vec![create.clone()]),
+                change(2, 2, vec![put(&[("name", "Rob")])]),
+                change(3, 1, vec![Operation::Delete { uuid }]),
+            ]),
+        );
+
+        assert_eq!(
+            vec![
+                change(3, 1, vec![Operation::Delete { uuid }]),
+                change(4, 1, vec![create.clone()]),
+            ],
+            compact_changes(vec![
+                change(1, 1, vec![create.clone()]),
+                change(2, 1, vec![put(&[("species", "elf")])]),
+                change(3, 1, vec![Operation::Delete { uuid }]),
+                change(4, 1, vec![create]),
+            ]),
+        );
+    }
+
+    #[test]
+    fn apply_fields_test() {
+        let uuid = Uuid::from_u128(1);
+        let thing = durnan();
+
+        assert_eq!(
+            to_fields(&thing),
+            to_fields(
+                &apply_fields(&uuid, None, &to_fields(&thing))
+                    .unwrap()
+                    .unwrap()
+            ),
+        );
+        assert_eq!(None, apply_fields(&uuid, None, &Map::new()).unwrap());
+
+        let Operation::Put { fields, .. } = put(&[("species", "elf")]) else {
+            unreachable!();
+        };
+        let elf = apply_fields(&uuid, Some(&thing), &fields).unwrap().unwrap();
+        assert_eq!(Some(&Species::Elf), elf.npc().unwrap().species.value());
+        assert_eq!(Some(&uuid), elf.uuid());
+
+        let Operation::Put { fields, .. } = put(&[("species", "ent")]) else {
+            unreachable!();
+        };
+        assert_eq!(
+            Err(RepositoryError::DataStoreFailed),
+            apply_fields(&uuid, Some(&thing), &fields),
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! A conformance suite for `DataStore` implementations, available to other crates with the
+//! `test-utils` feature.
+
+use super::DataStore;
+use crate::world::{Npc, Place, PlaceUuid};
+use crate::{Thing, ThingQuery, Uuid};
+
+/// Run a new, empty data store through every method of the `DataStore` trait, panicking at the
+/// first behaviour that differs from `MemoryDataStore`.
+///
+/// Things that come back from the data store are compared with their fields locked, since a data
+/// store that serializes things is not expected to remember which fields were locked.
+///
+/// ```ignore
+/// #[test]
+/// fn conformance_test() {
+///     tokio_test::block_on(initiative_core::test_data_store(MyDataStore::new()));
+/// }
+/// ```
+pub async fn test_data_store(mut data_store: impl DataStore) {
+    let ds = &mut data_store;
+
+    assert_eq!(Ok(()), ds.health_check().await, "health_check");
+    assert_empty(ds).await;
+
+    save_thing(ds).await;
+    get_thing_by_uuid(ds).await;
+    get_thing_by_name(ds).await;
+    get_things_by_name_start(ds).await;
+    get_things_by_query(ds).await;
+    edit_thing(ds).await;
+    delete_thing_by_uuid(ds).await;
+    key_values(ds).await;
+}
+
+async fn save_thing(ds: &mut impl DataStore) {
+    let uuid = Uuid::new_v4();
+
+    assert_eq!(Ok(()), ds.save_thing(&npc(uuid, "Frodo")).await);
+    assert_eq!(
+        Err(()),
+        ds.save_thing(&place(uuid, "Bag End")).await,
+        "save_thing must not overwrite an existing UUID",
+    );
+    assert_eq!(
+        Err(()),
+        ds.save_thing(&Npc::default().into()).await,
+        "save_thing must reject things without a UUID",
+    );
+
+    assert_things(vec![npc(uuid, "Frodo")], ds.get_all_the_things().await);
+
+    clear(ds).await;
+}
+
+async fn get_thing_by_uuid(ds: &mut impl DataStore) {
+    let uuid = Uuid::new_v4();
+
+    assert_eq!(Ok(None), ds.get_thing_by_uuid(&uuid).await);
+    ds.save_thing(&npc(uuid, "Samwise")).await.unwrap();
+    ds.save_thing(&npc(Uuid::new_v4(), "Rosie")).await.unwrap();
+
+    assert_things(
+        vec![npc(uuid, "Samwise")],
+        ds.get_thing_by_uuid(&uuid)
+            .await
+            .map(|thing| thing.into_iter().collect()),
+    );
+    assert_eq!(Ok(None), ds.get_thing_by_uuid(&Uuid::new_v4()).await);
+
+    clear(ds).await;
+}
+
+async fn get_thing_by_name(ds: &mut impl DataStore) {
+    let uuid = Uuid::new_v4();
+
+    assert_eq!(Ok(None), ds.get_thing_by_name("Gandalf the Grey").await);
+    ds.save_thing(&npc(uuid, "Gandalf the Grey")).await.unwrap();
+    ds.save_thing(
+        &Npc {
+            uuid: Some(Uuid::new_v4().into()),
+            ..Default::default()
+        }
+        .into(),
+    )
+    .await
+    .unwrap();
+
+    for name in ["Gandalf the Grey", "gandalf the grey", "gANDALF THE gREY"] {
+        assert_things(
+            vec![npc(uuid, "Gandalf the Grey")],
+            ds.get_thing_by_name(name)
+                .await
+                .map(|thing| thing.into_iter().collect()),
+        );
+    }
+
+    for name in [
+        "Gandalf",
+        "Gandalf the Grey the Grey",
+        "",
+        "%",
+        "Gandalf_the_Grey",
+    ] {
+        assert_eq!(
+            Ok(None),
+            ds.get_thing_by_name(name).await,
+            "get_thing_by_name({:?}) must only match whole names",
+            name,
+        );
+    }
+
+    clear(ds).await;
+}
+
+async fn get_things_by_name_start(ds: &mut impl DataStore) {
+    let (grey, white, frodo) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+    assert_things(Vec::new(), ds.get_things_by_name_start("gan", None).await);
+
+    ds.save_thing(&npc(grey, "Gandalf the Grey")).await.unwrap();
+    ds.save_thing(&npc(white, "gANDALF THE wHITE"))
+        .await
+        .unwrap();
+    ds.save_thing(&npc(frodo, "Frodo Baggins")).await.unwrap();
+    ds.save_thing(
+        &Npc {
+            uuid: Some(Uuid::new_v4().into()),
+            ..Default::default()
+        }
+        .into(),
+    )
+    .await
+    .unwrap();
+
+    let gandalfs = vec![
+        npc(grey, "Gandalf the Grey"),
+        npc(white, "gANDALF THE wHITE"),
+    ];
+
+    for prefix in ["gan", "GAN", "Gandalf the ", "g"] {
+        assert_things(
+            gandalfs.clone(),
+            ds.get_things_by_name_start(prefix, None).await,
+        );
+    }
+
+    assert_things(
+        vec![npc(grey, "Gandalf the Grey")],
+        ds.get_things_by_name_start("gandalf the grey", None).await,
+    );
+
+    assert_things(
+        vec![
+            npc(grey, "Gandalf the Grey"),
+            npc(white, "gANDALF THE wHITE"),
+            npc(frodo, "Frodo Baggins"),
+        ],
+        ds.get_things_by_name_start("", None).await,
+    );
+
+    for prefix in ["andalf", "Gandalf the Greyer", "%", "_andalf", "G%"] {
+        assert_things(Vec::new(), ds.get_things_by_name_start(prefix, None).await);
+    }
+
+    for (limit, expected) in [(0, 0), (1, 1), (2, 2), (3, 2), (usize::MAX, 2)] {
+        let result = ds.get_things_by_name_start("gan", Some(limit)).await;
+        assert_eq!(
+            Ok(expected),
+            result.as_ref().map(|things| things.len()),
+            "get_things_by_name_start(\"gan\", Some({}))",
+            limit,
+        );
+        assert!(
+            result.unwrap().iter().all(|thing| gandalfs
+                .iter()
+                .any(|gandalf| gandalf.uuid() == thing.uuid())),
+            "get_things_by_name_start must respect the prefix when limited",
+        );
+    }
+
+    clear(ds).await;
+}
+
+async fn get_things_by_query(ds: &mut impl DataStore) {
+    let (shire, hobbiton, frodo, sam, gandalf) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+
+    let things: Vec<Thing> = vec![
+        place(shire, "The Shire"),
+        Place {
+            location_uuid: PlaceUuid::from(shire).into(),
+            ..place(hobbiton, "Hobbiton").into_place().unwrap()
+        }
+        .into(),
+        Npc {
+            location_uuid: PlaceUuid::from(hobbiton).into(),
+            age_years: 50.into(),
+            tags: vec!["ringbearer".to_string()].into(),
+            ..npc(frodo, "Frodo").into_npc().unwrap()
+        }
+        .into(),
+        Npc {
+            location_uuid: PlaceUuid::from(hobbiton).into(),
+            age_years: 38.into(),
+            ..npc(sam, "Samwise").into_npc().unwrap()
+        }
+        .into(),
+        npc(gandalf, "Gandalf"),
+    ];
+
+    assert_things(
+        Vec::new(),
+        ds.get_things_by_query(&ThingQuery::default()).await,
+    );
+
+    for thing in things.iter() {
+        ds.save_thing(thing).await.unwrap();
+    }
+
+    assert_things(
+        things.clone(),
+        ds.get_things_by_query(&ThingQuery::default()).await,
+    );
+
+    assert_things(
+        vec![things[2].clone(), things[3].clone(), things[4].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            thing: Some(Npc::default().into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        vec![things[0].clone(), things[1].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            thing: Some(Place::default().into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        vec![things[2].clone(), things[3].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            location_uuid: Some(hobbiton.into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        vec![things[1].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            location_uuid: Some(shire.into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        vec![things[2].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            location_uuid: Some(hobbiton.into()),
+            min_age_years: Some(40),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        vec![things[2].clone()],
+        ds.get_things_by_query(&ThingQuery {
+            tags: vec!["ringbearer".to_string()],
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_things(
+        Vec::new(),
+        ds.get_things_by_query(&ThingQuery {
+            thing: Some(Place::default().into()),
+            location_uuid: Some(hobbiton.into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    clear(ds).await;
+}
+
+async fn edit_thing(ds: &mut impl DataStore) {
+    let uuid = Uuid::new_v4();
+
+    assert_eq!(
+        Ok(()),
+        ds.edit_thing(&npc(uuid, "Gandalf the Grey")).await,
+        "edit_thing must save things that don't exist yet",
+    );
+    assert_things(
+        vec![npc(uuid, "Gandalf the Grey")],
+        ds.get_all_the_things().await,
+    );
+
+    assert_eq!(Ok(()), ds.edit_thing(&npc(uuid, "Gandalf the White")).await);
+    assert_things(
+        vec![npc(uuid, "Gandalf the White")],
+        ds.get_all_the_things().await,
+    );
+    assert_things(
+        vec![npc(uuid, "Gandalf the White")],
+        ds.get_thing_by_uuid(&uuid)
+            .await
+            .map(|thing| thing.into_iter().collect()),
+    );
+    assert_eq!(
+        Ok(None),
+        ds.get_thing_by_name("Gandalf the Grey").await,
+        "edit_thing must update name lookups",
+    );
+    assert_things(
+        vec![npc(uuid, "Gandalf the White")],
+        ds.get_thing_by_name("gandalf the white")
+            .await
+            .map(|thing| thing.into_iter().collect()),
+    );
+    assert_things(
+        Vec::new(),
+        ds.get_things_by_name_start("Gandalf the G", None).await,
+    );
+
+    let shire = Uuid::new_v4();
+    let moved: Thing = Npc {
+        location_uuid: PlaceUuid::from(shire).into(),
+        ..npc(uuid, "Gandalf the White").into_npc().unwrap()
+    }
+    .into();
+    assert_eq!(Ok(()), ds.edit_thing(&moved).await);
+    assert_things(
+        vec![moved],
+        ds.get_things_by_query(&ThingQuery {
+            location_uuid: Some(shire.into()),
+            ..Default::default()
+        })
+        .await,
+    );
+
+    assert_eq!(
+        Err(()),
+        ds.edit_thing(&Npc::default().into()).await,
+        "edit_thing must reject things without a UUID",
+    );
+
+    clear(ds).await;
+}
+
+async fn delete_thing_by_uuid(ds: &mut impl DataStore) {
+    let (frodo, sam) = (Uuid::new_v4(), Uuid::new_v4());
+
+    ds.save_thing(&npc(frodo, "Frodo")).await.unwrap();
+    ds.save_thing(&npc(sam, "Samwise")).await.unwrap();
+
+    assert_eq!(
+        Err(()),
+        ds.delete_thing_by_uuid(&Uuid::new_v4()).await,
+        "delete_thing_by_uuid must fail if there is nothing to delete",
+    );
+    assert_eq!(Ok(2), ds.get_all_the_things().await.map(|v| v.len()));
+
+    assert_eq!(Ok(()), ds.delete_thing_by_uuid(&frodo).await);
+    assert_things(vec![npc(sam, "Samwise")], ds.get_all_the_things().await);
+    assert_eq!(Ok(None), ds.get_thing_by_uuid(&frodo).await);
+    assert_eq!(Ok(None), ds.get_thing_by_name("Frodo").await);
+    assert_things(Vec::new(), ds.get_things_by_name_start("F", None).await);
+
+    assert_eq!(Err(()), ds.delete_thing_by_uuid(&frodo).await);
+
+    assert_eq!(
+        Ok(()),
+        ds.save_thing(&npc(frodo, "Frodo")).await,
+        "a deleted UUID must be available to save again",
+    );
+
+    clear(ds).await;
+}
+
+async fn key_values(ds: &mut impl DataStore) {
+    assert_eq!(Ok(None), ds.get_value("somekey").await);
+    assert_eq!(Ok(()), ds.set_value("somekey", "abc").await);
+    assert_eq!(Ok(()), ds.set_value("otherkey", "def").await);
+    assert_eq!(Ok(Some("abc".to_string())), ds.get_value("somekey").await);
+    assert_eq!(
+        Ok(None),
+        ds.get_value("SOMEKEY").await,
+        "keys must be case sensitive",
+    );
+
+    assert_eq!(Ok(()), ds.set_value("somekey", "xyz").await);
+    assert_eq!(Ok(Some("xyz".to_string())), ds.get_value("somekey").await);
+
+    assert_eq!(Ok(()), ds.set_value("empty", "").await);
+    assert_eq!(Ok(Some(String::new())), ds.get_value("empty").await);
+
+    let json = r#"{"multi-line":"value",
+"with":["quotes", 'and', `backticks`]}"#;
+    assert_eq!(Ok(()), ds.set_value("json", json).await);
+    assert_eq!(Ok(Some(json.to_string())), ds.get_value("json").await);
+
+    assert_eq!(Ok(()), ds.delete_value("somekey").await);
+    assert_eq!(Ok(None), ds.get_value("somekey").await);
+    assert_eq!(Ok(Some("def".to_string())), ds.get_value("otherkey").await);
+    assert_eq!(
+        Ok(()),
+        ds.delete_value("somekey").await,
+        "delete_value must succeed if there is nothing to delete",
+    );
+
+    assert_eq!(
+        Ok(0),
+        ds.get_all_the_things().await.map(|v| v.len()),
+        "values must be kept separate from things",
+    );
+
+    for key in ["otherkey", "empty", "json"] {
+        ds.delete_value(key).await.unwrap();
+    }
+}
+
+async fn assert_empty(ds: &mut impl DataStore) {
+    assert_eq!(
+        Ok(0),
+        ds.get_all_the_things().await.map(|v| v.len()),
+        "test_data_store needs an empty data store",
+    );
+}
+
+/// Delete every thing in the data store, leaving it empty for the next test.
+async fn clear(ds: &mut impl DataStore) {
+    for thing in ds.get_all_the_things().await.unwrap() {
+        assert_eq!(Ok(()), ds.delete_thing_by_uuid(thing.uuid().unwrap()).await);
+    }
+
+    assert_empty(ds).await;
+}
+
+/// Compare things regardless of order and of which fields are locked.
+fn assert_things(expected: Vec<Thing>, actual: Result<Vec<Thing>, ()>) {
+    let normalize = |mut things: Vec<Thing>| {
+        things.iter_mut().for_each(Thing::lock_all);
+        things.sort_by(|a, b| a.uuid().cmp(&b.uuid()));
+        things
+    };
+
+    assert_eq!(Ok(normalize(expected)), actual.map(normalize));
+}
+
+fn npc(uuid: Uuid, name: &str) -> Thing {
+    Npc {
+        uuid: Some(uuid.into()),
+        name: name.into(),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn place(uuid: Uuid, name: &str) -> Thing {
+    Place {
+        uuid: Some(uuid.into()),
+        name: name.into(),
+        ..Default::default()
+    }
+    .into()
+}
@@ -0,0 +1,9 @@
+use super::Time;
+use serde::{Deserialize, Serialize};
+
+/// A named moment in game time, such as "the party left Waterdeep", for use with `time since`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub time: Time,
+}
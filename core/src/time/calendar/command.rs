@@ -0,0 +1,485 @@
+use super::{Calendar, LeapYear, Month, Season};
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
+};
+use crate::storage::{Change, KeyValue};
+use crate::utils::CaseInsensitiveStr;
+use async_trait::async_trait;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CalendarCommand {
+    Clear,
+    Epoch { year: i32, era: Option<String> },
+    Leap { month: String, every: u16 },
+    Months { months: Vec<(String, u16)> },
+    Name { name: String },
+    NoLeap,
+    Preset { name: String },
+    Seasons { seasons: Vec<(String, String)> },
+    Show,
+    Weekdays { weekdays: Vec<String> },
+}
+
+#[async_trait(?Send)]
+impl Runnable for CalendarCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        let calendar = app_meta
+            .repository
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .calendar();
+
+        let no_calendar = || {
+            "There is no calendar set up yet. Start with a preset like `calendar harptos`, or create your own with `calendar months [name] [days], [name] [days], ...`.".to_string()
+        };
+
+        let (calendar, message) = match self {
+            Self::Show => {
+                return Ok(calendar.map_or_else(
+                    || "There is no calendar set up, so days are simply numbered. Try `calendar harptos`, `calendar greyhawk`, or `calendar gregorian`, or create your own with `calendar months [name] [days], [name] [days], ...`.".to_string(),
+                    |calendar| calendar.display_details().to_string(),
+                ));
+            }
+            Self::Clear => (None, "Days are now simply numbered.".to_string()),
+            Self::Preset { name } => {
+                let calendar = Calendar::preset(&name).ok_or_else(no_calendar)?;
+                let message = format!("Now using the {} calendar.", calendar.name);
+                (Some(calendar), message)
+            }
+            Self::Months { months } => {
+                let months = months
+                    .into_iter()
+                    .map(|(name, days)| Month::new(name, days))
+                    .collect();
+
+                if let Some(mut calendar) = calendar {
+                    // The old seasons and leap day refer to months that may no longer exist.
+                    let message = if calendar.seasons.is_empty() && calendar.leap_year.is_none() {
+                        "The months of the year have been updated.".to_string()
+                    } else {
+                        "The months of the year have been updated. Since seasons and leap years depend on the months, you will need to set them up again.".to_string()
+                    };
+
+                    calendar.months = months;
+                    calendar.seasons.clear();
+                    calendar.leap_year = None;
+                    (Some(calendar), message)
+                } else {
+                    (
+                        Some(Calendar::new("Custom".to_string(), months)),
+                        "A new calendar has been created.".to_string(),
+                    )
+                }
+            }
+            Self::Weekdays { weekdays } => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                calendar.weekdays = weekdays;
+                (
+                    Some(calendar),
+                    "The days of the week have been updated.".to_string(),
+                )
+            }
+            Self::Seasons { seasons } => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                calendar.seasons = seasons
+                    .into_iter()
+                    .map(|(name, date)| {
+                        calendar
+                            .parse_date(&date, calendar.epoch_year)
+                            .map(|date| Season {
+                                name,
+                                month: date.month,
+                                day: date.day,
+                            })
+                            .ok_or_else(|| {
+                                format!(
+                                    "\"{}\" isn't a valid date in the {} calendar.",
+                                    date, calendar.name,
+                                )
+                            })
+                    })
+                    .collect::<Result<_, _>>()?;
+                (Some(calendar), "The seasons have been updated.".to_string())
+            }
+            Self::Leap { month, every } => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                let index = calendar
+                    .month_index(&month)
+                    .ok_or_else(|| format!("There is no month named {} in the calendar.", month))?;
+
+                calendar
+                    .months
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(i, month)| month.leap_days = if i == index { 1 } else { 0 });
+                calendar.leap_year = Some(LeapYear::every(every));
+
+                let message = format!(
+                    "Every {} years, {} will have an extra day.",
+                    every, calendar.months[index].name,
+                );
+                (Some(calendar), message)
+            }
+            Self::NoLeap => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                calendar
+                    .months
+                    .iter_mut()
+                    .for_each(|month| month.leap_days = 0);
+                calendar.leap_year = None;
+                (Some(calendar), "There are no more leap years.".to_string())
+            }
+            Self::Epoch { year, era } => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                calendar.epoch_year = year;
+                calendar.era = era;
+                (
+                    Some(calendar),
+                    "The campaign now starts on the first day of that year.".to_string(),
+                )
+            }
+            Self::Name { name } => {
+                let mut calendar = calendar.ok_or_else(no_calendar)?;
+                let message = format!("The calendar is now called {}.", name);
+                calendar.name = name;
+                (Some(calendar), message)
+            }
+        };
+
+        let now = app_meta
+            .repository
+            .get_key_value(&KeyValue::Time(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .time()
+            .unwrap_or_default();
+
+        let output = format!(
+            "{} It is currently {}. Use `undo` to reverse.",
+            message,
+            now.display_long(calendar.as_ref()),
+        );
+
+        app_meta
+            .repository
+            .modify(Change::SetKeyValue {
+                key_value: KeyValue::Calendar(calendar),
+            })
+            .await
+            .map(|_| output)
+            .map_err(|_| "Storage error.".to_string())
+    }
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for CalendarCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        let args = if input.eq_ci("calendar") {
+            return CommandMatches::new_canonical(Self::Show);
+        } else if let Some(args) = input.strip_prefix_ci("calendar ") {
+            args.trim()
+        } else {
+            return CommandMatches::default();
+        };
+
+        let list = |s: &str| -> Vec<String> {
+            s.split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+
+        let command = if args.eq_ci("none") {
+            Some(Self::Clear)
+        } else if let Some(months) = args.strip_prefix_ci("months ") {
+            list(months)
+                .into_iter()
+                .map(|month| {
+                    month.rsplit_once(' ').and_then(|(name, days)| {
+                        days.parse()
+                            .ok()
+                            .map(|days| (name.trim_end().to_string(), days))
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+                .filter(|months| !months.is_empty())
+                .map(|months| Self::Months { months })
+        } else if let Some(weekdays) = args.strip_prefix_ci("weekdays ") {
+            Some(list(weekdays))
+                .filter(|weekdays| !weekdays.is_empty())
+                .map(|weekdays| Self::Weekdays { weekdays })
+        } else if let Some(seasons) = args.strip_prefix_ci("seasons ") {
+            list(seasons)
+                .into_iter()
+                .map(|season| {
+                    season
+                        .to_ascii_lowercase()
+                        .find(" from ")
+                        .map(|i| (season[..i].to_string(), season[i + 6..].to_string()))
+                })
+                .collect::<Option<Vec<_>>>()
+                .filter(|seasons| !seasons.is_empty())
+                .map(|seasons| Self::Seasons { seasons })
+        } else if let Some(leap) = args.strip_prefix_ci("leap ") {
+            if leap.eq_ci("none") {
+                Some(Self::NoLeap)
+            } else {
+                let leap = leap.strip_suffix_ci(" years").unwrap_or(leap);
+
+                leap.to_ascii_lowercase().find(" every ").and_then(|i| {
+                    leap[i + 7..]
+                        .parse()
+                        .ok()
+                        .filter(|every| every > &0)
+                        .map(|every| Self::Leap {
+                            month: leap[..i].to_string(),
+                            every,
+                        })
+                })
+            }
+        } else if let Some(epoch) = args.strip_prefix_ci("epoch ") {
+            let (year, era) = epoch
+                .split_once(' ')
+                .map_or((epoch, None), |(year, era)| (year, Some(era.trim())));
+
+            year.parse().ok().map(|year| Self::Epoch {
+                year,
+                era: era.filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            })
+        } else if let Some(name) = args.strip_prefix_ci("name ") {
+            Some(Self::Name {
+                name: name.to_string(),
+            })
+        } else {
+            Calendar::preset(args).map(|calendar| Self::Preset {
+                name: calendar.name,
+            })
+        };
+
+        command
+            .map(CommandMatches::new_canonical)
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for CalendarCommand {
+    async fn autocomplete(input: &str, _app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        [
+            ("calendar", "show the campaign calendar"),
+            ("calendar gregorian", "use the real-world calendar"),
+            ("calendar greyhawk", "use the calendar of Greyhawk"),
+            (
+                "calendar harptos",
+                "use the calendar of the Forgotten Realms",
+            ),
+            ("calendar none", "number days instead of using a calendar"),
+            (
+                "calendar months [name] [days], ...",
+                "set the months of the year",
+            ),
+            ("calendar weekdays [name], ...", "set the days of the week"),
+            (
+                "calendar seasons [name] from [date], ...",
+                "set the seasons",
+            ),
+            (
+                "calendar leap [month] every [years]",
+                "add a leap day to a month",
+            ),
+            (
+                "calendar epoch [year] [era]",
+                "set the year the campaign starts",
+            ),
+            ("calendar name [name]", "rename the calendar"),
+        ]
+        .into_iter()
+        .filter(|(term, _)| term.starts_with_ci(input))
+        .map(|(term, summary)| AutocompleteSuggestion::new(term, summary))
+        .collect()
+    }
+}
+
+impl fmt::Display for CalendarCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Clear => write!(f, "calendar none"),
+            Self::Epoch { year, era } => {
+                write!(f, "calendar epoch {}", year)?;
+                if let Some(era) = era {
+                    write!(f, " {}", era)?;
+                }
+                Ok(())
+            }
+            Self::Leap { month, every } => write!(f, "calendar leap {} every {}", month, every),
+            Self::Months { months } => write!(
+                f,
+                "calendar months {}",
+                months
+                    .iter()
+                    .map(|(name, days)| format!("{} {}", name, days))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Self::Name { name } => write!(f, "calendar name {}", name),
+            Self::NoLeap => write!(f, "calendar leap none"),
+            Self::Preset { name } => write!(f, "calendar {}", name.to_lowercase()),
+            Self::Seasons { seasons } => write!(
+                f,
+                "calendar seasons {}",
+                seasons
+                    .iter()
+                    .map(|(name, date)| format!("{} from {}", name, date))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Self::Show => write!(f, "calendar"),
+            Self::Weekdays { weekdays } => write!(f, "calendar weekdays {}", weekdays.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::{Event, NullDataStore};
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+        let parse = |input: &str| block_on(CalendarCommand::parse_input(input, &app_meta));
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Show),
+            parse("Calendar"),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Preset {
+                name: "Harptos".to_string(),
+            }),
+            parse("calendar HARPTOS"),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Months {
+                months: vec![("Long Night".to_string(), 30), ("Thaw".to_string(), 1),],
+            }),
+            parse("calendar months Long Night 30, Thaw 1,"),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Seasons {
+                seasons: vec![("Wet".to_string(), "1 Thaw".to_string())],
+            }),
+            parse("calendar seasons Wet FROM 1 Thaw"),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Leap {
+                month: "Thaw".to_string(),
+                every: 4,
+            }),
+            parse("calendar leap Thaw every 4 years"),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(CalendarCommand::Epoch {
+                year: 1491,
+                era: Some("DR".to_string()),
+            }),
+            parse("calendar epoch 1491 DR"),
+        );
+
+        assert_eq!(CommandMatches::default(), parse("calendar potato"));
+        assert_eq!(CommandMatches::default(), parse("calendar months Thaw"));
+        assert_eq!(
+            CommandMatches::default(),
+            parse("calendar leap Thaw every 0")
+        );
+        assert_eq!(CommandMatches::default(), parse("calendar seasons Wet"));
+        assert_eq!(CommandMatches::default(), parse("calendars"));
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let app_meta = app_meta();
+
+        assert_autocomplete(
+            &[
+                ("calendar gregorian", "use the real-world calendar"),
+                ("calendar greyhawk", "use the calendar of Greyhawk"),
+            ][..],
+            block_on(CalendarCommand::autocomplete("calendar gr", &app_meta)),
+        );
+
+        assert_eq!(
+            Vec::<AutocompleteSuggestion>::new(),
+            block_on(CalendarCommand::autocomplete("", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            CalendarCommand::Clear,
+            CalendarCommand::Epoch {
+                year: -12,
+                era: Some("BR".to_string()),
+            },
+            CalendarCommand::Epoch { year: 1, era: None },
+            CalendarCommand::Leap {
+                month: "Thaw".to_string(),
+                every: 4,
+            },
+            CalendarCommand::Months {
+                months: vec![("Long Night".to_string(), 30), ("Thaw".to_string(), 1)],
+            },
+            CalendarCommand::Name {
+                name: "Elder Calendar".to_string(),
+            },
+            CalendarCommand::NoLeap,
+            CalendarCommand::Preset {
+                name: "Greyhawk".to_string(),
+            },
+            CalendarCommand::Seasons {
+                seasons: vec![
+                    ("Wet".to_string(), "1 Thaw".to_string()),
+                    ("Dry".to_string(), "Long Night 15".to_string()),
+                ],
+            },
+            CalendarCommand::Show,
+            CalendarCommand::Weekdays {
+                weekdays: vec!["Moonday".to_string(), "Sunday".to_string()],
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+            assert_ne!("", command_string);
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(CalendarCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(NullDataStore::default(), &event_dispatcher)
+    }
+}
@@ -0,0 +1,853 @@
+pub use command::CalendarCommand;
+
+mod command;
+
+use crate::utils::CaseInsensitiveStr;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A campaign calendar, used to turn the day count stored in a `Time` into a date like "3 Hammer
+/// 1491 DR". Day 1 is the first day of `epoch_year`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Calendar {
+    pub name: String,
+
+    pub months: Vec<Month>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekdays: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seasons: Vec<Season>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leap_year: Option<LeapYear>,
+
+    pub epoch_year: i32,
+
+    /// The suffix written after the year, eg. "DR" for Dalereckoning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+}
+
+/// A month of the year. Festival days that fall between months, such as Midwinter in the
+/// Harptos calendar, are treated as one-day months.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Month {
+    pub name: String,
+
+    pub days: u16,
+
+    /// Days added to the month in leap years.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub leap_days: u16,
+}
+
+/// Leap years occur every `every` years, except every `except_every` years, unless every
+/// `unless_every` years. The Gregorian calendar is 4, 100, and 400 respectively.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LeapYear {
+    pub every: u16,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub except_every: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unless_every: Option<u16>,
+}
+
+/// A season, which lasts from its starting date until the next season begins.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Season {
+    pub name: String,
+    pub month: usize,
+    pub day: u16,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Date {
+    pub year: i32,
+
+    /// The index of the month in `Calendar::months`.
+    pub month: usize,
+
+    pub day: u16,
+}
+
+pub struct DateView<'a>(&'a Calendar, Date);
+
+pub struct DetailsView<'a>(&'a Calendar);
+
+impl Calendar {
+    pub const PRESETS: [&'static str; 3] = ["Gregorian", "Greyhawk", "Harptos"];
+
+    pub fn new(name: String, months: Vec<Month>) -> Self {
+        Self {
+            name,
+            months,
+            weekdays: Vec::new(),
+            seasons: Vec::new(),
+            leap_year: None,
+            epoch_year: 1,
+            era: None,
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        let months = |months: &[(&str, u16)]| -> Vec<Month> {
+            months
+                .iter()
+                .map(|(name, days)| Month::new(name.to_string(), *days))
+                .collect()
+        };
+        let seasons = |calendar: &mut Calendar, seasons: &[(&str, u16, &str)]| {
+            calendar.seasons = seasons
+                .iter()
+                .filter_map(|(name, day, month)| {
+                    Some(Season {
+                        name: name.to_string(),
+                        month: calendar.month_index(month)?,
+                        day: *day,
+                    })
+                })
+                .collect();
+        };
+
+        if name.eq_ci("Harptos") {
+            let mut calendar = Self {
+                name: "Harptos".to_string(),
+                months: months(&[
+                    ("Hammer", 30),
+                    ("Midwinter", 1),
+                    ("Alturiak", 30),
+                    ("Ches", 30),
+                    ("Tarsakh", 30),
+                    ("Greengrass", 1),
+                    ("Mirtul", 30),
+                    ("Kythorn", 30),
+                    ("Flamerule", 30),
+                    ("Midsummer", 1),
+                    ("Shieldmeet", 0),
+                    ("Eleasis", 30),
+                    ("Eleint", 30),
+                    ("Highharvestide", 1),
+                    ("Marpenoth", 30),
+                    ("Uktar", 30),
+                    ("Feast of the Moon", 1),
+                    ("Nightal", 30),
+                ]),
+                weekdays: Vec::new(),
+                seasons: Vec::new(),
+                leap_year: Some(LeapYear::every(4)),
+                epoch_year: 1491,
+                era: Some("DR".to_string()),
+            };
+            calendar.months[10].leap_days = 1;
+            seasons(
+                &mut calendar,
+                &[
+                    ("Spring", 19, "Ches"),
+                    ("Summer", 20, "Kythorn"),
+                    ("Autumn", 21, "Eleint"),
+                    ("Winter", 20, "Nightal"),
+                ],
+            );
+            Some(calendar)
+        } else if name.eq_ci("Greyhawk") {
+            let mut calendar = Self {
+                name: "Greyhawk".to_string(),
+                months: months(&[
+                    ("Needfest", 7),
+                    ("Fireseek", 28),
+                    ("Readying", 28),
+                    ("Coldeven", 28),
+                    ("Growfest", 7),
+                    ("Planting", 28),
+                    ("Flocktime", 28),
+                    ("Wealsun", 28),
+                    ("Richfest", 7),
+                    ("Reaping", 28),
+                    ("Goodmonth", 28),
+                    ("Harvester", 28),
+                    ("Brewfest", 7),
+                    ("Patchwall", 28),
+                    ("Ready'reat", 28),
+                    ("Sunsebb", 28),
+                ]),
+                weekdays: [
+                    "Starday", "Sunday", "Moonday", "Godsday", "Waterday", "Earthday", "Freeday",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+                seasons: Vec::new(),
+                leap_year: None,
+                epoch_year: 576,
+                era: Some("CY".to_string()),
+            };
+            seasons(
+                &mut calendar,
+                &[
+                    ("Spring", 1, "Readying"),
+                    ("Low Summer", 1, "Planting"),
+                    ("High Summer", 1, "Reaping"),
+                    ("Autumn", 1, "Patchwall"),
+                    ("Winter", 1, "Sunsebb"),
+                ],
+            );
+            Some(calendar)
+        } else if name.eq_ci("Gregorian") {
+            let mut calendar = Self {
+                name: "Gregorian".to_string(),
+                months: months(&[
+                    ("January", 31),
+                    ("February", 28),
+                    ("March", 31),
+                    ("April", 30),
+                    ("May", 31),
+                    ("June", 30),
+                    ("July", 31),
+                    ("August", 31),
+                    ("September", 30),
+                    ("October", 31),
+                    ("November", 30),
+                    ("December", 31),
+                ]),
+                // 1 January 2024 was a Monday.
+                weekdays: [
+                    "Monday",
+                    "Tuesday",
+                    "Wednesday",
+                    "Thursday",
+                    "Friday",
+                    "Saturday",
+                    "Sunday",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+                seasons: Vec::new(),
+                leap_year: Some(LeapYear {
+                    every: 4,
+                    except_every: Some(100),
+                    unless_every: Some(400),
+                }),
+                epoch_year: 2024,
+                era: None,
+            };
+            calendar.months[1].leap_days = 1;
+            seasons(
+                &mut calendar,
+                &[
+                    ("Spring", 20, "March"),
+                    ("Summer", 21, "June"),
+                    ("Autumn", 22, "September"),
+                    ("Winter", 21, "December"),
+                ],
+            );
+            Some(calendar)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_leap_year(&self, year: i32) -> bool {
+        matches!(&self.leap_year, Some(leap_year) if leap_year.is_leap_year(year))
+    }
+
+    pub fn month_length(&self, year: i32, month: usize) -> u16 {
+        self.months.get(month).map_or(0, |month| {
+            if self.is_leap_year(year) {
+                month.days + month.leap_days
+            } else {
+                month.days
+            }
+        })
+    }
+
+    pub fn year_length(&self, year: i32) -> i64 {
+        (0..self.months.len())
+            .map(|month| self.month_length(year, month) as i64)
+            .sum()
+    }
+
+    pub fn month_index(&self, name: &str) -> Option<usize> {
+        self.months.iter().position(|month| month.name.eq_ci(name))
+    }
+
+    /// Convert a day count (as stored in `Time`) to a date. Returns `None` if the calendar has no
+    /// days in it.
+    pub fn date(&self, days: i32) -> Option<Date> {
+        let (start_year, offset) = self.cycle_start();
+        let cycle_years = self.cycle_years();
+        let cycle_length = self.cycle_length(start_year);
+
+        if cycle_length == 0 {
+            return None;
+        }
+
+        let days = days as i64 - 1 + offset;
+        let mut year: i32 = (start_year as i64
+            + days.div_euclid(cycle_length) * cycle_years as i64)
+            .try_into()
+            .ok()?;
+        let mut remainder = days.rem_euclid(cycle_length);
+
+        loop {
+            let year_length = self.year_length(year);
+            if remainder < year_length {
+                break;
+            }
+            remainder -= year_length;
+            year = year.checked_add(1)?;
+        }
+
+        for month in 0..self.months.len() {
+            let month_length = self.month_length(year, month) as i64;
+            if remainder < month_length {
+                return Some(Date {
+                    year,
+                    month,
+                    day: remainder as u16 + 1,
+                });
+            }
+            remainder -= month_length;
+        }
+
+        None
+    }
+
+    /// Convert a date to a day count, the inverse of `date()`. Returns `None` if the date doesn't
+    /// exist, eg. "31 February".
+    pub fn days(&self, date: &Date) -> Option<i32> {
+        if date.day == 0 || date.day > self.month_length(date.year, date.month) {
+            return None;
+        }
+
+        let (start_year, offset) = self.cycle_start();
+        let cycle_years = self.cycle_years() as i64;
+        let cycles = (date.year as i64 - start_year as i64).div_euclid(cycle_years);
+        let cycle_start_year = start_year as i64 + cycles * cycle_years;
+
+        let days = cycles * self.cycle_length(start_year)
+            + (cycle_start_year..date.year as i64)
+                .map(|year| self.year_length(year as i32))
+                .sum::<i64>()
+            + (0..date.month)
+                .map(|month| self.month_length(date.year, month) as i64)
+                .sum::<i64>()
+            + date.day as i64
+            - offset;
+
+        days.try_into().ok()
+    }
+
+    pub fn weekday(&self, days: i32) -> Option<&str> {
+        if self.weekdays.is_empty() {
+            None
+        } else {
+            Some(&self.weekdays[(days as i64 - 1).rem_euclid(self.weekdays.len() as i64) as usize])
+        }
+    }
+
+    pub fn season(&self, date: &Date) -> Option<&Season> {
+        let start = |season: &&Season| (season.month, season.day);
+
+        self.seasons
+            .iter()
+            .filter(|season| (season.month, season.day) <= (date.month, date.day))
+            .max_by_key(start)
+            // Before the first season starts, we're still in the last season of the previous year.
+            .or_else(|| self.seasons.iter().max_by_key(start))
+    }
+
+    /// Parse a date such as "3 Hammer 1491 DR", "Hammer 3rd", or "Midwinter". If no year is
+    /// given, `default_year` is used.
+    pub fn parse_date(&self, input: &str, default_year: i32) -> Option<Date> {
+        let input = input.trim();
+        let input = self
+            .weekdays
+            .iter()
+            .find_map(|weekday| input.strip_prefix_ci(weekday))
+            .filter(|rest| rest.starts_with(&[',', ' '][..]))
+            .map_or(input, |rest| rest.trim_start_matches(',').trim_start());
+
+        let without_era = self
+            .era
+            .as_ref()
+            .and_then(|era| input.strip_suffix_ci(era))
+            .filter(|rest| rest.ends_with(|c: char| c.is_ascii_digit() || c == ' '));
+        let has_era = without_era.is_some();
+        let input = without_era.unwrap_or(input);
+
+        let mut numbers = Vec::new();
+        let mut words = Vec::new();
+
+        input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .for_each(|word| {
+                if let Ok(number) = ["st", "nd", "rd", "th"]
+                    .iter()
+                    .find_map(|suffix| word.strip_suffix_ci(suffix))
+                    .unwrap_or(word)
+                    .parse::<i32>()
+                {
+                    numbers.push(number);
+                } else {
+                    words.push(word);
+                }
+            });
+
+        let month_name = words.join(" ");
+        let month = self.month_index(&month_name).or_else(|| {
+            month_name
+                .strip_prefix_ci("of ")
+                .and_then(|name| self.month_index(name))
+        })?;
+        let is_festival = self.months[month].days + self.months[month].leap_days <= 1;
+
+        let (day, year) = match numbers[..] {
+            [] => (1, default_year),
+            [year] if is_festival || has_era => (1, year),
+            [day] => (day, default_year),
+            [day, year] => (day, year),
+            _ => return None,
+        };
+
+        let date = Date {
+            year,
+            month,
+            day: day.try_into().ok()?,
+        };
+
+        if self.days(&date).is_some() {
+            Some(date)
+        } else {
+            None
+        }
+    }
+
+    pub fn display_date(&self, date: Date) -> DateView {
+        DateView(self, date)
+    }
+
+    pub fn display_details(&self) -> DetailsView {
+        DetailsView(self)
+    }
+
+    fn cycle_years(&self) -> i32 {
+        self.leap_year
+            .as_ref()
+            .map_or(1, |leap_year| leap_year.cycle_years())
+    }
+
+    fn cycle_length(&self, start_year: i32) -> i64 {
+        (0..self.cycle_years())
+            .map(|i| self.year_length(start_year.saturating_add(i)))
+            .sum()
+    }
+
+    /// The first year of the leap year cycle containing the epoch year, and the number of days
+    /// from the start of that year to day 1.
+    fn cycle_start(&self) -> (i32, i64) {
+        let start_year = self.epoch_year - self.epoch_year.rem_euclid(self.cycle_years());
+
+        (
+            start_year,
+            (start_year..self.epoch_year)
+                .map(|year| self.year_length(year))
+                .sum(),
+        )
+    }
+}
+
+impl Month {
+    pub fn new(name: String, days: u16) -> Self {
+        Self {
+            name,
+            days,
+            leap_days: 0,
+        }
+    }
+}
+
+impl LeapYear {
+    pub fn every(every: u16) -> Self {
+        Self {
+            every,
+            except_every: None,
+            unless_every: None,
+        }
+    }
+
+    pub fn is_leap_year(&self, year: i32) -> bool {
+        let divisible_by = |n: Option<u16>| matches!(n, Some(n) if n > 0 && year % n as i32 == 0);
+
+        divisible_by(Some(self.every))
+            && (!divisible_by(self.except_every) || divisible_by(self.unless_every))
+    }
+
+    fn cycle_years(&self) -> i32 {
+        self.unless_every
+            .or(self.except_every)
+            .unwrap_or(self.every)
+            .max(1) as i32
+    }
+}
+
+fn is_zero(n: &u16) -> bool {
+    *n == 0
+}
+
+impl<'a> fmt::Display for DateView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (calendar, date) = (self.0, self.1);
+        let month = &calendar.months[date.month].name;
+
+        // Festival days like "Midwinter" don't need a day number.
+        if calendar.month_length(date.year, date.month) == 1 {
+            write!(f, "{} {}", month, date.year)?;
+        } else {
+            write!(f, "{} {} {}", date.day, month, date.year)?;
+        }
+
+        if let Some(era) = &calendar.era {
+            write!(f, " {}", era)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for DetailsView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let calendar = self.0;
+        let plural = |n: u16| if n == 1 { "" } else { "s" };
+
+        writeln!(f, "# Calendar: {}", calendar.name)?;
+
+        for month in &calendar.months {
+            write!(f, "\n* **{}:** ", month.name)?;
+
+            match (month.days, month.leap_days) {
+                (0, leap_days) => {
+                    write!(f, "{} day{} in leap years", leap_days, plural(leap_days))?
+                }
+                (days, 0) => write!(f, "{} day{}", days, plural(days))?,
+                (days, leap_days) => write!(
+                    f,
+                    "{} day{} ({} in leap years)",
+                    days,
+                    plural(days),
+                    days + leap_days,
+                )?,
+            }
+        }
+
+        write!(f, "\n\n**First year:** {}", calendar.epoch_year)?;
+        if let Some(era) = &calendar.era {
+            write!(f, " {}", era)?;
+        }
+
+        if let Some(leap_year) = &calendar.leap_year {
+            write!(f, "\\\n**Leap years:** every {} years", leap_year.every)?;
+
+            if let Some(except_every) = leap_year.except_every {
+                write!(f, ", except every {} years", except_every)?;
+
+                if let Some(unless_every) = leap_year.unless_every {
+                    write!(f, " unless every {} years", unless_every)?;
+                }
+            }
+        }
+
+        if !calendar.weekdays.is_empty() {
+            write!(f, "\\\n**Weekdays:** {}", calendar.weekdays.join(", "))?;
+        }
+
+        if !calendar.seasons.is_empty() {
+            write!(f, "\\\n**Seasons:** ")?;
+
+            calendar
+                .seasons
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, season)| {
+                    write!(
+                        f,
+                        "{}{} (from {} {})",
+                        if i == 0 { "" } else { ", " },
+                        season.name,
+                        season.day,
+                        calendar
+                            .months
+                            .get(season.month)
+                            .map_or("?", |month| month.name.as_str()),
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn leap_year_test() {
+        let gregorian = LeapYear {
+            every: 4,
+            except_every: Some(100),
+            unless_every: Some(400),
+        };
+
+        assert!(gregorian.is_leap_year(2024));
+        assert!(!gregorian.is_leap_year(2023));
+        assert!(!gregorian.is_leap_year(1900));
+        assert!(gregorian.is_leap_year(2000));
+        assert!(gregorian.is_leap_year(-4));
+
+        assert!(LeapYear::every(4).is_leap_year(1900));
+    }
+
+    #[test]
+    fn date_test() {
+        let harptos = harptos();
+
+        assert_eq!(Some(h(1491, "Hammer", 1)), harptos.date(1));
+        assert_eq!(Some(h(1491, "Hammer", 30)), harptos.date(30));
+        assert_eq!(Some(h(1491, "Midwinter", 1)), harptos.date(31));
+        assert_eq!(Some(h(1491, "Alturiak", 1)), harptos.date(32));
+        assert_eq!(Some(h(1492, "Hammer", 1)), harptos.date(366));
+        assert_eq!(Some(h(1490, "Nightal", 30)), harptos.date(0));
+        assert_eq!(Some(h(1489, "Nightal", 30)), harptos.date(-365));
+
+        // 1492 is a leap year, so it has Shieldmeet.
+        assert_eq!(
+            Some(h(1492, "Shieldmeet", 1)),
+            harptos.date(365 + 30 * 7 + 4),
+        );
+        assert_eq!(Some(h(1493, "Hammer", 1)), harptos.date(366 + 366));
+    }
+
+    #[test]
+    fn date_test_gregorian() {
+        let gregorian = gregorian();
+
+        assert_eq!(Some(g(2024, "January", 1)), gregorian.date(1));
+        assert_eq!(Some(g(2024, "February", 29)), gregorian.date(60));
+        assert_eq!(Some(g(2025, "January", 1)), gregorian.date(367));
+        assert_eq!(Some(g(2023, "December", 31)), gregorian.date(0));
+        assert_eq!(Some(g(1900, "February", 28)), gregorian.date(-45231));
+        assert_eq!(Some(g(1900, "March", 1)), gregorian.date(-45230));
+    }
+
+    #[test]
+    fn days_test() {
+        let (harptos, gregorian) = (harptos(), gregorian());
+
+        for days in [-100_000, -1, 0, 1, 2, 364, 365, 366, 731, 1462, 100_000] {
+            assert_eq!(
+                Some(days),
+                harptos.days(&harptos.date(days).unwrap()),
+                "{}",
+                days,
+            );
+            assert_eq!(
+                Some(days),
+                gregorian.days(&gregorian.date(days).unwrap()),
+                "{}",
+                days,
+            );
+        }
+
+        assert_eq!(None, harptos.days(&h(1491, "Shieldmeet", 1)));
+        assert_eq!(None, gregorian.days(&g(2023, "February", 29)));
+        assert_eq!(None, gregorian.days(&g(2023, "February", 0)));
+    }
+
+    #[test]
+    fn date_test_empty() {
+        let calendar = Calendar {
+            months: vec![Month::new("Never".to_string(), 0)],
+            ..harptos()
+        };
+        assert_eq!(None, calendar.date(1));
+    }
+
+    #[test]
+    fn weekday_test() {
+        let gregorian = gregorian();
+
+        assert_eq!(Some("Monday"), gregorian.weekday(1));
+        assert_eq!(Some("Sunday"), gregorian.weekday(0));
+        assert_eq!(Some("Monday"), gregorian.weekday(8));
+        assert_eq!(None, harptos().weekday(1));
+    }
+
+    #[test]
+    fn season_test() {
+        let harptos = harptos();
+        let season = |month: &str, day: u16| {
+            harptos
+                .season(&h(1491, month, day))
+                .map(|season| season.name.as_str())
+        };
+
+        assert_eq!(Some("Winter"), season("Hammer", 1));
+        assert_eq!(Some("Winter"), season("Ches", 18));
+        assert_eq!(Some("Spring"), season("Ches", 19));
+        assert_eq!(Some("Winter"), season("Nightal", 30));
+    }
+
+    #[test]
+    fn parse_date_test() {
+        let (harptos, gregorian) = (harptos(), gregorian());
+
+        assert_eq!(
+            Some(h(1491, "Hammer", 3)),
+            harptos.parse_date("3 Hammer 1491 DR", 0),
+        );
+        assert_eq!(
+            Some(h(1495, "Hammer", 3)),
+            harptos.parse_date("hammer 3rd", 1495),
+        );
+        assert_eq!(
+            Some(h(1491, "Feast of the Moon", 1)),
+            harptos.parse_date("Feast of the Moon 1491 DR", 0),
+        );
+        assert_eq!(
+            Some(h(1492, "Midwinter", 1)),
+            harptos.parse_date("midwinter, 1492", 0),
+        );
+        assert_eq!(
+            Some(h(1491, "Ches", 1)),
+            harptos.parse_date("Ches 1491 DR", 0),
+        );
+        assert_eq!(
+            Some(g(2024, "March", 14)),
+            gregorian.parse_date("Thursday, 14th of March, 2024", 0),
+        );
+        assert_eq!(
+            Some(g(2024, "March", 14)),
+            gregorian.parse_date("March 14 2024", 0),
+        );
+
+        assert_eq!(None, harptos.parse_date("31 Hammer 1491", 0));
+        assert_eq!(None, harptos.parse_date("Shieldmeet 1491", 0));
+        assert_eq!(None, harptos.parse_date("3 Potato 1491", 0));
+        assert_eq!(None, harptos.parse_date("1 2 3 Hammer", 0));
+    }
+
+    #[test]
+    fn display_date_test() {
+        let harptos = harptos();
+
+        assert_eq!(
+            "3 Hammer 1491 DR",
+            harptos.display_date(h(1491, "Hammer", 3)).to_string(),
+        );
+        assert_eq!(
+            "Midwinter 1491 DR",
+            harptos.display_date(h(1491, "Midwinter", 1)).to_string(),
+        );
+        assert_eq!(
+            "3 January 2024",
+            gregorian().display_date(g(2024, "January", 3)).to_string(),
+        );
+    }
+
+    #[test]
+    fn display_details_test() {
+        assert_eq!(
+            "# Calendar: Gregorian
+
+* **January:** 31 days
+* **February:** 28 days (29 in leap years)
+* **March:** 31 days
+* **April:** 30 days
+* **May:** 31 days
+* **June:** 30 days
+* **July:** 31 days
+* **August:** 31 days
+* **September:** 30 days
+* **October:** 31 days
+* **November:** 30 days
+* **December:** 31 days
+
+**First year:** 2024\\
+**Leap years:** every 4 years, except every 100 years unless every 400 years\\
+**Weekdays:** Monday, Tuesday, Wednesday, Thursday, Friday, Saturday, Sunday\\
+**Seasons:** Spring (from 20 March), Summer (from 21 June), Autumn (from 22 September), Winter (from 21 December)",
+            gregorian().display_details().to_string(),
+        );
+
+        let harptos = harptos().display_details().to_string();
+        assert!(
+            harptos.contains("\n* **Midwinter:** 1 day\n"),
+            "{}",
+            harptos,
+        );
+        assert!(
+            harptos.contains("\n* **Shieldmeet:** 1 day in leap years\n"),
+            "{}",
+            harptos,
+        );
+        assert!(
+            harptos.contains("\n**First year:** 1491 DR\\\n"),
+            "{}",
+            harptos,
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_test() {
+        let harptos = harptos();
+        let json = serde_json::to_string(&harptos).unwrap();
+
+        assert!(
+            json.contains(r#"{"name":"Shieldmeet","days":0,"leap_days":1}"#),
+            "{}",
+            json,
+        );
+        assert_eq!(harptos, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn preset_test() {
+        Calendar::PRESETS.iter().for_each(|name| {
+            let calendar = Calendar::preset(&name.to_uppercase()).unwrap();
+            assert_eq!(name, &calendar.name);
+            assert!(calendar.seasons.len() >= 4, "{}", name);
+        });
+
+        assert_eq!(None, Calendar::preset("potato"));
+    }
+
+    fn h(year: i32, month: &str, day: u16) -> Date {
+        date(&harptos(), year, month, day)
+    }
+
+    fn g(year: i32, month: &str, day: u16) -> Date {
+        date(&gregorian(), year, month, day)
+    }
+
+    fn date(calendar: &Calendar, year: i32, month: &str, day: u16) -> Date {
+        Date {
+            year,
+            month: calendar.month_index(month).unwrap(),
+            day,
+        }
+    }
+
+    fn harptos() -> Calendar {
+        Calendar::preset("Harptos").unwrap()
+    }
+
+    fn gregorian() -> Calendar {
+        Calendar::preset("Gregorian").unwrap()
+    }
+}
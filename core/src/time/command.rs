@@ -1,7 +1,9 @@
-use super::Interval;
+use super::effect::{spell_duration, Effect};
+use super::{describe_elapsed, Bookmark, Calendar, Interval, ScheduledEvent, Time};
 use crate::app::{
     AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
 };
+use crate::reference::Spell;
 use crate::storage::{Change, KeyValue};
 use crate::utils::CaseInsensitiveStr;
 use async_trait::async_trait;
@@ -10,34 +12,117 @@ use std::iter;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TimeCommand {
-    Add { interval: Interval },
+    Add {
+        interval: Interval,
+    },
+    AddEffect {
+        name: String,
+        target: Option<String>,
+        duration: Option<Interval>,
+        concentration: bool,
+    },
+    Agenda,
+    Effects,
+    EndEffect {
+        name: String,
+    },
+    Mark {
+        name: String,
+    },
     Now,
-    Sub { interval: Interval },
+    ScheduleAt {
+        time: String,
+        description: String,
+    },
+    ScheduleIn {
+        interval: Interval,
+        description: String,
+    },
+    Set {
+        time: String,
+    },
+    Since {
+        name: Option<String>,
+    },
+    Sub {
+        interval: Interval,
+    },
+    Until {
+        time: String,
+    },
 }
 
 #[async_trait(?Send)]
 impl Runnable for TimeCommand {
     async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
-        let time = {
-            let current_time = app_meta
-                .repository
-                .get_key_value(&KeyValue::Time(None))
-                .await
-                .map_err(|_| "Storage error.".to_string())?
-                .time()
-                .unwrap_or_default();
-
-            match &self {
-                Self::Add { interval } => current_time.checked_add(interval),
-                Self::Sub { interval } => current_time.checked_sub(interval),
-                Self::Now => {
-                    return Ok(format!("It is currently {}.", current_time.display_long()))
+        let current_time = app_meta
+            .repository
+            .get_key_value(&KeyValue::Time(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .time()
+            .unwrap_or_default();
+        let calendar = app_meta
+            .repository
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.calendar());
+
+        let time = match &self {
+            Self::Add { interval } => current_time.checked_add(interval),
+            Self::Sub { interval } => current_time.checked_sub(interval),
+            Self::Set { time } => Time::parse_absolute(time, &current_time, calendar.as_ref()),
+            Self::Until { time } => {
+                match Time::parse_next(time, &current_time, calendar.as_ref()) {
+                    Some(time) if time <= current_time => {
+                        return Err(format!(
+                            "That time has already passed. It is currently {}.",
+                            current_time.display_long(calendar.as_ref()),
+                        ));
+                    }
+                    time => time,
+                }
+            }
+            Self::Now => {
+                let mut output = format!(
+                    "It is currently {}.",
+                    current_time.display_long(calendar.as_ref()),
+                );
+
+                if let Some(season) = calendar.as_ref().and_then(|calendar| {
+                    calendar
+                        .date(current_time.days)
+                        .and_then(|date| calendar.season(&date))
+                }) {
+                    output.push_str(&format!(" It is {}.", season.name.to_lowercase()));
                 }
+
+                return Ok(output);
+            }
+            Self::AddEffect { .. } | Self::Effects | Self::EndEffect { .. } => {
+                return self
+                    .run_effect(&current_time, calendar.as_ref(), app_meta)
+                    .await;
+            }
+            Self::Mark { .. } | Self::Since { .. } => {
+                return self
+                    .run_bookmark(&current_time, calendar.as_ref(), app_meta)
+                    .await;
+            }
+            Self::Agenda | Self::ScheduleAt { .. } | Self::ScheduleIn { .. } => {
+                return self
+                    .run_schedule(&current_time, calendar.as_ref(), app_meta)
+                    .await;
             }
         };
 
         if let Some(time) = time {
-            let response = format!("It is now {}. Use `undo` to reverse.", time.display_long());
+            let mut response = format!(
+                "It is now {}. Use `undo` to reverse.",
+                time.display_long(calendar.as_ref()),
+            );
+            response.push_str(&describe_elapsed(&app_meta.repository, &current_time, &time).await);
 
             app_meta
                 .repository
@@ -57,18 +142,371 @@ impl Runnable for TimeCommand {
             Self::Sub { interval } => {
                 format!("Unable to rewind time by {}.", interval.display_long())
             }
-            Self::Now => unreachable!(),
+            Self::Set { time } | Self::Until { time } => format!(
+                "\"{}\" isn't a time I understand. Try eg. `time set day 3 at 8:00 am` or `until dawn`.",
+                time,
+            ),
+            _ => unreachable!(),
         })
     }
 }
 
+impl TimeCommand {
+    async fn run_effect(
+        self,
+        now: &Time,
+        calendar: Option<&Calendar>,
+        app_meta: &mut AppMeta,
+    ) -> Result<String, String> {
+        let mut effects: Vec<Effect> = app_meta
+            .repository
+            .get_key_value(&KeyValue::Effects(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .effects()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|effect| effect.is_active(now))
+            .collect();
+
+        let output = match self {
+            Self::AddEffect {
+                name,
+                target,
+                duration,
+                concentration,
+            } => {
+                let spell: Option<Spell> = name.parse().ok();
+
+                let duration = duration
+                    .or_else(|| spell.as_ref().and_then(spell_duration))
+                    .ok_or_else(|| match &spell {
+                        Some(spell) => format!(
+                            "{} doesn't have a fixed duration ({}). Try `{} [duration]`, eg. `{} 1h`.",
+                            spell.get_name(),
+                            spell.get_duration().to_lowercase(),
+                            name,
+                            name,
+                        ),
+                        None => format!("How long does {} last? Try `{} 1h`.", name, name),
+                    })?;
+
+                let effect = Effect {
+                    name: spell
+                        .as_ref()
+                        .map_or(name, |spell| spell.get_name().to_string()),
+                    target,
+                    expires: now.checked_add(&duration).ok_or_else(|| {
+                        format!("Unable to advance time by {}.", duration.display_long())
+                    })?,
+                    concentration: concentration
+                        || matches!(&spell, Some(spell) if spell.is_concentration()),
+                };
+
+                let output = format!(
+                    "{} will last for {}, until {}. Use `undo` to reverse.",
+                    effect.display_summary(),
+                    duration.display_long(),
+                    effect.expires.display_long(calendar),
+                );
+
+                // Recasting an effect replaces the old one rather than stacking.
+                effects.retain(|e| !(e.name.eq_ci(&effect.name) && e.target == effect.target));
+                effects.push(effect);
+                output
+            }
+            Self::Effects => {
+                return if effects.is_empty() {
+                    Ok("There are no active effects. Add one with eg. `Bless on Aria 1 minute` or `torch 1h`.".to_string())
+                } else {
+                    effects.sort_by(|a, b| a.expires.cmp(&b.expires));
+
+                    let mut output = "# Active effects\n".to_string();
+                    effects.iter().for_each(|effect| {
+                        output.push_str(&format!(
+                            "\n* {}: {} remaining",
+                            effect.display_summary(),
+                            effect
+                                .expires
+                                .checked_interval_since(now)
+                                .unwrap_or_default()
+                                .display_long(),
+                        ));
+                    });
+                    Ok(output)
+                };
+            }
+            Self::EndEffect { name } => {
+                let index = effects
+                    .iter()
+                    .position(|effect| effect.is_named(&name))
+                    .ok_or_else(|| format!("There is no active effect named \"{}\".", name))?;
+                let effect = effects.remove(index);
+
+                format!("{} has ended. Use `undo` to reverse.", effect)
+            }
+            _ => unreachable!(),
+        };
+
+        app_meta
+            .repository
+            .modify(Change::SetKeyValue {
+                key_value: KeyValue::Effects(if effects.is_empty() {
+                    None
+                } else {
+                    Some(effects)
+                }),
+            })
+            .await
+            .map(|_| output)
+            .map_err(|_| "Storage error.".to_string())
+    }
+
+    async fn run_bookmark(
+        self,
+        now: &Time,
+        calendar: Option<&Calendar>,
+        app_meta: &mut AppMeta,
+    ) -> Result<String, String> {
+        let mut bookmarks: Vec<Bookmark> = app_meta
+            .repository
+            .get_key_value(&KeyValue::Bookmarks(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .bookmarks()
+            .unwrap_or_default();
+
+        match self {
+            Self::Mark { name } => {
+                let output = format!(
+                    "Bookmarked {} at {}. Use `time since {}` to see how long ago it was, or `undo` to reverse.",
+                    name,
+                    now.display_long(calendar),
+                    name,
+                );
+
+                // Marking an existing bookmark moves it rather than creating a duplicate.
+                bookmarks.retain(|bookmark| !bookmark.name.eq_ci(&name));
+                bookmarks.push(Bookmark {
+                    name,
+                    time: now.clone(),
+                });
+
+                app_meta
+                    .repository
+                    .modify(Change::SetKeyValue {
+                        key_value: KeyValue::Bookmarks(Some(bookmarks)),
+                    })
+                    .await
+                    .map(|_| output)
+                    .map_err(|_| "Storage error.".to_string())
+            }
+            Self::Since { name: Some(name) } => bookmarks
+                .iter()
+                .find(|bookmark| bookmark.name.eq_ci(&name))
+                .map(|bookmark| describe_since(bookmark, now))
+                .ok_or_else(|| {
+                    format!(
+                        "There is no bookmark named \"{}\". Create one with `time mark {}`.",
+                        name, name,
+                    )
+                }),
+            Self::Since { name: None } => {
+                if bookmarks.is_empty() {
+                    Ok(
+                        "There are no bookmarks. Create one with eg. `time mark left Waterdeep`."
+                            .to_string(),
+                    )
+                } else {
+                    bookmarks.sort_by(|a, b| a.time.cmp(&b.time));
+
+                    let mut output = "# Bookmarks\n".to_string();
+                    bookmarks.iter().for_each(|bookmark| {
+                        output.push_str(&format!(
+                            "\n* **{}**: {}",
+                            bookmark.name,
+                            bookmark.time.display_long(calendar),
+                        ));
+                    });
+                    Ok(output)
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    async fn run_schedule(
+        self,
+        now: &Time,
+        calendar: Option<&Calendar>,
+        app_meta: &mut AppMeta,
+    ) -> Result<String, String> {
+        let mut events: Vec<ScheduledEvent> = app_meta
+            .repository
+            .get_key_value(&KeyValue::Schedule(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .schedule()
+            .unwrap_or_default();
+
+        let event = match self {
+            Self::Agenda => {
+                let mut upcoming: Vec<&ScheduledEvent> =
+                    events.iter().filter(|e| e.is_upcoming(now)).collect();
+
+                return if upcoming.is_empty() {
+                    Ok("Nothing is scheduled. Add an event with eg. `in 3d the ritual completes` or `at dawn the caravan leaves`.".to_string())
+                } else {
+                    upcoming.sort_by(|a, b| a.time.cmp(&b.time));
+
+                    let mut output = "# Agenda\n".to_string();
+                    upcoming.iter().for_each(|event| {
+                        output.push_str(&format!(
+                            "\n* **{}**: {} _(in {})_",
+                            event.time.display_long(calendar),
+                            event.description,
+                            event
+                                .time
+                                .checked_interval_since(now)
+                                .unwrap_or_default()
+                                .display_long(),
+                        ));
+                    });
+                    Ok(output)
+                };
+            }
+            Self::ScheduleAt { time, description } => {
+                let time = Time::parse_next(&time, now, calendar).ok_or_else(|| {
+                    format!(
+                        "\"{}\" isn't a time I understand. Try eg. `at day 5 8:00 am {}`.",
+                        time, description,
+                    )
+                })?;
+
+                if &time <= now {
+                    return Err(format!(
+                        "That time has already passed. It is currently {}.",
+                        now.display_long(calendar),
+                    ));
+                }
+
+                ScheduledEvent { description, time }
+            }
+            Self::ScheduleIn {
+                interval,
+                description,
+            } => ScheduledEvent {
+                description,
+                time: now.checked_add(&interval).ok_or_else(|| {
+                    format!("Unable to advance time by {}.", interval.display_long())
+                })?,
+            },
+            _ => unreachable!(),
+        };
+
+        let output = format!(
+            "Scheduled \"{}\" for {}, {} from now. Use `agenda` to see upcoming events, or `undo` to reverse.",
+            event.description,
+            event.time.display_long(calendar),
+            event
+                .time
+                .checked_interval_since(now)
+                .unwrap_or_default()
+                .display_long(),
+        );
+
+        events.push(event);
+
+        app_meta
+            .repository
+            .modify(Change::SetKeyValue {
+                key_value: KeyValue::Schedule(Some(events)),
+            })
+            .await
+            .map(|_| output)
+            .map_err(|_| "Storage error.".to_string())
+    }
+}
+
+fn describe_since(bookmark: &Bookmark, now: &Time) -> String {
+    match (
+        now.checked_interval_since(&bookmark.time),
+        bookmark.time.checked_interval_since(now),
+    ) {
+        (Some(interval), _) if interval != Interval::default() => format!(
+            "It has been {} since {}.",
+            interval.display_long(),
+            bookmark.name,
+        ),
+        (_, Some(interval)) if interval != Interval::default() => format!(
+            "{} is {} in the future.",
+            bookmark.name,
+            interval.display_long(),
+        ),
+        _ => format!("{} is right now.", bookmark.name),
+    }
+}
+
 #[async_trait(?Send)]
 impl ContextAwareParse for TimeCommand {
-    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+    async fn parse_input(input: &str, app_meta: &AppMeta) -> CommandMatches<Self> {
         if input.eq_ci("now") {
             CommandMatches::new_canonical(Self::Now)
         } else if input.in_ci(&["time", "date"]) {
             CommandMatches::new_fuzzy(Self::Now)
+        } else if input.eq_ci("effects") {
+            CommandMatches::new_canonical(Self::Effects)
+        } else if input.eq_ci("agenda") {
+            CommandMatches::new_canonical(Self::Agenda)
+        } else if let Some(args) = input.strip_prefix_ci("at ") {
+            let calendar = app_meta
+                .repository
+                .get_key_value(&KeyValue::Calendar(None))
+                .await
+                .ok()
+                .and_then(|kv| kv.calendar());
+
+            parse_schedule(args, |time| {
+                Time::parse_next(time, &Time::default(), calendar.as_ref()).is_some()
+            })
+            .map(|(time, description)| {
+                CommandMatches::new_canonical(Self::ScheduleAt {
+                    time: time.to_string(),
+                    description: description.to_string(),
+                })
+            })
+            .unwrap_or_default()
+        } else if let Some(args) = input.strip_prefix_ci("in ") {
+            parse_schedule(args, |interval| {
+                interval.starts_with(|c: char| c.is_ascii_digit())
+                    && matches!(interval.parse::<Interval>(), Ok(i) if i != Interval::default())
+            })
+            .and_then(|(interval, description)| {
+                Some(Self::ScheduleIn {
+                    interval: interval.parse().ok()?,
+                    description: description.to_string(),
+                })
+            })
+            .map(CommandMatches::new_canonical)
+            .unwrap_or_default()
+        } else if input.eq_ci("time since") {
+            CommandMatches::new_canonical(Self::Since { name: None })
+        } else if let Some(name) = input.strip_prefix_ci("time since ") {
+            CommandMatches::new_canonical(Self::Since {
+                name: Some(name.trim().to_string()),
+            })
+        } else if let Some(name) = input.strip_prefix_ci("time mark ") {
+            CommandMatches::new_canonical(Self::Mark {
+                name: name.trim().to_string(),
+            })
+        } else if let Some(time) = input.strip_prefix_ci("time set ") {
+            CommandMatches::new_canonical(Self::Set {
+                time: time.trim().to_string(),
+            })
+        } else if let Some(time) = input.strip_prefix_ci("until ") {
+            CommandMatches::new_canonical(Self::Until {
+                time: time.trim().to_string(),
+            })
         } else if let Some(canonical_match) = input
             .strip_prefix('+')
             .and_then(|s| s.parse().ok())
@@ -81,15 +519,118 @@ impl ContextAwareParse for TimeCommand {
             })
         {
             CommandMatches::new_canonical(canonical_match)
+        } else if let Some(args) = input.strip_prefix_ci("effect ") {
+            parse_effect(args, false)
+                .map(CommandMatches::new_canonical)
+                .unwrap_or_default()
+        } else if let Some(args) = input.strip_prefix_ci("concentrate on ") {
+            parse_effect(args, true)
+                .map(CommandMatches::new_canonical)
+                .unwrap_or_default()
+        } else if let Some(name) = input.strip_prefix_ci("end ") {
+            // "end" is shared with other commands (eg. "end fight"), so only match effects that
+            // actually exist.
+            let effect_exists = app_meta
+                .repository
+                .get_key_value(&KeyValue::Effects(None))
+                .await
+                .ok()
+                .and_then(|kv| kv.effects())
+                .filter(|effects| effects.iter().any(|e| e.is_named(name)))
+                .is_some();
+
+            if effect_exists {
+                CommandMatches::new_fuzzy(Self::EndEffect {
+                    name: name.to_string(),
+                })
+            } else {
+                CommandMatches::default()
+            }
         } else {
-            CommandMatches::default()
+            // Unprefixed effects like "torch 1h" are only a fuzzy match, and only if we have
+            // something more to go on than a name.
+            match parse_effect(input, false) {
+                Some(
+                    command @ Self::AddEffect {
+                        duration: Some(_), ..
+                    },
+                ) => CommandMatches::new_fuzzy(command),
+                Some(
+                    command @ Self::AddEffect {
+                        target: Some(_), ..
+                    },
+                ) if matches!(
+                    command,
+                    Self::AddEffect { ref name, .. } if name.parse::<Spell>().is_ok()
+                ) =>
+                {
+                    CommandMatches::new_fuzzy(command)
+                }
+                _ => CommandMatches::default(),
+            }
         }
     }
 }
 
+/// Split input of the form `[when] [description]`, where `[when]` is the longest run of words
+/// accepted by `is_when` and the description is whatever is left over.
+fn parse_schedule(input: &str, is_when: impl Fn(&str) -> bool) -> Option<(&str, &str)> {
+    let input = input.trim();
+
+    input
+        .match_indices(' ')
+        .map(|(i, _)| (&input[..i], input[i + 1..].trim()))
+        .filter(|(when, description)| !description.is_empty() && is_when(when))
+        .last()
+}
+
+/// Parse input of the form `[name] on [target] for [duration]`, where the target and duration are
+/// both optional and "for" can be omitted.
+fn parse_effect(input: &str, concentration: bool) -> Option<TimeCommand> {
+    let input = input.trim();
+
+    let (rest, duration) = input
+        .char_indices()
+        .filter(|(_, c)| c == &' ')
+        .find_map(|(i, _)| {
+            let duration_str = &input[i + 1..];
+
+            if duration_str.starts_with(|c: char| c.is_ascii_digit()) {
+                duration_str
+                    .parse::<Interval>()
+                    .ok()
+                    .filter(|interval| interval != &Interval::default())
+                    .map(|interval| (&input[..i], Some(interval)))
+            } else {
+                None
+            }
+        })
+        .unwrap_or((input, None));
+
+    let rest = rest.trim_end();
+    let rest = rest.strip_suffix_ci(" for").unwrap_or(rest);
+
+    let (name, target) = if let Some(i) = rest.to_lowercase().find(" on ") {
+        (rest[..i].trim(), Some(rest[i + 4..].trim()))
+    } else {
+        (rest, None)
+    };
+
+    if name.is_empty() || target == Some("") {
+        None
+    } else {
+        Some(TimeCommand::AddEffect {
+            name: name.to_string(),
+            target: target.map(|s| s.to_string()),
+            duration,
+            concentration,
+        })
+    }
+}
+
 #[async_trait(?Send)]
 impl Autocomplete for TimeCommand {
-    async fn autocomplete(input: &str, _app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+    async fn autocomplete(input: &str, app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
         if input.starts_with(&['+', '-'][..]) {
             let suggest = |suffix: &str| -> Result<AutocompleteSuggestion, ()> {
                 let term = format!("{}{}", input, suffix);
@@ -126,11 +667,74 @@ impl Autocomplete for TimeCommand {
                 .collect(),
                 _ => suggest_all().collect(),
             }
+        } else if let Some(name) = input.strip_prefix_ci("end ") {
+            app_meta
+                .repository
+                .get_key_value(&KeyValue::Effects(None))
+                .await
+                .ok()
+                .and_then(|kv| kv.effects())
+                .unwrap_or_default()
+                .iter()
+                .map(|effect| effect.to_string())
+                .filter(|effect_name| effect_name.starts_with_ci(name))
+                .map(|effect_name| {
+                    AutocompleteSuggestion::new(
+                        format!("{}{}", &input[..input.len() - name.len()], effect_name),
+                        format!("end {}", effect_name),
+                    )
+                })
+                .collect()
+        } else if let Some(time) = input.strip_prefix_ci("until ") {
+            ["midnight", "dawn", "noon", "dusk"]
+                .into_iter()
+                .filter(|term| term.starts_with_ci(time))
+                .map(|term| {
+                    AutocompleteSuggestion::new(
+                        format!("{}{}", &input[..input.len() - time.len()], term),
+                        format!("advance time until {}", term),
+                    )
+                })
+                .collect()
+        } else if let Some(name) = input.strip_prefix_ci("time since ") {
+            app_meta
+                .repository
+                .get_key_value(&KeyValue::Bookmarks(None))
+                .await
+                .ok()
+                .and_then(|kv| kv.bookmarks())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|bookmark| bookmark.name.starts_with_ci(name))
+                .map(|bookmark| {
+                    AutocompleteSuggestion::new(
+                        format!("{}{}", &input[..input.len() - name.len()], bookmark.name),
+                        format!("time since {}", bookmark.name),
+                    )
+                })
+                .collect()
         } else if !input.is_empty() {
             ["now", "time", "date"]
                 .into_iter()
                 .filter(|term| term.starts_with_ci(input))
                 .map(|term| AutocompleteSuggestion::new(term, "get the current time"))
+                .chain(
+                    [
+                        ("effect [name] [duration]", "track a timed effect"),
+                        ("effects", "list active effects"),
+                        ("concentrate on [spell]", "track a concentration effect"),
+                        ("time set [date] [time]", "set the current time"),
+                        ("until [time]", "advance time until a time of day"),
+                        ("time mark [name]", "bookmark the current time"),
+                        ("time since [name]", "time elapsed since a bookmark"),
+                        ("at [time] [event]", "schedule an event"),
+                        ("in [duration] [event]", "schedule an event"),
+                        ("agenda", "list upcoming events"),
+                    ]
+                    .into_iter()
+                    .filter(|(term, _)| term.starts_with_ci(input))
+                    .map(|(term, summary)| AutocompleteSuggestion::new(term, summary)),
+                )
                 .collect()
         } else {
             Vec::new()
@@ -142,8 +746,43 @@ impl fmt::Display for TimeCommand {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
             Self::Add { interval } => write!(f, "+{}", interval.display_short()),
+            Self::AddEffect {
+                name,
+                target,
+                duration,
+                concentration,
+            } => {
+                if *concentration {
+                    write!(f, "concentrate on {}", name)?;
+                } else {
+                    write!(f, "effect {}", name)?;
+                }
+
+                if let Some(target) = target {
+                    write!(f, " on {}", target)?;
+                }
+
+                if let Some(duration) = duration {
+                    write!(f, " {}", duration.display_short())?;
+                }
+
+                Ok(())
+            }
+            Self::Agenda => write!(f, "agenda"),
+            Self::Effects => write!(f, "effects"),
+            Self::EndEffect { name } => write!(f, "end {}", name),
+            Self::Mark { name } => write!(f, "time mark {}", name),
             Self::Now => write!(f, "now"),
+            Self::ScheduleAt { time, description } => write!(f, "at {} {}", time, description),
+            Self::ScheduleIn {
+                interval,
+                description,
+            } => write!(f, "in {} {}", interval.display_short(), description),
+            Self::Set { time } => write!(f, "time set {}", time),
+            Self::Since { name: Some(name) } => write!(f, "time since {}", name),
+            Self::Since { name: None } => write!(f, "time since"),
             Self::Sub { interval } => write!(f, "-{}", interval.display_short()),
+            Self::Until { time } => write!(f, "until {}", time),
         }
     }
 }
@@ -180,10 +819,153 @@ mod test {
             block_on(TimeCommand::parse_input("-10h", &app_meta)),
         );
 
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::Add {
+                interval: Interval::new(1, 2, 30, 0, 0),
+            }),
+            block_on(TimeCommand::parse_input("+1d2h30m", &app_meta)),
+        );
+
         assert_eq!(
             CommandMatches::default(),
             block_on(TimeCommand::parse_input("1d2h", &app_meta)),
         );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::Set {
+                time: "day 12 14:30".to_string(),
+            }),
+            block_on(TimeCommand::parse_input("time set day 12 14:30", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::Until {
+                time: "midnight".to_string(),
+            }),
+            block_on(TimeCommand::parse_input("until midnight", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::Since { name: None }),
+            block_on(TimeCommand::parse_input("time since", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::ScheduleAt {
+                time: "day 5 08:00".to_string(),
+                description: "the caravan leaves".to_string(),
+            }),
+            block_on(TimeCommand::parse_input(
+                "at day 5 08:00 the caravan leaves",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::ScheduleIn {
+                interval: Interval::new(3, 0, 0, 0, 0),
+                description: "the ritual completes".to_string(),
+            }),
+            block_on(TimeCommand::parse_input(
+                "in 3d the ritual completes",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::ScheduleIn {
+                interval: Interval::new(0, 2, 30, 0, 0),
+                description: "the guards change".to_string(),
+            }),
+            block_on(TimeCommand::parse_input(
+                "in 2 hours 30 minutes the guards change",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("at the tavern", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("in 3d", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("in a while", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::Effects),
+            block_on(TimeCommand::parse_input("effects", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_fuzzy(TimeCommand::AddEffect {
+                name: "torch".to_string(),
+                target: None,
+                duration: Some(Interval::new(0, 1, 0, 0, 0)),
+                concentration: false,
+            }),
+            block_on(TimeCommand::parse_input("torch 1h", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_fuzzy(TimeCommand::AddEffect {
+                name: "Bless".to_string(),
+                target: Some("Aria".to_string()),
+                duration: Some(Interval::new(0, 0, 1, 0, 0)),
+                concentration: false,
+            }),
+            block_on(TimeCommand::parse_input(
+                "Bless on Aria 1 minute",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::AddEffect {
+                name: "rage".to_string(),
+                target: Some("Bob".to_string()),
+                duration: Some(Interval::new(0, 0, 1, 0, 0)),
+                concentration: false,
+            }),
+            block_on(TimeCommand::parse_input(
+                "effect rage on Bob for 1 minute",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TimeCommand::AddEffect {
+                name: "Hold Person".to_string(),
+                target: Some("the ogre".to_string()),
+                duration: None,
+                concentration: true,
+            }),
+            block_on(TimeCommand::parse_input(
+                "concentrate on Hold Person on the ogre",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("torch", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("torch 0h", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TimeCommand::parse_input("end torch", &app_meta)),
+        );
     }
 
     #[test]
@@ -311,7 +1093,10 @@ mod test {
             TimeCommand::Add {
                 interval: Interval::new(2, 3, 4, 5, 6),
             },
+            TimeCommand::Agenda,
+            TimeCommand::Effects,
             TimeCommand::Now,
+            TimeCommand::Since { name: None },
             TimeCommand::Sub {
                 interval: Interval::new(2, 3, 4, 5, 6),
             },
@@ -338,6 +1123,59 @@ mod test {
                 command_string.to_uppercase(),
             );
         });
+
+        // Names, targets, and times keep their case, so only check the round trip as written.
+        [
+            TimeCommand::AddEffect {
+                name: "Bless".to_string(),
+                target: Some("Aria".to_string()),
+                duration: Some(Interval::new(0, 0, 1, 0, 0)),
+                concentration: false,
+            },
+            TimeCommand::AddEffect {
+                name: "torch".to_string(),
+                target: None,
+                duration: None,
+                concentration: false,
+            },
+            TimeCommand::AddEffect {
+                name: "Hold Person".to_string(),
+                target: Some("the ogre".to_string()),
+                duration: Some(Interval::new(0, 0, 0, 0, 10)),
+                concentration: true,
+            },
+            TimeCommand::Mark {
+                name: "left Waterdeep".to_string(),
+            },
+            TimeCommand::ScheduleAt {
+                time: "dawn".to_string(),
+                description: "the caravan leaves".to_string(),
+            },
+            TimeCommand::ScheduleIn {
+                interval: Interval::new(1, 2, 30, 0, 0),
+                description: "The ritual completes".to_string(),
+            },
+            TimeCommand::Set {
+                time: "day 12 at 2:30 pm".to_string(),
+            },
+            TimeCommand::Since {
+                name: Some("left Waterdeep".to_string()),
+            },
+            TimeCommand::Until {
+                time: "dawn".to_string(),
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(TimeCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
     }
 
     fn event_dispatcher(_event: Event) {}
@@ -0,0 +1,179 @@
+use super::{Interval, Time};
+use crate::reference::Spell;
+use crate::utils::CaseInsensitiveStr;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A spell, condition, light source, or anything else that lasts for a set amount of game time.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Effect {
+    pub name: String,
+
+    /// The creature affected, if any. Effects without a target apply to the party as a whole.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    pub expires: Time,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub concentration: bool,
+}
+
+pub struct EffectSummaryView<'a>(&'a Effect);
+
+impl Effect {
+    pub fn is_active(&self, now: &Time) -> bool {
+        &self.expires > now
+    }
+
+    /// Does `name` refer to this effect? Matches either the effect name alone ("Bless") or the
+    /// name and target ("Bless on Aria").
+    pub fn is_named(&self, name: &str) -> bool {
+        if self.name.eq_ci(name) {
+            return true;
+        }
+
+        if let (Some(target), Some(rest)) = (&self.target, name.strip_prefix_ci(&self.name)) {
+            if let Some(rest_target) = rest.strip_prefix_ci(" on ") {
+                return rest_target.eq_ci(target);
+            }
+        }
+
+        false
+    }
+
+    pub fn display_summary(&self) -> EffectSummaryView {
+        EffectSummaryView(self)
+    }
+}
+
+/// Parse the SRD duration of a spell, eg. "Up to 1 minute". Returns `None` for durations that
+/// don't correspond to a fixed amount of time, such as "Instantaneous" or "Until dispelled".
+pub fn spell_duration(spell: &Spell) -> Option<Interval> {
+    let duration = spell.get_duration();
+
+    duration
+        .strip_prefix_ci("up to ")
+        .unwrap_or(duration)
+        .parse()
+        .ok()
+}
+
+/// Get the effects ending after `from`, up to and including `to`, in the order they ended.
+pub fn ended_between<'a>(effects: &'a [Effect], from: &Time, to: &Time) -> Vec<&'a Effect> {
+    let mut ended: Vec<&Effect> = effects
+        .iter()
+        .filter(|effect| &effect.expires > from && &effect.expires <= to)
+        .collect();
+    ended.sort_by(|a, b| a.expires.cmp(&b.expires));
+    ended
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(target) = &self.target {
+            write!(f, "{} on {}", self.name, target)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl<'a> fmt::Display for EffectSummaryView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let effect = self.0;
+
+        write!(f, "**{}**", effect.name)?;
+
+        if let Some(target) = &effect.target {
+            write!(f, " on {}", target)?;
+        }
+
+        if effect.concentration {
+            write!(f, " _(concentration)_")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_named_test() {
+        let bless = effect("Bless", Some("Aria"), 1);
+        assert!(bless.is_named("bless"));
+        assert!(bless.is_named("BLESS on aria"));
+        assert!(!bless.is_named("Bless on Bob"));
+        assert!(!bless.is_named("Bless on"));
+
+        let torch = effect("torch", None, 1);
+        assert!(torch.is_named("Torch"));
+        assert!(!torch.is_named("torch on Aria"));
+    }
+
+    #[test]
+    fn ended_between_test() {
+        let effects = [
+            effect("torch", None, 3),
+            effect("Bless", Some("Aria"), 2),
+            effect("Mage Armor", None, 5),
+        ];
+
+        assert_eq!(
+            vec!["Bless", "torch"],
+            ended_between(&effects, &t(1), &t(3))
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+        );
+        assert!(ended_between(&effects, &t(3), &t(4)).is_empty());
+    }
+
+    #[test]
+    fn display_summary_test() {
+        assert_eq!(
+            "**Bless** on Aria _(concentration)_",
+            Effect {
+                concentration: true,
+                ..effect("Bless", Some("Aria"), 1)
+            }
+            .display_summary()
+            .to_string(),
+        );
+        assert_eq!(
+            "**torch**",
+            effect("torch", None, 1).display_summary().to_string(),
+        );
+    }
+
+    #[test]
+    fn serialize_deserialize_test() {
+        let bless = Effect {
+            concentration: true,
+            ..effect("Bless", Some("Aria"), 1)
+        };
+        let json = serde_json::to_string(&bless).unwrap();
+
+        assert_eq!(
+            r#"{"name":"Bless","target":"Aria","expires":"1:08:01:00","concentration":true}"#,
+            json,
+        );
+        assert_eq!(bless, serde_json::from_str(&json).unwrap());
+    }
+
+    fn effect(name: &str, target: Option<&str>, minutes: u8) -> Effect {
+        Effect {
+            name: name.to_string(),
+            target: target.map(|s| s.to_string()),
+            expires: t(minutes),
+            concentration: false,
+        }
+    }
+
+    fn t(minutes: u8) -> Time {
+        Time::try_new(1, 8, minutes, 0).unwrap()
+    }
+}
@@ -66,11 +66,59 @@ impl AddAssign for Interval {
     }
 }
 
+impl Interval {
+    /// Rewrite spelled-out units in the shorthand understood by `from_str`, eg. "1 hour and 30
+    /// minutes" becomes "1h 30m". Lone unit letters following a space ("1 d") are deliberately not
+    /// rewritten, nor are two-letter abbreviations attached to a number ("1hr"), which would be
+    /// ambiguous with the shorthand.
+    fn normalize_units(raw: &str) -> String {
+        let unit = |word: &str| -> Option<char> {
+            match word.to_lowercase().as_str() {
+                "day" | "days" => Some('d'),
+                "hour" | "hours" | "hr" | "hrs" => Some('h'),
+                "minute" | "minutes" | "min" | "mins" => Some('m'),
+                "second" | "seconds" | "sec" | "secs" => Some('s'),
+                "round" | "rounds" => Some('r'),
+                _ => None,
+            }
+        };
+
+        let mut words = raw
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty() && !word.eq_ignore_ascii_case("and"))
+            .peekable();
+        let mut parts = Vec::new();
+
+        while let Some(word) = words.next() {
+            let digits_end = word
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(word.len());
+
+            if digits_end == word.len() {
+                if let Some(c) = words.peek().and_then(|next| unit(next)) {
+                    words.next();
+                    parts.push(format!("{}{}", word, c));
+                    continue;
+                }
+            } else if digits_end > 0 && word.len() - digits_end > 2 {
+                if let Some(c) = unit(&word[digits_end..]) {
+                    parts.push(format!("{}{}", &word[..digits_end], c));
+                    continue;
+                }
+            }
+
+            parts.push(word.to_string());
+        }
+
+        parts.join(" ")
+    }
+}
+
 impl FromStr for Interval {
     type Err = ();
 
     fn from_str(raw: &str) -> Result<Self, Self::Err> {
-        match raw.trim() {
+        match Self::normalize_units(raw).as_str() {
             "" => Err(()),
             "0" => Ok(Interval::default()),
             s => {
@@ -242,7 +290,15 @@ mod test {
         assert_eq!(Ok(i(2, 3, 4, 5, 6)), "2d3h4m5s6r".parse());
         assert_eq!(Ok(i(2, 3, 4, 5, 6)), "2d 3h 4m 5s 6r".parse());
 
+        assert_eq!(Ok(minutes(1)), "1 minute".parse());
+        assert_eq!(Ok(hours(8)), "8 Hours".parse());
+        assert_eq!(Ok(rounds(10)), "10rounds".parse());
+        assert_eq!(Ok(i(1, 2, 30, 0, 0)), "1 day, 2 hrs and 30 mins".parse());
+        assert_eq!(Ok(i(0, 1, 30, 0, 0)), "1h 30 minutes".parse());
+
         assert_eq!(Err(()), format!("{}d", i64::MAX).parse::<Interval>());
+        assert_eq!(Err(()), "minute".parse::<Interval>());
+        assert_eq!(Err(()), "1 minute 1m".parse::<Interval>());
         assert_eq!(Err(()), "".parse::<Interval>());
         assert_eq!(Err(()), "1 d".parse::<Interval>());
         assert_eq!(Err(()), "1a".parse::<Interval>());
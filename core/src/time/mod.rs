@@ -1,13 +1,25 @@
+pub use bookmark::Bookmark;
+pub use calendar::{Calendar, CalendarCommand};
 pub use command::TimeCommand;
+pub use effect::Effect;
 pub use interval::Interval;
+pub use schedule::ScheduledEvent;
 
+mod bookmark;
+mod calendar;
 mod command;
+mod effect;
 mod interval;
+mod schedule;
 
+use crate::storage::{KeyValue, Repository};
+use crate::utils::CaseInsensitiveStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct Time {
     days: i32,
     hours: u8,
@@ -15,9 +27,12 @@ pub struct Time {
     seconds: u8,
 }
 
+/// Hours, minutes, and seconds.
+type TimeOfDay = (u8, u8, u8);
+
 pub struct TimeShortView<'a>(&'a Time);
 
-pub struct TimeLongView<'a>(&'a Time);
+pub struct TimeLongView<'a>(&'a Time, Option<&'a Calendar>);
 
 impl Time {
     pub fn try_new(days: i32, hours: u8, minutes: u8, seconds: u8) -> Result<Self, ()> {
@@ -33,6 +48,11 @@ impl Time {
         }
     }
 
+    /// The day number, counting from day 1.
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
     pub fn checked_add(&self, interval: &Interval) -> Option<Self> {
         let (mut days, mut hours, mut minutes, mut seconds) = (
             (self.days as i64) + (interval.days as i64),
@@ -97,12 +117,223 @@ impl Time {
         }
     }
 
+    /// The amount of time that has passed since `earlier`, or `None` if `earlier` is actually
+    /// later than `self`.
+    pub fn checked_interval_since(&self, earlier: &Time) -> Option<Interval> {
+        let seconds = self.as_seconds() - earlier.as_seconds();
+
+        if seconds < 0 {
+            None
+        } else {
+            Some(Interval::new(
+                (seconds / 86400).try_into().ok()?,
+                ((seconds / 3600) % 24) as i32,
+                ((seconds / 60) % 60) as i32,
+                (seconds % 60) as i32,
+                0,
+            ))
+        }
+    }
+
     pub fn display_short(&self) -> TimeShortView {
         TimeShortView(self)
     }
 
-    pub fn display_long(&self) -> TimeLongView {
-        TimeLongView(self)
+    /// Display the time as a human-readable date, using the campaign calendar if there is one,
+    /// or simply numbering days if not.
+    pub fn display_long<'a>(&'a self, calendar: Option<&'a Calendar>) -> TimeLongView<'a> {
+        TimeLongView(self, calendar)
+    }
+
+    /// Parse a time in the format produced by `display_long()`, eg. "day 3 at 8:00:00 am" or "3
+    /// Hammer 1491 DR at 8:00 am". Day numbers are always accepted, even if a calendar is in use.
+    pub fn parse_long(input: &str, calendar: Option<&Calendar>) -> Option<Self> {
+        let split_at = input.to_ascii_lowercase().rfind(" at ")?;
+        let (date, time_of_day) = (&input[..split_at], &input[split_at + 4..]);
+
+        let days = parse_day(date, &Time::default(), calendar)?;
+        let (hours, minutes, seconds) = parse_time_of_day(time_of_day)?;
+        Time::try_new(days, hours, minutes, seconds).ok()
+    }
+
+    /// Parse user input like "day 12 14:30", "3 Hammer at dusk", or "dawn" as an absolute time.
+    /// If only a date is given, the time of day stays the same, and if only a time of day is given,
+    /// the date stays the same.
+    pub fn parse_absolute(input: &str, now: &Time, calendar: Option<&Calendar>) -> Option<Self> {
+        let (days, time_of_day) = parse_date_and_time(input, now, calendar)?;
+        let (hours, minutes, seconds) =
+            time_of_day.unwrap_or((now.hours, now.minutes, now.seconds));
+
+        Time::try_new(days.unwrap_or(now.days), hours, minutes, seconds).ok()
+    }
+
+    /// Parse user input as the next time after `now` that matches it. A time of day alone, like
+    /// "midnight", means the next time the clock reads that, while a date with no time of day
+    /// means the start of that day. Unlike `parse_absolute()`, the result may be in the past if a
+    /// past date was given explicitly.
+    pub fn parse_next(input: &str, now: &Time, calendar: Option<&Calendar>) -> Option<Self> {
+        match parse_date_and_time(input, now, calendar)? {
+            (None, Some((hours, minutes, seconds))) => {
+                let time = Time::try_new(now.days, hours, minutes, seconds).ok()?;
+
+                if &time > now {
+                    Some(time)
+                } else {
+                    time.checked_add(&Interval::new_days(1))
+                }
+            }
+            (Some(days), time_of_day) => {
+                let (hours, minutes, seconds) = time_of_day.unwrap_or((0, 0, 0));
+                Time::try_new(days, hours, minutes, seconds).ok()
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+/// Parse a date and/or time of day, in the forms "[date] at [time]", "[date] [time]", "[date]",
+/// or "[time]". At least one of the two will be present in the result.
+fn parse_date_and_time(
+    input: &str,
+    now: &Time,
+    calendar: Option<&Calendar>,
+) -> Option<(Option<i32>, Option<TimeOfDay>)> {
+    let input = input.trim();
+
+    if let Some(time_of_day) = parse_time_of_day(input) {
+        return Some((None, Some(time_of_day)));
+    }
+
+    if let Some(split_at) = input.to_ascii_lowercase().rfind(" at ") {
+        return Some((
+            Some(parse_day(&input[..split_at], now, calendar)?),
+            Some(parse_time_of_day(&input[split_at + 4..])?),
+        ));
+    }
+
+    // The time of day may be one or two words at the end, eg. "14:30" or "2:30 pm".
+    if let Some(result) = input.rmatch_indices(' ').take(2).find_map(|(split_at, _)| {
+        Some((
+            Some(parse_day(&input[..split_at], now, calendar)?),
+            Some(parse_time_of_day(&input[split_at + 1..])?),
+        ))
+    }) {
+        return Some(result);
+    }
+
+    Some((Some(parse_day(input, now, calendar)?), None))
+}
+
+/// Parse a day, either as a day number ("day 3"), relative to `now` ("tomorrow"), or as a date in
+/// the campaign calendar ("3 Hammer"). Dates without a year are assumed to be in the current year.
+fn parse_day(input: &str, now: &Time, calendar: Option<&Calendar>) -> Option<i32> {
+    let input = input.trim();
+
+    if let Some(days) = input.strip_prefix_ci("day ") {
+        days.trim().parse().ok()
+    } else if input.eq_ci("today") {
+        Some(now.days)
+    } else if input.eq_ci("tomorrow") {
+        now.days.checked_add(1)
+    } else if input.eq_ci("yesterday") {
+        now.days.checked_sub(1)
+    } else {
+        let calendar = calendar?;
+        let year = calendar.date(now.days)?.year;
+        calendar.days(&calendar.parse_date(input, year)?)
+    }
+}
+
+/// Describe anything that happened while the clock advanced from `from` to `to`, such as effects
+/// ending or scheduled events occurring. The result is empty if there is nothing to report, or is
+/// otherwise formatted to be appended to a command's output.
+pub async fn describe_elapsed(repository: &Repository, from: &Time, to: &Time) -> String {
+    let mut output = String::new();
+
+    let effects = repository
+        .get_key_value(&KeyValue::Effects(None))
+        .await
+        .ok()
+        .and_then(|kv| kv.effects())
+        .unwrap_or_default();
+    let ended = effect::ended_between(&effects, from, to);
+
+    if !ended.is_empty() {
+        output.push_str("\n\nThe following effects have ended:\n");
+        ended.iter().for_each(|effect| {
+            output.push_str(&format!("\n* {}", effect.display_summary()));
+        });
+    }
+
+    let events = repository
+        .get_key_value(&KeyValue::Schedule(None))
+        .await
+        .ok()
+        .and_then(|kv| kv.schedule())
+        .unwrap_or_default();
+    let occurred = schedule::occurred_between(&events, from, to);
+
+    if !occurred.is_empty() {
+        output.push_str("\n\nThe following events have occurred:\n");
+        occurred.iter().for_each(|event| {
+            output.push_str(&format!("\n* {}", event));
+        });
+    }
+
+    output
+}
+
+/// Parse a time of day like "8:00:00 am", "8pm", "14:30", or "dawn". A bare number like "8" is
+/// not accepted, since it's too easily confused with a date.
+fn parse_time_of_day(input: &str) -> Option<TimeOfDay> {
+    let input = input.trim().to_ascii_lowercase();
+
+    match input.as_str() {
+        "midnight" => return Some((0, 0, 0)),
+        "dawn" | "sunrise" => return Some((6, 0, 0)),
+        "noon" | "midday" => return Some((12, 0, 0)),
+        "dusk" | "sunset" => return Some((18, 0, 0)),
+        _ => {}
+    }
+
+    let (input, pm) = if let Some(input) = input.strip_suffix("am") {
+        (input.trim_end(), Some(false))
+    } else if let Some(input) = input.strip_suffix("pm") {
+        (input.trim_end(), Some(true))
+    } else {
+        (input.as_str(), None)
+    };
+
+    let mut parts = input.split(':');
+    let hours: u8 = parts.next()?.parse().ok()?;
+    let minutes: u8 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+    let seconds: u8 = parts.next().map_or(Some(0), |s| s.parse().ok())?;
+
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    if pm.is_none() && !input.contains(':') {
+        return None;
+    }
+
+    let hours = match (hours, pm) {
+        (1..=11, Some(true)) => hours + 12,
+        (12, Some(false)) => 0,
+        (1..=12, Some(_)) => hours,
+        (0..=23, None) => hours,
+        _ => return None,
+    };
+
+    Some((hours, minutes, seconds))
+}
+
+impl Time {
+    fn as_seconds(&self) -> i64 {
+        self.days as i64 * 86400
+            + self.hours as i64 * 3600
+            + self.minutes as i64 * 60
+            + self.seconds as i64
     }
 }
 
@@ -136,6 +367,20 @@ impl FromStr for Time {
     }
 }
 
+impl Serialize for Time {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.display_short())
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|_| serde::de::Error::custom("invalid time"))
+    }
+}
+
 impl<'a> fmt::Display for TimeShortView<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let time = self.0;
@@ -158,10 +403,22 @@ impl<'a> fmt::Display for TimeLongView<'a> {
             _ => (time.hours % 12, "pm"),
         };
 
+        if let Some((calendar, date)) = self
+            .1
+            .and_then(|calendar| calendar.date(time.days).map(|date| (calendar, date)))
+        {
+            if let Some(weekday) = calendar.weekday(time.days) {
+                write!(f, "{}, ", weekday)?;
+            }
+            write!(f, "{}", calendar.display_date(date))?;
+        } else {
+            write!(f, "day {}", time.days)?;
+        }
+
         write!(
             f,
-            "day {} at {}:{:02}:{:02} {}",
-            time.days, hours, time.minutes, time.seconds, am_pm
+            " at {}:{:02}:{:02} {}",
+            hours, time.minutes, time.seconds, am_pm
         )
     }
 }
@@ -302,6 +559,43 @@ mod test {
         assert!(t0().checked_sub(&Interval::new_seconds(i32::MIN)).is_none());
     }
 
+    #[test]
+    fn time_checked_interval_since_test() {
+        assert_eq!(
+            Some(Interval::new(1, 2, 3, 4, 0)),
+            t(2, 10, 3, 4).checked_interval_since(&t(1, 8, 0, 0)),
+        );
+        assert_eq!(
+            Some(Interval::new(0, 23, 59, 59, 0)),
+            t(2, 0, 0, 0).checked_interval_since(&t(1, 0, 0, 1)),
+        );
+        assert_eq!(
+            Some(Interval::default()),
+            t1().checked_interval_since(&t1()),
+        );
+        assert_eq!(None, t0().checked_interval_since(&t1()));
+    }
+
+    #[test]
+    fn time_ord_test() {
+        assert!(t(1, 0, 0, 0) > t(0, 23, 59, 59));
+        assert!(t(1, 1, 0, 0) > t(1, 0, 59, 59));
+        assert!(t(-1, 0, 0, 0) < t0());
+    }
+
+    #[test]
+    fn time_serialize_deserialize_test() {
+        assert_eq!(
+            r#""1:02:03:04""#,
+            serde_json::to_string(&t(1, 2, 3, 4)).unwrap(),
+        );
+        assert_eq!(
+            t(1, 2, 3, 4),
+            serde_json::from_str::<Time>(r#""1:02:03:04""#).unwrap(),
+        );
+        assert!(serde_json::from_str::<Time>(r#""1:24:00:00""#).is_err());
+    }
+
     #[test]
     fn time_display_short_test() {
         assert_eq!("1:02:03:04", t(1, 2, 3, 4).display_short().to_string());
@@ -310,27 +604,148 @@ mod test {
 
     #[test]
     fn time_display_long_test() {
-        assert_eq!("day 0 at 12:00:00 am", t0().display_long().to_string());
+        assert_eq!("day 0 at 12:00:00 am", t0().display_long(None).to_string());
         assert_eq!(
             "day 1 at 1:02:03 am",
-            t(1, 1, 2, 3).display_long().to_string(),
+            t(1, 1, 2, 3).display_long(None).to_string(),
         );
         assert_eq!(
             "day 2 at 11:59:59 am",
-            t(2, 11, 59, 59).display_long().to_string(),
+            t(2, 11, 59, 59).display_long(None).to_string(),
         );
         assert_eq!(
             "day 3 at 12:00:00 pm",
-            t(3, 12, 0, 0).display_long().to_string(),
+            t(3, 12, 0, 0).display_long(None).to_string(),
         );
         assert_eq!(
             "day 4 at 1:00:00 pm",
-            t(4, 13, 0, 0).display_long().to_string(),
+            t(4, 13, 0, 0).display_long(None).to_string(),
         );
         assert_eq!(
             "day 5 at 11:59:59 pm",
-            t(5, 23, 59, 59).display_long().to_string(),
+            t(5, 23, 59, 59).display_long(None).to_string(),
+        );
+    }
+
+    #[test]
+    fn time_display_long_test_calendar() {
+        let harptos = Calendar::preset("harptos").unwrap();
+        let greyhawk = Calendar::preset("greyhawk").unwrap();
+
+        assert_eq!(
+            "3 Hammer 1491 DR at 8:00:00 am",
+            t(3, 8, 0, 0).display_long(Some(&harptos)).to_string(),
+        );
+        assert_eq!(
+            "Midwinter 1491 DR at 8:00:00 am",
+            t(31, 8, 0, 0).display_long(Some(&harptos)).to_string(),
+        );
+        assert_eq!(
+            "Moonday, 3 Needfest 576 CY at 8:00:00 am",
+            t(3, 8, 0, 0).display_long(Some(&greyhawk)).to_string(),
+        );
+    }
+
+    #[test]
+    fn time_parse_long_test() {
+        let harptos = Calendar::preset("harptos").unwrap();
+
+        [t(1, 8, 0, 0), t(-400, 0, 0, 0), t(1000, 23, 59, 59)]
+            .iter()
+            .for_each(|time| {
+                let long = time.display_long(None).to_string();
+                assert_eq!(
+                    Some(time),
+                    Time::parse_long(&long, None).as_ref(),
+                    "{}",
+                    long
+                );
+                assert_eq!(
+                    Some(time),
+                    Time::parse_long(&long, Some(&harptos)).as_ref(),
+                    "{}",
+                    long,
+                );
+
+                let long = time.display_long(Some(&harptos)).to_string();
+                assert_eq!(
+                    Some(time),
+                    Time::parse_long(&long, Some(&harptos)).as_ref(),
+                    "{}",
+                    long,
+                );
+            });
+
+        assert_eq!(
+            Some(t(3, 14, 30, 0)),
+            Time::parse_long("3 hammer 1491 DR at 2:30 pm", Some(&harptos)),
+        );
+        assert_eq!(
+            Some(t(3, 0, 15, 0)),
+            Time::parse_long("Day 3 at 12:15am", None),
+        );
+        assert_eq!(
+            Some(t(3, 14, 30, 0)),
+            Time::parse_long("day 3 at 14:30", None),
+        );
+
+        assert_eq!(None, Time::parse_long("3 Hammer 1491 DR at 8:00 am", None));
+        assert_eq!(None, Time::parse_long("day 3", None));
+        assert_eq!(None, Time::parse_long("day 3 at 13 pm", None));
+        assert_eq!(None, Time::parse_long("day 3 at 24:00", None));
+    }
+
+    #[test]
+    fn time_parse_absolute_test() {
+        let harptos = Calendar::preset("harptos").unwrap();
+        let now = t(2, 10, 20, 30);
+
+        assert_eq!(
+            Some(t(12, 14, 30, 0)),
+            Time::parse_absolute("day 12 14:30", &now, None),
+        );
+        assert_eq!(
+            Some(t(12, 14, 30, 0)),
+            Time::parse_absolute("day 12 at 2:30 pm", &now, None),
+        );
+        assert_eq!(
+            Some(t(12, 10, 20, 30)),
+            Time::parse_absolute("day 12", &now, None),
+        );
+        assert_eq!(
+            Some(t(2, 6, 0, 0)),
+            Time::parse_absolute("dawn", &now, None),
+        );
+        assert_eq!(
+            Some(t(3, 18, 0, 0)),
+            Time::parse_absolute("tomorrow at dusk", &now, None),
+        );
+        assert_eq!(
+            Some(t(5, 0, 0, 0)),
+            Time::parse_absolute("5 Hammer midnight", &now, Some(&harptos)),
+        );
+
+        assert_eq!(None, Time::parse_absolute("5 Hammer", &now, None));
+        assert_eq!(None, Time::parse_absolute("day 12 8", &now, None));
+        assert_eq!(None, Time::parse_absolute("teatime", &now, None));
+    }
+
+    #[test]
+    fn time_parse_next_test() {
+        let now = t(2, 10, 20, 30);
+
+        assert_eq!(
+            Some(t(3, 0, 0, 0)),
+            Time::parse_next("midnight", &now, None)
+        );
+        assert_eq!(Some(t(2, 12, 0, 0)), Time::parse_next("noon", &now, None));
+        assert_eq!(Some(t(3, 6, 0, 0)), Time::parse_next("dawn", &now, None));
+        assert_eq!(
+            Some(t(3, 10, 20, 30)),
+            Time::parse_next("10:20:30", &now, None),
         );
+        assert_eq!(Some(t(5, 0, 0, 0)), Time::parse_next("day 5", &now, None));
+        assert_eq!(Some(t(1, 0, 0, 0)), Time::parse_next("day 1", &now, None));
     }
 
     #[test]
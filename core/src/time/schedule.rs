@@ -0,0 +1,86 @@
+use super::Time;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Something that is going to happen at a given point in game time, such as "the caravan
+/// leaves". Events stay on the schedule after they happen, so rewinding and replaying time will
+/// trigger them again.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScheduledEvent {
+    pub description: String,
+    pub time: Time,
+}
+
+impl ScheduledEvent {
+    pub fn is_upcoming(&self, now: &Time) -> bool {
+        &self.time > now
+    }
+}
+
+/// Get the events occurring after `from`, up to and including `to`, in chronological order.
+pub fn occurred_between<'a>(
+    events: &'a [ScheduledEvent],
+    from: &Time,
+    to: &Time,
+) -> Vec<&'a ScheduledEvent> {
+    let mut occurred: Vec<&ScheduledEvent> = events
+        .iter()
+        .filter(|event| &event.time > from && &event.time <= to)
+        .collect();
+    occurred.sort_by(|a, b| a.time.cmp(&b.time));
+    occurred
+}
+
+impl fmt::Display for ScheduledEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_upcoming_test() {
+        let caravan = event("the caravan leaves", 3);
+        assert!(caravan.is_upcoming(&t(2)));
+        assert!(!caravan.is_upcoming(&t(3)));
+        assert!(!caravan.is_upcoming(&t(4)));
+    }
+
+    #[test]
+    fn occurred_between_test() {
+        let events = [
+            event("the ritual completes", 5),
+            event("the caravan leaves", 3),
+            event("the moon is full", 8),
+        ];
+
+        let descriptions = |from: i32, to: i32| -> Vec<&str> {
+            occurred_between(&events, &t(from), &t(to))
+                .iter()
+                .map(|event| event.description.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            vec!["the caravan leaves", "the ritual completes"],
+            descriptions(2, 5),
+        );
+        assert_eq!(vec!["the moon is full"], descriptions(5, 10));
+        assert!(descriptions(8, 10).is_empty());
+        assert!(descriptions(10, 1).is_empty());
+    }
+
+    fn event(description: &str, days: i32) -> ScheduledEvent {
+        ScheduledEvent {
+            description: description.to_string(),
+            time: t(days),
+        }
+    }
+
+    fn t(days: i32) -> Time {
+        Time::try_new(days, 0, 0, 0).unwrap()
+    }
+}
@@ -0,0 +1,449 @@
+use super::{Mode, Pace, Trip};
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
+};
+use crate::storage::{Change, KeyValue};
+use crate::time::{self, Interval};
+use crate::utils::{ordinal, CaseInsensitiveStr};
+use crate::world::{Thing, ThingRelations};
+use async_trait::async_trait;
+use rand::Rng;
+use std::fmt;
+
+/// The length of a watch, for the purposes of rolling random encounters.
+const WATCH_HOURS: i32 = 4;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TravelCommand {
+    pub destination: Option<String>,
+    pub miles: Option<u32>,
+    pub pace: Option<Pace>,
+    pub mode: Option<Mode>,
+
+    /// Travel without stopping to rest, even if that means a forced march.
+    pub forced: bool,
+
+    /// Roll for random encounters during each watch of the journey.
+    pub encounters: bool,
+}
+
+#[async_trait(?Send)]
+impl Runnable for TravelCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        let pace = self.pace.unwrap_or(Pace::Normal);
+        let mode = self.mode.unwrap_or(Mode::Foot);
+
+        // Without a distance, travelling to a named destination takes a day.
+        let miles = match (self.miles, &self.destination) {
+            (Some(miles), _) => miles,
+            (None, Some(_)) => mode.miles_per_day(pace),
+            (None, None) => return Err("How far are you going? Try `travel 20 miles`.".to_string()),
+        };
+
+        let trip = Trip::new(miles, pace, mode, self.forced)
+            .ok_or_else(|| "That's too far to travel in one go.".to_string())?;
+
+        let old_time = app_meta
+            .repository
+            .get_key_value(&KeyValue::Time(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .time()
+            .unwrap_or_default();
+        let calendar = app_meta
+            .repository
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.calendar());
+        let time = old_time
+            .checked_add(&trip.elapsed)
+            .ok_or_else(|| format!("Unable to advance time by {}.", trip.elapsed.display_long()))?;
+
+        let mut output = format!(
+            "Travelling {} mile{} {}",
+            miles,
+            if miles == 1 { "" } else { "s" },
+            mode,
+        );
+
+        if mode.has_pace() {
+            output.push_str(&format!(" at a {} pace", pace));
+        }
+
+        output.push_str(&format!(" takes {}", trip.travel_time.display_long()));
+
+        if trip.days > 1 {
+            output.push_str(&format!(" over {} days", trip.days));
+        }
+
+        output.push('.');
+
+        if self.miles.is_none() {
+            output.push_str(" With no distance given, the journey is assumed to take a day.");
+        }
+
+        if let Some(destination) = &self.destination {
+            output.push_str(&format!(
+                " You arrive at {}.",
+                describe_destination(app_meta, destination).await,
+            ));
+        }
+
+        if mode.has_pace() {
+            output.push_str(&format!(
+                "\n\n**{}{} pace:** {}",
+                pace.to_string()[..1].to_uppercase(),
+                &pace.to_string()[1..],
+                pace.effect(),
+            ));
+        }
+
+        if trip.forced_march_hours > 0 {
+            output.push_str(&format!(
+                "\n\n**Forced march:** Travelling for more than 8 hours in a day risks exhaustion. At the end of each hour past the 8th, each character must succeed on a Constitution saving throw or gain one level of exhaustion. The DC is 11 after the 9th hour, rising to {} after the {}.",
+                10 + trip.forced_march_hours,
+                ordinal(8 + trip.forced_march_hours),
+            ));
+        }
+
+        if self.encounters {
+            output.push_str("\n\n**Random encounters:** ");
+
+            let mut encounters = Vec::new();
+            let mut watch_start = old_time.clone();
+            while watch_start < time {
+                if app_meta.rng.gen_range(1..=20) >= 18 {
+                    encounters.push(watch_start.display_long(calendar.as_ref()).to_string());
+                }
+
+                watch_start = match watch_start.checked_add(&Interval::new_hours(WATCH_HOURS)) {
+                    Some(t) => t,
+                    None => break,
+                };
+            }
+
+            if encounters.is_empty() {
+                output.push_str("none.");
+            } else {
+                output.push_str("during the watches beginning ");
+                output.push_str(&encounters.join(", "));
+                output.push('.');
+            }
+        }
+
+        output.push_str(&format!(
+            "\n\nIt is now {}. Use `undo` to reverse.",
+            time.display_long(calendar.as_ref()),
+        ));
+        output.push_str(&time::describe_elapsed(&app_meta.repository, &old_time, &time).await);
+
+        app_meta
+            .repository
+            .modify(Change::SetKeyValue {
+                key_value: KeyValue::Time(Some(time)),
+            })
+            .await
+            .map(|_| output)
+            .map_err(|_| "Storage error.".to_string())
+    }
+}
+
+/// Describe the destination, including where it is if it's a place in the journal, eg. "Sea of
+/// Glass, in Luskan".
+async fn describe_destination(app_meta: &AppMeta, destination: &str) -> String {
+    let place = match app_meta.repository.get_by_name(destination).await {
+        Ok(Thing::Place(place)) => place,
+        _ => return destination.to_string(),
+    };
+
+    let mut output = place.display_name().to_string();
+
+    if let Ok(ThingRelations::Place(relations)) =
+        app_meta.repository.load_relations(&place.into()).await
+    {
+        match relations.location {
+            Some((parent, Some(grandparent))) => output.push_str(&format!(
+                ", in {}, {}",
+                parent.display_name(),
+                grandparent.display_name(),
+            )),
+            Some((parent, None)) => output.push_str(&format!(", in {}", parent.display_name())),
+            None => {}
+        }
+    }
+
+    output
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for TravelCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        input
+            .strip_prefix_ci("travel ")
+            .and_then(parse_travel)
+            .map(CommandMatches::new_canonical)
+            .unwrap_or_default()
+    }
+}
+
+/// Parse the arguments to `travel`. Options can appear in any order after the destination, if
+/// any, eg. `to Neverwinter 30 miles fast by horse`.
+fn parse_travel(input: &str) -> Option<TravelCommand> {
+    let mut command = TravelCommand {
+        destination: None,
+        miles: None,
+        pace: None,
+        mode: None,
+        forced: false,
+        encounters: false,
+    };
+
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+
+    // Consume options from the end until there are none left.
+    loop {
+        let len = words.len();
+        let last_two = if len >= 2 {
+            Some(format!("{} {}", words[len - 2], words[len - 1]))
+        } else {
+            None
+        };
+
+        if let Some(mode) = last_two.as_deref().and_then(Mode::parse) {
+            if command.mode.replace(mode).is_some() {
+                return None;
+            }
+            words.truncate(len - 2);
+        } else if let (Some(miles), Some(true)) = (
+            len.checked_sub(2)
+                .and_then(|i| words[i].parse::<u32>().ok()),
+            words.last().map(|w| w.in_ci(&["mile", "miles", "mi"])),
+        ) {
+            if command.miles.replace(miles).is_some() {
+                return None;
+            }
+            words.truncate(len - 2);
+        } else if last_two
+            .as_deref()
+            .filter(|s| s.eq_ci("with encounters"))
+            .is_some()
+        {
+            command.encounters = true;
+            words.truncate(len - 2);
+        } else if let Some(pace) = words.last().and_then(|w| Pace::parse(w)) {
+            if command.pace.replace(pace).is_some() {
+                return None;
+            }
+            words.truncate(len - 1);
+        } else if words.last().filter(|w| w.eq_ci("forced")).is_some() {
+            command.forced = true;
+            words.truncate(len - 1);
+        } else {
+            break;
+        }
+    }
+
+    match words.split_first() {
+        None => Some(command).filter(|c| c.miles.is_some()),
+        Some((first, rest)) if first.eq_ci("to") && !rest.is_empty() => {
+            command.destination = Some(rest.join(" "));
+            Some(command)
+        }
+        Some(_) => None,
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for TravelCommand {
+    async fn autocomplete(input: &str, _app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if input.is_empty() {
+            Vec::new()
+        } else {
+            [
+                ("travel [distance] miles", "advance time by travelling"),
+                (
+                    "travel to [place] [distance] miles",
+                    "advance time by travelling",
+                ),
+            ]
+            .into_iter()
+            .filter(|(term, _)| term.starts_with_ci(input))
+            .map(|(term, summary)| AutocompleteSuggestion::new(term, summary))
+            .collect()
+        }
+    }
+}
+
+impl fmt::Display for TravelCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "travel")?;
+
+        if let Some(destination) = &self.destination {
+            write!(f, " to {}", destination)?;
+        }
+
+        if let Some(miles) = self.miles {
+            write!(f, " {} miles", miles)?;
+        }
+
+        if let Some(pace) = self.pace {
+            write!(f, " {}", pace)?;
+        }
+
+        if let Some(mode) = self.mode {
+            write!(f, " {}", mode)?;
+        }
+
+        if self.forced {
+            write!(f, " forced")?;
+        }
+
+        if self.encounters {
+            write!(f, " with encounters")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::{Event, NullDataStore};
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(TravelCommand {
+                destination: None,
+                miles: Some(30),
+                pace: Some(Pace::Fast),
+                mode: None,
+                forced: false,
+                encounters: false,
+            }),
+            block_on(TravelCommand::parse_input(
+                "travel 30 miles fast",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TravelCommand {
+                destination: Some("Sea of Glass".to_string()),
+                miles: None,
+                pace: None,
+                mode: Some(Mode::Foot),
+                forced: false,
+                encounters: false,
+            }),
+            block_on(TravelCommand::parse_input(
+                "travel to Sea of Glass by foot",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(TravelCommand {
+                destination: Some("Neverwinter".to_string()),
+                miles: Some(40),
+                pace: Some(Pace::Slow),
+                mode: Some(Mode::Horse),
+                forced: true,
+                encounters: true,
+            }),
+            block_on(TravelCommand::parse_input(
+                "travel to Neverwinter slow on horseback 40 mi with encounters forced",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TravelCommand::parse_input("travel fast", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TravelCommand::parse_input(
+                "travel 30 miles 20 miles",
+                &app_meta
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(TravelCommand::parse_input("travel far away", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let app_meta = app_meta();
+
+        assert_autocomplete(
+            &[
+                ("travel [distance] miles", "advance time by travelling"),
+                (
+                    "travel to [place] [distance] miles",
+                    "advance time by travelling",
+                ),
+            ][..],
+            block_on(TravelCommand::autocomplete("tr", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[(
+                "travel to [place] [distance] miles",
+                "advance time by travelling",
+            )][..],
+            block_on(TravelCommand::autocomplete("travel t", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            TravelCommand {
+                destination: None,
+                miles: Some(30),
+                pace: Some(Pace::Fast),
+                mode: None,
+                forced: false,
+                encounters: false,
+            },
+            TravelCommand {
+                destination: Some("Sea of Glass".to_string()),
+                miles: Some(12),
+                pace: None,
+                mode: Some(Mode::Ship),
+                forced: true,
+                encounters: true,
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(TravelCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(NullDataStore::default(), &event_dispatcher)
+    }
+}
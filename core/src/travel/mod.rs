@@ -0,0 +1,262 @@
+pub use command::TravelCommand;
+
+mod command;
+
+use crate::time::Interval;
+use std::fmt;
+
+const SECONDS_PER_HOUR: u64 = 3600;
+
+/// The number of hours in a normal day of travel. Travelling for longer than this is a forced
+/// march.
+const HOURS_PER_DAY: u64 = 8;
+
+/// How quickly the party travels on foot or on horseback, per the Player's Handbook.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pace {
+    Fast,
+    Normal,
+    Slow,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Mode {
+    Foot,
+    Horse,
+    Boat,
+    Ship,
+}
+
+/// The result of working out how long a journey takes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trip {
+    /// The time spent actually moving.
+    pub travel_time: Interval,
+
+    /// The total time taken, including any nights spent resting along the way.
+    pub elapsed: Interval,
+
+    /// The number of days on which the party travels.
+    pub days: u64,
+
+    /// The number of hours beyond a normal day of travel, if the party travels without stopping.
+    pub forced_march_hours: u64,
+}
+
+impl Pace {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "fast" => Some(Self::Fast),
+            "normal" => Some(Self::Normal),
+            "slow" => Some(Self::Slow),
+            _ => None,
+        }
+    }
+
+    pub fn effect(&self) -> &'static str {
+        match self {
+            Self::Fast => "−5 penalty to passive Wisdom (Perception) scores.",
+            Self::Normal => "No special effect.",
+            Self::Slow => "The party is able to use stealth.",
+        }
+    }
+}
+
+impl Mode {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "by foot" | "on foot" => Some(Self::Foot),
+            "by horse" | "on horseback" => Some(Self::Horse),
+            "by boat" => Some(Self::Boat),
+            "by ship" => Some(Self::Ship),
+            _ => None,
+        }
+    }
+
+    /// Speed in tenths of a mile per hour. Boats and ships use the rowboat and sailing ship speeds
+    /// from the Dungeon Master's Guide, and don't have a pace.
+    fn speed(&self, pace: Pace) -> u64 {
+        match (self, pace) {
+            (Self::Foot | Self::Horse, Pace::Fast) => 40,
+            (Self::Foot | Self::Horse, Pace::Normal) => 30,
+            (Self::Foot | Self::Horse, Pace::Slow) => 20,
+            (Self::Boat, _) => 15,
+            (Self::Ship, _) => 20,
+        }
+    }
+
+    /// The distance covered in a normal day of travel. Ships sail around the clock.
+    pub fn miles_per_day(&self, pace: Pace) -> u32 {
+        let hours = if *self == Self::Ship {
+            24
+        } else {
+            HOURS_PER_DAY
+        };
+
+        (self.speed(pace) * hours / 10) as u32
+    }
+
+    /// Only travel on foot or on horseback has a pace. A crewed ship can keep sailing through the
+    /// night, while boats travel as fast as they can be rowed.
+    pub fn has_pace(&self) -> bool {
+        matches!(self, Self::Foot | Self::Horse)
+    }
+}
+
+impl Trip {
+    /// Work out how long it takes to travel `miles` miles. Unless `forced` is set, the party
+    /// travels for 8 hours a day and rests for the remainder.
+    pub fn new(miles: u32, pace: Pace, mode: Mode, forced: bool) -> Option<Self> {
+        let speed = mode.speed(pace);
+        let travel_seconds = (miles as u64 * SECONDS_PER_HOUR * 10).checked_div(speed)?;
+        let day_seconds = HOURS_PER_DAY * SECONDS_PER_HOUR;
+
+        let (elapsed_seconds, days, forced_march_hours) = if mode == Mode::Ship {
+            (
+                travel_seconds,
+                travel_seconds.div_ceil(24 * SECONDS_PER_HOUR).max(1),
+                0,
+            )
+        } else if forced {
+            (
+                travel_seconds,
+                1,
+                travel_seconds.saturating_sub(day_seconds) / SECONDS_PER_HOUR,
+            )
+        } else {
+            // Travel for a full day, then rest until the same time the next day.
+            let days = travel_seconds.div_ceil(day_seconds).max(1);
+            (
+                travel_seconds + (days - 1) * (24 - HOURS_PER_DAY) * SECONDS_PER_HOUR,
+                days,
+                0,
+            )
+        };
+
+        Some(Trip {
+            travel_time: interval(travel_seconds)?,
+            elapsed: interval(elapsed_seconds)?,
+            days,
+            forced_march_hours,
+        })
+    }
+}
+
+fn interval(seconds: u64) -> Option<Interval> {
+    Some(Interval::new(
+        (seconds / 86400).try_into().ok()?,
+        ((seconds / 3600) % 24) as i32,
+        ((seconds / 60) % 60) as i32,
+        (seconds % 60) as i32,
+        0,
+    ))
+}
+
+impl fmt::Display for Pace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Fast => write!(f, "fast"),
+            Self::Normal => write!(f, "normal"),
+            Self::Slow => write!(f, "slow"),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Foot => write!(f, "by foot"),
+            Self::Horse => write!(f, "by horse"),
+            Self::Boat => write!(f, "by boat"),
+            Self::Ship => write!(f, "by ship"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn miles_per_day_test() {
+        assert_eq!(24, Mode::Foot.miles_per_day(Pace::Normal));
+        assert_eq!(32, Mode::Horse.miles_per_day(Pace::Fast));
+        assert_eq!(16, Mode::Foot.miles_per_day(Pace::Slow));
+        assert_eq!(12, Mode::Boat.miles_per_day(Pace::Fast));
+        assert_eq!(48, Mode::Ship.miles_per_day(Pace::Slow));
+    }
+
+    #[test]
+    fn trip_new_test_one_day() {
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(0, 7, 30, 0, 0),
+                elapsed: Interval::new(0, 7, 30, 0, 0),
+                days: 1,
+                forced_march_hours: 0,
+            }),
+            Trip::new(30, Pace::Fast, Mode::Foot, false),
+        );
+
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(0, 8, 0, 0, 0),
+                elapsed: Interval::new(0, 8, 0, 0, 0),
+                days: 1,
+                forced_march_hours: 0,
+            }),
+            Trip::new(24, Pace::Normal, Mode::Horse, false),
+        );
+    }
+
+    #[test]
+    fn trip_new_test_several_days() {
+        // 100 miles at 3 mph is 33h20m of travel: four full days, plus 1h20m on the fifth.
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(1, 9, 20, 0, 0),
+                elapsed: Interval::new(4, 1, 20, 0, 0),
+                days: 5,
+                forced_march_hours: 0,
+            }),
+            Trip::new(100, Pace::Normal, Mode::Foot, false),
+        );
+
+        // 32 miles at 2 mph is exactly two days of travel, with one night's rest in between.
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(0, 16, 0, 0, 0),
+                elapsed: Interval::new(1, 8, 0, 0, 0),
+                days: 2,
+                forced_march_hours: 0,
+            }),
+            Trip::new(32, Pace::Slow, Mode::Foot, false),
+        );
+    }
+
+    #[test]
+    fn trip_new_test_forced() {
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(0, 10, 0, 0, 0),
+                elapsed: Interval::new(0, 10, 0, 0, 0),
+                days: 1,
+                forced_march_hours: 2,
+            }),
+            Trip::new(40, Pace::Fast, Mode::Foot, true),
+        );
+    }
+
+    #[test]
+    fn trip_new_test_ship() {
+        assert_eq!(
+            Some(Trip {
+                travel_time: Interval::new(2, 2, 0, 0, 0),
+                elapsed: Interval::new(2, 2, 0, 0, 0),
+                days: 3,
+                forced_march_hours: 0,
+            }),
+            Trip::new(100, Pace::Fast, Mode::Ship, false),
+        );
+    }
+}
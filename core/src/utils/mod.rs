@@ -19,6 +19,19 @@ pub fn capitalize(input: &str) -> String {
     result
 }
 
+/// Format a number as an ordinal, eg. "1st", "12th", "22nd".
+pub fn ordinal(number: u64) -> String {
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", number, suffix)
+}
+
 pub fn pluralize(word: &str) -> (&str, &str) {
     match word {
         "Goose" => ("Geese", ""),
@@ -34,6 +47,41 @@ pub fn pluralize(word: &str) -> (&str, &str) {
     }
 }
 
+/// The inverse of `pluralize`, more or less. Since English is what it is, this returns every
+/// plausible singular form of the word, most likely first; it's up to the caller to decide which
+/// of them is a real word.
+pub fn singularize(word: &str) -> Vec<String> {
+    let word = word.to_lowercase();
+    let mut result = Vec::new();
+
+    if let Some(stem) = word.strip_suffix("men") {
+        result.push(format!("{}man", stem));
+    } else if word == "children" {
+        result.push("child".to_string());
+    } else if word == "people" {
+        result.push("person".to_string());
+    }
+
+    if let Some(stem) = word.strip_suffix("ves") {
+        result.push(format!("{}f", stem));
+        result.push(format!("{}fe", stem));
+    }
+
+    if let Some(stem) = word.strip_suffix("ies") {
+        result.push(format!("{}y", stem));
+    }
+
+    if let Some(stem) = word.strip_suffix("es") {
+        result.push(stem.to_string());
+    }
+
+    if let Some(stem) = word.strip_suffix('s') {
+        result.push(stem.to_string());
+    }
+
+    result
+}
+
 pub struct Word<'a> {
     phrase: &'a str,
     inner_range: Range<usize>,
@@ -61,3 +109,22 @@ impl<'a> Word<'a> {
         &self.outer_range
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ordinal_test() {
+        assert_eq!(
+            vec![
+                "0th", "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "23rd",
+                "101st", "111th"
+            ],
+            [0, 1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 101, 111]
+                .into_iter()
+                .map(ordinal)
+                .collect::<Vec<_>>(),
+        );
+    }
+}
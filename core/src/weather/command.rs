@@ -0,0 +1,280 @@
+use super::Weather;
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
+};
+use crate::storage::{Change, KeyValue};
+use crate::time::Time;
+use crate::utils::CaseInsensitiveStr;
+use crate::world::place::{GeographyType, PlaceType, RegionType};
+use crate::world::Thing;
+use async_trait::async_trait;
+use std::fmt;
+
+/// If the weather hasn't been checked for longer than this, don't bother simulating every day in
+/// between.
+const MAX_DAYS_TO_SIMULATE: i32 = 30;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WeatherCommand {
+    Show,
+
+    /// Change the terrain, either to a geography type like "desert" or to the name of a region in
+    /// the journal. Resolved when the command is run.
+    Terrain {
+        terrain: String,
+    },
+}
+
+#[async_trait(?Send)]
+impl Runnable for WeatherCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        let now = app_meta
+            .repository
+            .get_key_value(&KeyValue::Time(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .time()
+            .unwrap_or_default();
+        let calendar = app_meta
+            .repository
+            .get_key_value(&KeyValue::Calendar(None))
+            .await
+            .ok()
+            .and_then(|kv| kv.calendar());
+        let previous = app_meta
+            .repository
+            .get_key_value(&KeyValue::Weather(None))
+            .await
+            .map_err(|_| "Storage error.".to_string())?
+            .weather();
+
+        let is_show = matches!(self, Self::Show);
+
+        let weather = match self {
+            Self::Show => match previous {
+                Some(weather) if weather.day == now.days() => {
+                    return Ok(weather.display_details().to_string());
+                }
+                Some(previous)
+                    if (1..=MAX_DAYS_TO_SIMULATE).contains(&(now.days() - previous.day)) =>
+                {
+                    // Step through each day in turn so that the weather changes gradually.
+                    let mut weather = previous;
+                    for day in weather.day + 1..=now.days() {
+                        let time = Time::try_new(day, 0, 0, 0).unwrap_or_default();
+                        weather = Weather::generate(
+                            &mut app_meta.rng,
+                            &time,
+                            calendar.as_ref(),
+                            weather.terrain,
+                            Some(&weather),
+                        );
+                    }
+                    weather
+                }
+                previous => Weather::generate(
+                    &mut app_meta.rng,
+                    &now,
+                    calendar.as_ref(),
+                    previous.map_or(GeographyType::Plain, |w| w.terrain),
+                    None,
+                ),
+            },
+            Self::Terrain { terrain } => {
+                let terrain = if let Ok(geography) = terrain.parse() {
+                    geography
+                } else if let Ok(Thing::Place(place)) =
+                    app_meta.repository.get_by_name(&terrain).await
+                {
+                    match place.subtype.value() {
+                        Some(PlaceType::Region(RegionType::Geography(geography))) => *geography,
+                        _ => {
+                            return Err(format!(
+                                "{} isn't a geographic region. Try eg. `weather desert`.",
+                                place.display_name(),
+                            ))
+                        }
+                    }
+                } else {
+                    return Err(format!(
+                        "\"{}\" isn't a terrain I know. Try eg. `weather desert`, or the name of a region in your journal.",
+                        terrain,
+                    ));
+                };
+
+                Weather::generate(
+                    &mut app_meta.rng,
+                    &now,
+                    calendar.as_ref(),
+                    terrain,
+                    previous.as_ref(),
+                )
+            }
+        };
+
+        let output = weather.display_details().to_string();
+        let change = Change::SetKeyValue {
+            key_value: KeyValue::Weather(Some(weather)),
+        };
+
+        // Looking at the weather only fills in what was already going to happen, so it shouldn't
+        // be something that can be undone.
+        if is_show {
+            app_meta
+                .repository
+                .modify_without_undo(change)
+                .await
+                .map(|_| output)
+                .map_err(|_| "Storage error.".to_string())
+        } else {
+            app_meta
+                .repository
+                .modify(change)
+                .await
+                .map(|_| output)
+                .map_err(|_| "Storage error.".to_string())
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for WeatherCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        if input.eq_ci("weather") {
+            CommandMatches::new_canonical(Self::Show)
+        } else if let Some(terrain) = input.strip_prefix_ci("weather ") {
+            let terrain = terrain.trim();
+            let terrain = terrain.strip_prefix_ci("in ").unwrap_or(terrain);
+
+            if terrain.is_empty() {
+                CommandMatches::default()
+            } else {
+                CommandMatches::new_canonical(Self::Terrain {
+                    terrain: terrain.to_string(),
+                })
+            }
+        } else {
+            CommandMatches::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for WeatherCommand {
+    async fn autocomplete(input: &str, _app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if let Some(terrain) = input.strip_prefix_ci("weather ") {
+            GeographyType::get_words()
+                .filter(|word| word.starts_with_ci(terrain))
+                .map(|word| {
+                    AutocompleteSuggestion::new(
+                        format!("{}{}", &input[..input.len() - terrain.len()], word),
+                        format!("{} weather", word),
+                    )
+                })
+                .collect()
+        } else if !input.is_empty() {
+            [
+                ("weather", "today's weather"),
+                ("weather [terrain]", "change the terrain"),
+            ]
+            .into_iter()
+            .filter(|(term, _)| term.starts_with_ci(input))
+            .map(|(term, summary)| AutocompleteSuggestion::new(term, summary))
+            .collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl fmt::Display for WeatherCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Show => write!(f, "weather"),
+            Self::Terrain { terrain } => write!(f, "weather {}", terrain),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::{Event, NullDataStore};
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(WeatherCommand::Show),
+            block_on(WeatherCommand::parse_input("weather", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(WeatherCommand::Terrain {
+                terrain: "desert".to_string(),
+            }),
+            block_on(WeatherCommand::parse_input("weather in desert", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(WeatherCommand::parse_input("weathered", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let app_meta = app_meta();
+
+        assert_autocomplete(
+            &[
+                ("weather", "today's weather"),
+                ("weather [terrain]", "change the terrain"),
+            ][..],
+            block_on(WeatherCommand::autocomplete("wea", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[
+                ("weather marsh", "marsh weather"),
+                ("weather mesa", "mesa weather"),
+                ("weather moor", "moor weather"),
+                ("weather mountain", "mountain weather"),
+            ][..],
+            block_on(WeatherCommand::autocomplete("weather m", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            WeatherCommand::Show,
+            WeatherCommand::Terrain {
+                terrain: "tundra".to_string(),
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+            assert_ne!("", command_string);
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(WeatherCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(NullDataStore::default(), &event_dispatcher)
+    }
+}
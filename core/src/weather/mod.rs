@@ -0,0 +1,467 @@
+pub use command::WeatherCommand;
+
+mod command;
+
+use crate::time::{Calendar, Time};
+use crate::world::place::GeographyType;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The weather for a given day, persisted to the key-value store so that it stays the same for
+/// the rest of the day and so that tomorrow's weather can follow on from it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Weather {
+    pub day: i32,
+    pub terrain: GeographyType,
+    pub season: Season,
+    pub temperature: Temperature,
+    pub precipitation: Precipitation,
+    pub wind: Wind,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Autumn,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Temperature {
+    Freezing,
+    Cold,
+    Cool,
+    Mild,
+    Warm,
+    Hot,
+    Scorching,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Precipitation {
+    None,
+    Light,
+    Heavy,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Wind {
+    Calm,
+    Breeze,
+    Strong,
+}
+
+/// The typical conditions of a terrain in a given season.
+struct Climate {
+    temperature: Temperature,
+
+    /// The percent chance of precipitation on a given day.
+    precipitation: u8,
+
+    /// The percent chance of strong wind on a given day.
+    wind: u8,
+}
+
+pub struct WeatherDetailsView<'a>(&'a Weather);
+
+impl Weather {
+    /// Generate the weather for `time`. If `previous` is the weather of an earlier day in the same
+    /// terrain, the new weather follows on from it rather than starting from scratch, so a cold
+    /// snap or a storm will tend to last for a few days.
+    pub fn generate(
+        rng: &mut impl Rng,
+        time: &Time,
+        calendar: Option<&Calendar>,
+        terrain: GeographyType,
+        previous: Option<&Weather>,
+    ) -> Self {
+        let season = Season::from_time(time, calendar);
+        let climate = Climate::new(terrain, season);
+
+        let previous = previous.filter(|w| w.terrain == terrain && w.day < time.days());
+
+        // How far the temperature strays from the seasonal norm, which drifts by at most one step
+        // per day.
+        let offset = previous.map_or(0, |w| {
+            w.temperature as i8 - Climate::new(terrain, w.season).temperature as i8
+        }) + rng.gen_range(-1..=1);
+        let temperature = Temperature::from_index(climate.temperature as i8 + offset.clamp(-2, 2));
+
+        let precipitation_chance = match previous.map(|w| w.precipitation) {
+            Some(Precipitation::None) | None => climate.precipitation,
+            Some(_) => climate.precipitation.saturating_add(25),
+        };
+        let precipitation = if rng.gen_range(0..100) < precipitation_chance {
+            if rng.gen_range(0..10) < 3 {
+                Precipitation::Heavy
+            } else {
+                Precipitation::Light
+            }
+        } else {
+            Precipitation::None
+        };
+
+        let wind_chance = match previous.map(|w| w.wind) {
+            Some(Wind::Strong) => climate.wind.saturating_add(30),
+            _ => climate.wind,
+        };
+        let wind = if rng.gen_range(0..100) < wind_chance {
+            Wind::Strong
+        } else if rng.gen_bool(0.5) {
+            Wind::Breeze
+        } else {
+            Wind::Calm
+        };
+
+        Weather {
+            day: time.days(),
+            terrain,
+            season,
+            temperature,
+            precipitation,
+            wind,
+        }
+    }
+
+    pub fn is_snow(&self) -> bool {
+        self.precipitation != Precipitation::None && self.temperature <= Temperature::Cold
+    }
+
+    /// Strong winds whip up sand and dust in dry terrain.
+    pub fn is_sandstorm(&self) -> bool {
+        self.wind == Wind::Strong
+            && self.precipitation == Precipitation::None
+            && is_arid(self.terrain)
+    }
+
+    /// A short description like "cold, with heavy snow and strong winds".
+    pub fn summary(&self) -> String {
+        let mut output = self.temperature.to_string();
+
+        let precipitation = match (self.precipitation, self.is_snow()) {
+            (Precipitation::None, _) => None,
+            (Precipitation::Light, true) => Some("light snow"),
+            (Precipitation::Heavy, true) if self.wind == Wind::Strong => Some("a blizzard"),
+            (Precipitation::Heavy, true) => Some("heavy snow"),
+            (Precipitation::Light, false) => Some("light rain"),
+            (Precipitation::Heavy, false) => Some("heavy rain"),
+        };
+
+        let wind = match self.wind {
+            _ if self.is_sandstorm() => Some("a sandstorm"),
+            _ if precipitation == Some("a blizzard") => None,
+            Wind::Calm => None,
+            Wind::Breeze => Some("a light breeze"),
+            Wind::Strong => Some("strong winds"),
+        };
+
+        match (precipitation, wind) {
+            (Some(p), Some(w)) => output.push_str(&format!(", with {} and {}", p, w)),
+            (Some(p), None) => output.push_str(&format!(", with {}", p)),
+            (None, Some(w)) => output.push_str(&format!(" and clear, with {}", w)),
+            (None, None) => output.push_str(" and clear"),
+        }
+
+        output
+    }
+
+    pub fn display_details(&self) -> WeatherDetailsView {
+        WeatherDetailsView(self)
+    }
+}
+
+impl Season {
+    /// Get the season, preferring the seasons of the campaign calendar if they have familiar
+    /// names. Otherwise the year is divided into quarters, with the solstices and equinoxes
+    /// falling where they do on Earth.
+    pub fn from_time(time: &Time, calendar: Option<&Calendar>) -> Self {
+        let (day_of_year, year_length) = if let Some((calendar, date)) =
+            calendar.and_then(|calendar| Some((calendar, calendar.date(time.days())?)))
+        {
+            if let Some(season) = calendar
+                .season(&date)
+                .and_then(|season| Self::from_name(&season.name))
+            {
+                return season;
+            }
+
+            (
+                (0..date.month)
+                    .map(|month| calendar.month_length(date.year, month) as i64)
+                    .sum::<i64>()
+                    + date.day as i64
+                    - 1,
+                calendar.year_length(date.year),
+            )
+        } else {
+            ((time.days() as i64 - 1).rem_euclid(365), 365)
+        };
+
+        match day_of_year * 100 / year_length.max(1) {
+            0..=21 | 97.. => Self::Winter,
+            22..=46 => Self::Spring,
+            47..=71 => Self::Summer,
+            _ => Self::Autumn,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        if name.contains("winter") {
+            Some(Self::Winter)
+        } else if name.contains("spring") {
+            Some(Self::Spring)
+        } else if name.contains("summer") {
+            Some(Self::Summer)
+        } else if name.contains("autumn") || name.contains("fall") {
+            Some(Self::Autumn)
+        } else {
+            None
+        }
+    }
+}
+
+impl Temperature {
+    fn from_index(index: i8) -> Self {
+        match index {
+            i8::MIN..=0 => Self::Freezing,
+            1 => Self::Cold,
+            2 => Self::Cool,
+            3 => Self::Mild,
+            4 => Self::Warm,
+            5 => Self::Hot,
+            _ => Self::Scorching,
+        }
+    }
+}
+
+impl Climate {
+    fn new(terrain: GeographyType, season: Season) -> Self {
+        use GeographyType::*;
+        use Temperature::*;
+
+        let (temperatures, precipitation, wind) = match terrain {
+            Tundra => ([Freezing, Cold, Cool, Cold], 30, 30),
+            Mountain => ([Freezing, Cool, Mild, Cool], 40, 35),
+            Barrens | Desert | Mesa | Wasteland => ([Cool, Warm, Scorching, Warm], 5, 20),
+            Jungle => ([Warm, Hot, Hot, Warm], 60, 10),
+            Marsh | Swamp => ([Cool, Mild, Hot, Mild], 50, 10),
+            Archipelago | Coastline | Lake | Ocean | Reef | Sea => {
+                ([Cool, Mild, Warm, Mild], 40, 35)
+            }
+            Moor | Plateau => ([Cold, Cool, Mild, Cool], 40, 25),
+            Continent | Forest | Plain | World => ([Cold, Mild, Warm, Mild], 35, 15),
+        };
+
+        Climate {
+            temperature: temperatures[season as usize],
+            precipitation,
+            wind,
+        }
+    }
+}
+
+fn is_arid(terrain: GeographyType) -> bool {
+    matches!(
+        terrain,
+        GeographyType::Barrens
+            | GeographyType::Desert
+            | GeographyType::Mesa
+            | GeographyType::Wasteland
+    )
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Winter => write!(f, "winter"),
+            Self::Spring => write!(f, "spring"),
+            Self::Summer => write!(f, "summer"),
+            Self::Autumn => write!(f, "autumn"),
+        }
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Freezing => write!(f, "freezing"),
+            Self::Cold => write!(f, "cold"),
+            Self::Cool => write!(f, "cool"),
+            Self::Mild => write!(f, "mild"),
+            Self::Warm => write!(f, "warm"),
+            Self::Hot => write!(f, "hot"),
+            Self::Scorching => write!(f, "scorching"),
+        }
+    }
+}
+
+impl<'a> fmt::Display for WeatherDetailsView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let weather = self.0;
+        let summary = weather.summary();
+        let obscured = weather.precipitation == Precipitation::Heavy || weather.is_sandstorm();
+
+        write!(
+            f,
+            "# Weather\n\n{}{}.\n\n**Terrain:** {}\\\n**Season:** {}\\\n**Visibility:** {}\\\n**Travel:** {}",
+            summary[..1].to_uppercase(),
+            &summary[1..],
+            weather.terrain.as_str(),
+            weather.season,
+            if obscured {
+                "lightly obscured"
+            } else {
+                "normal"
+            },
+            if (weather.is_snow() && weather.precipitation == Precipitation::Heavy)
+                || weather.is_sandstorm()
+            {
+                "half pace"
+            } else {
+                "normal pace"
+            },
+        )?;
+
+        let mut effects = Vec::new();
+
+        if obscured {
+            effects.push("**Heavy precipitation:** Everything is lightly obscured, and creatures have disadvantage on Wisdom (Perception) checks that rely on sight. Open flames are extinguished.");
+        }
+
+        if weather.wind == Wind::Strong {
+            effects.push("**Strong wind:** Disadvantage on ranged weapon attack rolls and Wisdom (Perception) checks that rely on hearing. Open flames are extinguished, fog is dispersed, and flying by nonmagical means is nearly impossible.");
+        }
+
+        match weather.temperature {
+            Temperature::Freezing => effects.push("**Extreme cold:** Creatures exposed without cold weather gear must succeed on a DC 10 Constitution saving throw at the end of each hour or gain one level of exhaustion."),
+            Temperature::Scorching => effects.push("**Extreme heat:** Creatures without access to drinkable water must succeed on a Constitution saving throw at the end of each hour or gain one level of exhaustion. The DC is 5 for the first hour and increases by 1 for each additional hour."),
+            _ => {}
+        }
+
+        if !effects.is_empty() {
+            writeln!(f)?;
+            effects
+                .iter()
+                .try_for_each(|effect| write!(f, "\n* {}", effect))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn season_from_time_test() {
+        assert_eq!(Season::Winter, Season::from_time(&t(1), None));
+        assert_eq!(Season::Spring, Season::from_time(&t(100), None));
+        assert_eq!(Season::Summer, Season::from_time(&t(200), None));
+        assert_eq!(Season::Autumn, Season::from_time(&t(300), None));
+        assert_eq!(Season::Winter, Season::from_time(&t(360), None));
+        assert_eq!(Season::Summer, Season::from_time(&t(565), None));
+
+        let harptos = Calendar::preset("harptos").unwrap();
+        assert_eq!(Season::Winter, Season::from_time(&t(1), Some(&harptos)));
+        assert_eq!(Season::Summer, Season::from_time(&t(200), Some(&harptos)));
+    }
+
+    #[test]
+    fn generate_test_climate() {
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let weather = Weather::generate(&mut rng, &t(200), None, GeographyType::Desert, None);
+            assert_eq!(Season::Summer, weather.season);
+            assert!(weather.temperature >= Temperature::Warm, "{:?}", weather);
+            assert!(!weather.is_snow(), "{:?}", weather);
+
+            let weather = Weather::generate(&mut rng, &t(1), None, GeographyType::Tundra, None);
+            assert!(weather.temperature <= Temperature::Cool, "{:?}", weather);
+        }
+    }
+
+    #[test]
+    fn generate_test_continuity() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut weather = Weather::generate(&mut rng, &t(150), None, GeographyType::Plain, None);
+
+        for day in 151..250 {
+            let next = Weather::generate(
+                &mut rng,
+                &t(day),
+                None,
+                GeographyType::Plain,
+                Some(&weather),
+            );
+            assert_eq!(day, next.day);
+            assert!(
+                (next.temperature as i8 - weather.temperature as i8).abs() <= 2,
+                "{:?} -> {:?}",
+                weather,
+                next,
+            );
+            weather = next;
+        }
+    }
+
+    #[test]
+    fn summary_test() {
+        let weather = Weather {
+            day: 1,
+            terrain: GeographyType::Tundra,
+            season: Season::Winter,
+            temperature: Temperature::Freezing,
+            precipitation: Precipitation::Heavy,
+            wind: Wind::Strong,
+        };
+        assert_eq!("freezing, with a blizzard", weather.summary());
+
+        let weather = Weather {
+            terrain: GeographyType::Desert,
+            temperature: Temperature::Hot,
+            precipitation: Precipitation::None,
+            ..weather
+        };
+        assert_eq!("hot and clear, with a sandstorm", weather.summary());
+
+        let weather = Weather {
+            terrain: GeographyType::Forest,
+            temperature: Temperature::Mild,
+            precipitation: Precipitation::Light,
+            wind: Wind::Breeze,
+            ..weather
+        };
+        assert_eq!(
+            "mild, with light rain and a light breeze",
+            weather.summary(),
+        );
+    }
+
+    #[test]
+    fn display_details_test() {
+        let weather = Weather {
+            day: 1,
+            terrain: GeographyType::Desert,
+            season: Season::Summer,
+            temperature: Temperature::Scorching,
+            precipitation: Precipitation::None,
+            wind: Wind::Calm,
+        };
+
+        assert_eq!(
+            "# Weather\n\nScorching and clear.\n\n**Terrain:** desert\\\n**Season:** summer\\\n**Visibility:** normal\\\n**Travel:** normal pace\n\n* **Extreme heat:** Creatures without access to drinkable water must succeed on a Constitution saving throw at the end of each hour or gain one level of exhaustion. The DC is 5 for the first hour and increases by 1 for each additional hour.",
+            weather.display_details().to_string(),
+        );
+    }
+
+    fn t(days: i32) -> Time {
+        Time::try_new(days, 8, 0, 0).unwrap()
+    }
+}
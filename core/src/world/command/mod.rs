@@ -1,4 +1,4 @@
-use super::{Field, Npc, Place, Thing};
+use super::{display_tags, journal_tags, merge_tags, parse_tags, Field, Npc, Place, Thing};
 use crate::app::{
     AppMeta, Autocomplete, AutocompleteSuggestion, CommandAlias, CommandMatches, ContextAwareParse,
     Runnable,
@@ -25,6 +25,18 @@ pub enum WorldCommand {
         name: String,
         diff: ParsedThing<Thing>,
     },
+
+    /// Add tags to an existing entry, eg. "Bob is tagged villain, waterdeep".
+    Tag {
+        name: String,
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from an existing entry, eg. "Bob is not tagged villain".
+    Untag {
+        name: String,
+        tags: Vec<String>,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -229,6 +241,8 @@ impl Runnable for WorldCommand {
                 }
                 .map(|s| append_unknown_words_notice(s, input, unknown_words))
             }
+            Self::Tag { name, tags } => edit_tags(app_meta, &name, &tags, true).await,
+            Self::Untag { name, tags } => edit_tags(app_meta, &name, &tags, false).await,
         }
     }
 }
@@ -260,6 +274,24 @@ impl ContextAwareParse for WorldCommand {
                 input[word.range().end..].trim(),
             );
 
+            if let Some(tags) = description.strip_prefix_ci("tagged ").map(parse_tags) {
+                if !tags.is_empty() {
+                    matches.push_canonical(Self::Tag {
+                        name: name.to_string(),
+                        tags,
+                    });
+                }
+                return matches;
+            } else if let Some(tags) = description.strip_prefix_ci("not tagged ").map(parse_tags) {
+                if !tags.is_empty() {
+                    matches.push_canonical(Self::Untag {
+                        name: name.to_string(),
+                        tags,
+                    });
+                }
+                return matches;
+            }
+
             let (diff, thing) = if let Ok(thing) = app_meta.repository.get_by_name(name).await {
                 (
                     match thing {
@@ -337,6 +369,41 @@ impl Autocomplete for WorldCommand {
                     )
                 }));
 
+                let description = input[split_pos..].trim_start();
+                let (tag_list, untag) =
+                    if let Some(tag_list) = description.strip_prefix_ci("tagged ") {
+                        (Some(tag_list), false)
+                    } else {
+                        (description.strip_prefix_ci("not tagged "), true)
+                    };
+
+                if let Some(partial_tag) =
+                    tag_list.and_then(|s| s.rsplit(|c: char| c == ',' || c.is_whitespace()).next())
+                {
+                    let partial_tag = partial_tag.trim_start_matches('#');
+                    let prefix = &input[..input.len() - partial_tag.len()];
+                    let tags = if untag {
+                        thing.tags().to_vec()
+                    } else {
+                        journal_tags(&app_meta.repository).await
+                    };
+
+                    suggestions.extend(
+                        tags.into_iter()
+                            .filter(|tag| tag.starts_with_ci(partial_tag))
+                            .map(|tag| {
+                                AutocompleteSuggestion::new(
+                                    format!("{}{}", prefix, tag),
+                                    format!(
+                                        "{} {}",
+                                        if untag { "untag" } else { "tag" },
+                                        thing.as_str()
+                                    ),
+                                )
+                            }),
+                    );
+                }
+
                 if next_word.as_str().in_ci(&["named", "called"]) && input_words.next().is_some() {
                     suggestions.push(AutocompleteSuggestion::new(
                         input.to_string(),
@@ -420,6 +487,10 @@ impl fmt::Display for WorldCommand {
             Self::Edit { name, diff } => {
                 write!(f, "{} is {}", name, diff.thing.display_description())
             }
+            Self::Tag { name, tags } => write!(f, "{} is tagged {}", name, display_tags(tags)),
+            Self::Untag { name, tags } => {
+                write!(f, "{} is not tagged {}", name, display_tags(tags))
+            }
         }
     }
 }
@@ -450,6 +521,93 @@ impl<T: Into<Thing>> From<ParsedThing<T>> for Thing {
     }
 }
 
+/// Add or remove tags on the named entry.
+async fn edit_tags(
+    app_meta: &mut AppMeta,
+    name: &str,
+    tags: &[String],
+    add: bool,
+) -> Result<String, String> {
+    let thing = app_meta
+        .repository
+        .get_by_name(name)
+        .await
+        .map_err(|_| format!(r#"There is no character or place named "{}"."#, name))?;
+
+    let new_tags = if add {
+        merge_tags(thing.tags(), tags)
+    } else {
+        thing
+            .tags()
+            .iter()
+            .filter(|tag| !tags.contains(tag))
+            .cloned()
+            .collect()
+    };
+
+    let diff: Thing = match thing {
+        Thing::Npc(_) => Npc {
+            tags: Field::new(new_tags),
+            ..Default::default()
+        }
+        .into(),
+        Thing::Place(_) => Place {
+            tags: Field::new(new_tags),
+            ..Default::default()
+        }
+        .into(),
+    };
+
+    let name = thing.name().to_string();
+
+    match edit_and_display(app_meta, &name, diff).await {
+        Some((details, saved)) => Ok(format!(
+            "{}\n\n_{} was successfully {} {}{}. Use `undo` to reverse this._",
+            details,
+            name,
+            if add { "tagged" } else { "untagged" },
+            display_tags(tags),
+            if saved {
+                " and automatically saved to your `journal`"
+            } else {
+                ""
+            },
+        )),
+        None => Err(format!("Couldn't edit `{}`.", name)),
+    }
+}
+
+/// Applies `diff` to the entry called `name`, returning the entry's updated details and whether
+/// the edit also saved it to the journal, or `None` if the edit failed.
+pub(crate) async fn edit_and_display(
+    app_meta: &mut AppMeta,
+    name: &str,
+    diff: Thing,
+) -> Option<(String, bool)> {
+    let thing = app_meta
+        .repository
+        .modify(Change::Edit {
+            name: name.to_string(),
+            uuid: None,
+            diff,
+        })
+        .await
+        .ok()
+        .flatten()?;
+
+    let saved = matches!(
+        app_meta.repository.undo_history().next(),
+        Some(Change::EditAndUnsave { .. }),
+    );
+    let relations = app_meta
+        .repository
+        .load_relations(&thing)
+        .await
+        .unwrap_or_default();
+
+    Some((thing.display_details(relations).to_string(), saved))
+}
+
 fn append_unknown_words_notice(
     mut output: String,
     input: &str,
@@ -703,6 +861,55 @@ mod test {
         });
     }
 
+    #[test]
+    fn parse_input_test_tags() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(WorldCommand::Tag {
+                name: "Bob".to_string(),
+                tags: vec!["villain".to_string(), "waterdeep".to_string()],
+            }),
+            block_on(WorldCommand::parse_input(
+                "Bob is tagged Waterdeep, #villain",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(WorldCommand::Untag {
+                name: "Bob".to_string(),
+                tags: vec!["villain".to_string()],
+            }),
+            block_on(WorldCommand::parse_input(
+                "Bob is not tagged villain",
+                &app_meta
+            )),
+        );
+
+        [
+            WorldCommand::Tag {
+                name: "Bob".to_string(),
+                tags: vec!["villain".to_string(), "waterdeep".to_string()],
+            },
+            WorldCommand::Untag {
+                name: "Bob".to_string(),
+                tags: vec!["villain".to_string()],
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(WorldCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
     fn create(thing: impl Into<Thing>) -> WorldCommand {
         WorldCommand::Create {
             thing: ParsedThing {
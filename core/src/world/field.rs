@@ -14,6 +14,12 @@ impl<T> Field<T> {
         Self::Locked(Some(value))
     }
 
+    /// An empty, locked field. Used as the serde default for optional fields that are left out of
+    /// saved data, so that they deserialize the same way as an explicit `null`.
+    pub fn locked_none() -> Self {
+        Self::Locked(None)
+    }
+
     #[cfg(test)]
     pub fn new_generated(value: T) -> Self {
         Self::Unlocked(Some(value))
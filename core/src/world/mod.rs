@@ -5,12 +5,18 @@ pub mod place;
 pub use command::{ParsedThing, WorldCommand};
 pub use demographics::Demographics;
 pub use field::Field;
+pub use note::{Note, NoteCommand};
 pub use npc::{Npc, NpcRelations};
 pub use place::{Place, PlaceRelations, Uuid as PlaceUuid};
+pub use query::{ParsedQuery, ThingQuery};
+pub use tag::{display_tags, journal_tags, merge_tags, parse_tags};
 pub use thing::{Thing, ThingRelations};
 
 mod command;
 mod field;
+mod note;
+mod query;
+mod tag;
 mod thing;
 mod word;
 
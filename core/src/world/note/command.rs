@@ -0,0 +1,319 @@
+use super::Note;
+use crate::app::{
+    AppMeta, Autocomplete, AutocompleteSuggestion, CommandMatches, ContextAwareParse, Runnable,
+};
+use crate::storage::{KeyValue, RepositoryError};
+use crate::utils::CaseInsensitiveStr;
+use crate::world::command::edit_and_display;
+use crate::world::{Field, Npc, Place, Thing};
+use async_trait::async_trait;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NoteCommand {
+    Add { name: String, text: String },
+    Search { query: Option<String> },
+}
+
+#[async_trait(?Send)]
+impl Runnable for NoteCommand {
+    async fn run(self, _input: &str, app_meta: &mut AppMeta) -> Result<String, String> {
+        match self {
+            Self::Add { name, text } => {
+                let thing = app_meta.repository.get_by_name(&name).await.map_err(|e| {
+                    if e == RepositoryError::NotFound {
+                        format!(r#"There is no character or place named "{}"."#, name)
+                    } else {
+                        "Storage error.".to_string()
+                    }
+                })?;
+
+                let time = app_meta
+                    .repository
+                    .get_key_value(&KeyValue::Time(None))
+                    .await
+                    .map_err(|_| "Storage error.".to_string())?
+                    .time()
+                    .unwrap_or_default();
+
+                let mut notes = thing.notes().to_vec();
+                notes.push(Note { time, text });
+
+                let diff = match thing {
+                    Thing::Npc(_) => Npc {
+                        notes: Field::new(notes),
+                        ..Default::default()
+                    }
+                    .into(),
+                    Thing::Place(_) => Place {
+                        notes: Field::new(notes),
+                        ..Default::default()
+                    }
+                    .into(),
+                };
+
+                let name = thing.name().to_string();
+
+                match edit_and_display(app_meta, &name, diff).await {
+                    Some((details, saved)) => Ok(format!(
+                        "{}\n\n_Added a note to {}{}. Use `undo` to reverse this._",
+                        details,
+                        name,
+                        if saved {
+                            " and automatically saved it to your `journal`"
+                        } else {
+                            ""
+                        },
+                    )),
+                    None => Err(format!("Couldn't add a note to `{}`.", name)),
+                }
+            }
+            Self::Search { query } => {
+                let mut things: Vec<Thing> = app_meta
+                    .repository
+                    .journal()
+                    .await
+                    .map_err(|_| "Couldn't access the journal.".to_string())?
+                    .into_iter()
+                    .filter(|thing| !thing.notes().is_empty())
+                    .collect();
+                things.sort_by(|a, b| a.name().to_string().cmp_ci(b.name().to_string()));
+
+                let calendar = app_meta
+                    .repository
+                    .get_key_value(&KeyValue::Calendar(None))
+                    .await
+                    .ok()
+                    .and_then(|kv| kv.calendar());
+
+                let query_lower = query.as_ref().map(|q| q.to_lowercase());
+                let mut output = if let Some(query) = &query {
+                    format!("# Notes matching \"{}\"", query)
+                } else {
+                    "# Notes".to_string()
+                };
+                let mut note_count = 0;
+
+                for thing in things {
+                    let name_matches = query_lower
+                        .as_ref()
+                        .is_none_or(|q| thing.name().to_string().to_lowercase().contains(q));
+
+                    let notes: Vec<&Note> = thing
+                        .notes()
+                        .iter()
+                        .filter(|note| {
+                            name_matches
+                                || query_lower
+                                    .as_ref()
+                                    .is_none_or(|q| note.text.to_lowercase().contains(q))
+                        })
+                        .collect();
+
+                    if !notes.is_empty() {
+                        output.push_str(&format!("\n\n{}\n", thing.display_summary()));
+                        notes.iter().for_each(|note| {
+                            output.push_str(&format!("\n* {}", note.display(calendar.as_ref())))
+                        });
+                        note_count += notes.len();
+                    }
+                }
+
+                if note_count == 0 {
+                    if let Some(query) = &query {
+                        output.push_str(&format!("\n\n*No notes match \"{}\".*", query));
+                    } else {
+                        output.push_str("\n\n*There are no notes in your journal yet. Use `note [name]: [text]` to add one.*");
+                    }
+                }
+
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl ContextAwareParse for NoteCommand {
+    async fn parse_input(input: &str, _app_meta: &AppMeta) -> CommandMatches<Self> {
+        if input.eq_ci("notes") {
+            CommandMatches::new_canonical(Self::Search { query: None })
+        } else if let Some(query) = input.strip_prefix_ci("notes ") {
+            let query = query.trim();
+
+            if query.is_empty() {
+                CommandMatches::default()
+            } else {
+                CommandMatches::new_canonical(Self::Search {
+                    query: Some(query.to_string()),
+                })
+            }
+        } else if let Some((name, text)) = input
+            .strip_prefix_ci("note ")
+            .and_then(|s| s.split_once(':'))
+        {
+            let (name, text) = (name.trim(), text.trim());
+
+            if name.is_empty() || text.is_empty() {
+                CommandMatches::default()
+            } else {
+                CommandMatches::new_canonical(Self::Add {
+                    name: name.to_string(),
+                    text: text.to_string(),
+                })
+            }
+        } else {
+            CommandMatches::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Autocomplete for NoteCommand {
+    async fn autocomplete(input: &str, app_meta: &AppMeta) -> Vec<AutocompleteSuggestion> {
+        if let Some(name) = input
+            .strip_prefix_ci("note ")
+            .filter(|name| !name.contains(':'))
+        {
+            app_meta
+                .repository
+                .get_by_name_start(name.trim_start(), Some(10))
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|thing| {
+                    AutocompleteSuggestion::new(
+                        format!("note {}: [text]", thing.name()),
+                        format!("add a note to the {}", thing.as_str()),
+                    )
+                })
+                .collect()
+        } else if input.is_empty() {
+            Vec::new()
+        } else {
+            [
+                ("note [name]: [text]", "add a note to an entry"),
+                ("notes", "list all notes"),
+                ("notes [search]", "search notes"),
+            ]
+            .into_iter()
+            .filter(|(term, _)| term.starts_with_ci(input))
+            .map(|(term, summary)| AutocompleteSuggestion::new(term, summary))
+            .collect()
+        }
+    }
+}
+
+impl fmt::Display for NoteCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Add { name, text } => write!(f, "note {}: {}", name, text),
+            Self::Search { query: None } => write!(f, "notes"),
+            Self::Search { query: Some(query) } => write!(f, "notes {}", query),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::assert_autocomplete;
+    use crate::{Event, NullDataStore};
+    use tokio_test::block_on;
+
+    #[test]
+    fn parse_input_test() {
+        let app_meta = app_meta();
+
+        assert_eq!(
+            CommandMatches::new_canonical(NoteCommand::Add {
+                name: "Bob".to_string(),
+                text: "owes the party 50gp: payable on demand".to_string(),
+            }),
+            block_on(NoteCommand::parse_input(
+                "note Bob : owes the party 50gp: payable on demand",
+                &app_meta,
+            )),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(NoteCommand::Search { query: None }),
+            block_on(NoteCommand::parse_input("NOTES", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::new_canonical(NoteCommand::Search {
+                query: Some("gold".to_string()),
+            }),
+            block_on(NoteCommand::parse_input("notes gold", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(NoteCommand::parse_input("note Bob", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(NoteCommand::parse_input("note Bob:", &app_meta)),
+        );
+
+        assert_eq!(
+            CommandMatches::default(),
+            block_on(NoteCommand::parse_input("note : hello", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn autocomplete_test() {
+        let app_meta = app_meta();
+
+        assert_autocomplete(
+            &[
+                ("note [name]: [text]", "add a note to an entry"),
+                ("notes", "list all notes"),
+                ("notes [search]", "search notes"),
+            ][..],
+            block_on(NoteCommand::autocomplete("no", &app_meta)),
+        );
+
+        assert_autocomplete(
+            &[("notes [search]", "search notes")][..],
+            block_on(NoteCommand::autocomplete("notes ", &app_meta)),
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let app_meta = app_meta();
+
+        [
+            NoteCommand::Add {
+                name: "Bob".to_string(),
+                text: "owes the party 50gp".to_string(),
+            },
+            NoteCommand::Search { query: None },
+            NoteCommand::Search {
+                query: Some("gold".to_string()),
+            },
+        ]
+        .into_iter()
+        .for_each(|command| {
+            let command_string = command.to_string();
+            assert_ne!("", command_string);
+
+            assert_eq!(
+                CommandMatches::new_canonical(command),
+                block_on(NoteCommand::parse_input(&command_string, &app_meta)),
+                "{}",
+                command_string,
+            );
+        });
+    }
+
+    fn event_dispatcher(_event: Event) {}
+
+    fn app_meta() -> AppMeta {
+        AppMeta::new(NullDataStore::default(), &event_dispatcher)
+    }
+}
@@ -0,0 +1,95 @@
+pub use command::NoteCommand;
+
+mod command;
+
+use crate::time::{Calendar, Time};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A freeform note attached to an NPC or place, eg. "owes the party 50gp", stamped with the game
+/// time at which it was written.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Note {
+    pub time: Time,
+    pub text: String,
+}
+
+/// A single note, with its time shown in the campaign calendar if one is set.
+pub struct NoteView<'a>(&'a Note, Option<&'a Calendar>);
+
+/// The notes section of an NPC or place's details view.
+pub struct NotesView<'a>(&'a [Note], Option<&'a Calendar>);
+
+impl Note {
+    pub fn display<'a>(&'a self, calendar: Option<&'a Calendar>) -> NoteView<'a> {
+        NoteView(self, calendar)
+    }
+
+    pub fn display_list<'a>(notes: &'a [Note], calendar: Option<&'a Calendar>) -> NotesView<'a> {
+        NotesView(notes, calendar)
+    }
+}
+
+impl<'a> fmt::Display for NotesView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, "\n\n## Notes\n")?;
+
+        for note in self.0 {
+            write!(f, "\n* {}", note.display(self.1))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for NoteView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let note = self.0;
+        write!(f, "**{}:** {}", note.time.display_long(self.1), note.text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let notes = [
+            Note {
+                time: Time::try_new(1, 8, 0, 0).unwrap(),
+                text: "owes the party 50gp".to_string(),
+            },
+            Note {
+                time: Time::try_new(3, 14, 30, 0).unwrap(),
+                text: "paid back 20gp".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            "\n\n## Notes\n\n* **day 1 at 8:00:00 am:** owes the party 50gp\n* **day 3 at 2:30:00 pm:** paid back 20gp",
+            Note::display_list(&notes, None).to_string(),
+        );
+
+        assert_eq!("", Note::display_list(&[], None).to_string());
+    }
+
+    #[test]
+    fn serialize_deserialize_test() {
+        let note = Note {
+            time: Time::try_new(1, 8, 0, 0).unwrap(),
+            text: "owes the party 50gp".to_string(),
+        };
+
+        let json = serde_json::to_string(&note).unwrap();
+        assert_eq!(
+            r#"{"time":"1:08:00:00","text":"owes the party 50gp"}"#,
+            json
+        );
+        assert_eq!(note, serde_json::from_str(&json).unwrap());
+    }
+}
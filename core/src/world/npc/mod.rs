@@ -12,7 +12,8 @@ mod size;
 mod species;
 mod view;
 
-use super::{Demographics, Field, Generate, Place, PlaceUuid};
+use super::{Demographics, Field, Generate, Note, Place, PlaceUuid};
+use crate::time::Calendar;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +30,12 @@ pub struct Npc {
     pub species: Field<Species>,
     pub ethnicity: Field<Ethnicity>,
     pub location_uuid: Field<PlaceUuid>,
+
+    #[serde(default = "Field::locked_none", skip_serializing_if = "Field::is_none")]
+    pub notes: Field<Vec<Note>>,
+
+    #[serde(default = "Field::locked_none", skip_serializing_if = "Field::is_none")]
+    pub tags: Field<Vec<String>>,
     // pub home: Field<PlaceUuid>,
     // pub occupation: Field<Role>,
     // pub languages: Field<Vec<String>>,
@@ -41,6 +48,9 @@ pub struct Npc {
 #[derive(Debug, Default)]
 pub struct NpcRelations {
     pub location: Option<(Place, Option<Place>)>,
+
+    /// The campaign calendar, if any, in which to show the times of notes.
+    pub calendar: Option<Calendar>,
 }
 
 impl Npc {
@@ -78,6 +88,8 @@ impl Npc {
             species,
             ethnicity,
             location_uuid,
+            notes,
+            tags,
         } = self;
 
         name.lock();
@@ -88,6 +100,75 @@ impl Npc {
         species.lock();
         ethnicity.lock();
         location_uuid.lock();
+        notes.lock();
+        tags.lock();
+    }
+
+    /// The names of the unlocked fields. The lock state isn't serialized along with the fields,
+    /// so this is used to preserve it where it matters.
+    pub fn unlocked_fields(&self) -> Vec<&'static str> {
+        let Self {
+            uuid: _,
+            name,
+            gender,
+            age,
+            age_years,
+            size,
+            species,
+            ethnicity,
+            location_uuid,
+            notes,
+            tags,
+        } = self;
+
+        [
+            ("name", name.is_unlocked()),
+            ("gender", gender.is_unlocked()),
+            ("age", age.is_unlocked()),
+            ("age_years", age_years.is_unlocked()),
+            ("size", size.is_unlocked()),
+            ("species", species.is_unlocked()),
+            ("ethnicity", ethnicity.is_unlocked()),
+            ("location_uuid", location_uuid.is_unlocked()),
+            ("notes", notes.is_unlocked()),
+            ("tags", tags.is_unlocked()),
+        ]
+        .into_iter()
+        .filter(|(_, unlocked)| *unlocked)
+        .map(|(field, _)| field)
+        .collect()
+    }
+
+    pub fn unlock_fields(&mut self, fields: &[String]) {
+        let Self {
+            uuid: _,
+            name,
+            gender,
+            age,
+            age_years,
+            size,
+            species,
+            ethnicity,
+            location_uuid,
+            notes,
+            tags,
+        } = self;
+
+        for field in fields {
+            match field.as_str() {
+                "name" => name.unlock(),
+                "gender" => gender.unlock(),
+                "age" => age.unlock(),
+                "age_years" => age_years.unlock(),
+                "size" => size.unlock(),
+                "species" => species.unlock(),
+                "ethnicity" => ethnicity.unlock(),
+                "location_uuid" => location_uuid.unlock(),
+                "notes" => notes.unlock(),
+                "tags" => tags.unlock(),
+                _ => {}
+            }
+        }
     }
 
     pub fn apply_diff(&mut self, diff: &mut Self) {
@@ -101,6 +182,8 @@ impl Npc {
             species,
             ethnicity,
             location_uuid,
+            notes,
+            tags,
         } = self;
 
         name.apply_diff(&mut diff.name);
@@ -111,6 +194,8 @@ impl Npc {
         species.apply_diff(&mut diff.species);
         ethnicity.apply_diff(&mut diff.ethnicity);
         location_uuid.apply_diff(&mut diff.location_uuid);
+        notes.apply_diff(&mut diff.notes);
+        tags.apply_diff(&mut diff.tags);
     }
 }
 
@@ -149,6 +234,7 @@ impl Generate for Npc {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::time::Time;
     use rand::prelude::*;
 
     #[test]
@@ -185,6 +271,24 @@ mod test {
         assert_eq!(npc, value);
     }
 
+    #[test]
+    fn serialize_deserialize_test_notes_and_tags() {
+        let npc = Npc {
+            notes: vec![Note {
+                time: Time::try_new(1, 8, 0, 0).unwrap(),
+                text: "You shall not pass!".to_string(),
+            }]
+            .into(),
+            tags: vec!["wizard".to_string()].into(),
+            ..gandalf()
+        };
+
+        let json = r#"{"uuid":"00000000-0000-0000-0000-000000000000","name":"Gandalf the Grey","gender":"neuter","age":"geriatric","age_years":65535,"size":{"type":"Medium","height":72,"weight":200},"species":"human","ethnicity":"human","location_uuid":null,"notes":[{"time":"1:08:00:00","text":"You shall not pass!"}],"tags":["wizard"]}"#;
+
+        assert_eq!(json, serde_json::to_string(&npc).unwrap());
+        assert_eq!(npc, serde_json::from_str::<Npc>(json).unwrap());
+    }
+
     #[test]
     fn apply_diff_test_no_change() {
         let mut npc = gandalf();
@@ -228,6 +332,8 @@ mod test {
             species: Species::Human.into(),
             ethnicity: Ethnicity::Human.into(),
             location_uuid: None.into(),
+            notes: None.into(),
+            tags: None.into(),
         }
     }
 
@@ -247,6 +353,8 @@ mod test {
                 species: Field::Locked(None),
                 ethnicity: Field::Locked(None),
                 location_uuid: Field::Locked(None),
+                notes: Field::Locked(None),
+                tags: Field::Locked(None),
             },
             npc,
         );
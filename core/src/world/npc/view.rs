@@ -1,4 +1,5 @@
 use super::{Age, Gender, Npc, NpcRelations};
+use crate::world::{display_tags, Note};
 use std::fmt;
 
 pub struct SummaryView<'a>(&'a Npc);
@@ -147,6 +148,18 @@ impl<'a> fmt::Display for DetailsView<'a> {
             })
             .transpose()?;
 
+        if let Some(tags) = npc.tags.value().filter(|tags| !tags.is_empty()) {
+            write!(f, "\\\n**Tags:** {}", display_tags(tags))?;
+        }
+
+        if let Some(notes) = npc.notes.value() {
+            write!(
+                f,
+                "{}",
+                Note::display_list(notes, relations.calendar.as_ref())
+            )?;
+        }
+
         write!(f, "\n\n</div>")?;
 
         Ok(())
@@ -325,6 +338,7 @@ mod test {
                 },
                 None,
             )),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -361,6 +375,7 @@ mod test {
                     ..Default::default()
                 }),
             )),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -30,6 +30,15 @@ pub enum BuildingType {
 }
 
 impl BuildingType {
+    /// Whether a building of type `other` is also of this type, eg. an inn is a business.
+    pub fn includes(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Any, _) => true,
+            (Self::Business(business::BusinessType::Any), Self::Business(_)) => true,
+            _ => self == other,
+        }
+    }
+
     pub const fn get_emoji(&self) -> Option<&'static str> {
         match self {
             Self::Any => None,
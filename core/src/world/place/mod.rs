@@ -1,3 +1,4 @@
+pub use region::{GeographyType, RegionType};
 pub use view::{DescriptionView, DetailsView, NameView, SummaryView};
 
 mod building;
@@ -5,7 +6,8 @@ mod location;
 mod region;
 mod view;
 
-use super::{Demographics, Field, Generate};
+use super::{Demographics, Field, Generate, Note};
+use crate::time::Calendar;
 use initiative_macros::WordList;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -21,6 +23,12 @@ pub struct Place {
 
     pub name: Field<String>,
     pub description: Field<String>,
+
+    #[serde(default = "Field::locked_none", skip_serializing_if = "Field::is_none")]
+    pub notes: Field<Vec<Note>>,
+
+    #[serde(default = "Field::locked_none", skip_serializing_if = "Field::is_none")]
+    pub tags: Field<Vec<String>>,
     // pub architecture: Option<String>,
     // pub floors: Field<u8>,
     // pub owner: Field<Vec<NpcUuid>>,
@@ -35,6 +43,9 @@ pub struct Place {
 #[derive(Debug, Default)]
 pub struct PlaceRelations {
     pub location: Option<(Place, Option<Place>)>,
+
+    /// The campaign calendar, if any, in which to show the times of notes.
+    pub calendar: Option<Calendar>,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, WordList)]
@@ -76,12 +87,67 @@ impl Place {
             subtype,
             name,
             description,
+            notes,
+            tags,
         } = self;
 
         location_uuid.lock();
         subtype.lock();
         name.lock();
         description.lock();
+        notes.lock();
+        tags.lock();
+    }
+
+    /// The names of the unlocked fields. The lock state isn't serialized along with the fields,
+    /// so this is used to preserve it where it matters.
+    pub fn unlocked_fields(&self) -> Vec<&'static str> {
+        let Self {
+            uuid: _,
+            location_uuid,
+            subtype,
+            name,
+            description,
+            notes,
+            tags,
+        } = self;
+
+        [
+            ("location_uuid", location_uuid.is_unlocked()),
+            ("subtype", subtype.is_unlocked()),
+            ("name", name.is_unlocked()),
+            ("description", description.is_unlocked()),
+            ("notes", notes.is_unlocked()),
+            ("tags", tags.is_unlocked()),
+        ]
+        .into_iter()
+        .filter(|(_, unlocked)| *unlocked)
+        .map(|(field, _)| field)
+        .collect()
+    }
+
+    pub fn unlock_fields(&mut self, fields: &[String]) {
+        let Self {
+            uuid: _,
+            location_uuid,
+            subtype,
+            name,
+            description,
+            notes,
+            tags,
+        } = self;
+
+        for field in fields {
+            match field.as_str() {
+                "location_uuid" => location_uuid.unlock(),
+                "subtype" => subtype.unlock(),
+                "name" => name.unlock(),
+                "description" => description.unlock(),
+                "notes" => notes.unlock(),
+                "tags" => tags.unlock(),
+                _ => {}
+            }
+        }
     }
 
     pub fn apply_diff(&mut self, diff: &mut Self) {
@@ -91,12 +157,16 @@ impl Place {
             subtype,
             name,
             description,
+            notes,
+            tags,
         } = self;
 
         location_uuid.apply_diff(&mut diff.location_uuid);
         subtype.apply_diff(&mut diff.subtype);
         name.apply_diff(&mut diff.name);
         description.apply_diff(&mut diff.description);
+        notes.apply_diff(&mut diff.notes);
+        tags.apply_diff(&mut diff.tags);
     }
 }
 
@@ -120,6 +190,17 @@ impl Generate for Place {
 }
 
 impl PlaceType {
+    /// Whether a place of type `other` is also of this type, eg. an inn is a building.
+    pub fn includes(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Any, _) => true,
+            (Self::Building(a), Self::Building(b)) => a.includes(b),
+            (Self::Location(location::LocationType::Any), Self::Location(_)) => true,
+            (Self::Region(RegionType::Any), Self::Region(_)) => true,
+            _ => self == other,
+        }
+    }
+
     pub const fn get_emoji(&self) -> &'static str {
         if let Some(emoji) = match self {
             Self::Any => None,
@@ -159,6 +240,7 @@ impl fmt::Display for PlaceType {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::time::Time;
 
     #[test]
     fn generate_test() {
@@ -183,6 +265,23 @@ mod test {
         assert_eq!(PlaceType::Any, PlaceType::default());
     }
 
+    #[test]
+    fn includes_test() {
+        let [any, building, business, inn, temple, region]: [PlaceType; 6] =
+            ["place", "building", "business", "inn", "temple", "region"]
+                .map(|word| word.parse().unwrap());
+
+        assert!(any.includes(&inn));
+        assert!(building.includes(&inn));
+        assert!(business.includes(&inn));
+        assert!(inn.includes(&inn));
+        assert!(!inn.includes(&business));
+        assert!(!business.includes(&temple));
+        assert!(building.includes(&temple));
+        assert!(!region.includes(&inn));
+        assert!(!inn.includes(&any));
+    }
+
     #[test]
     fn place_type_serialize_deserialize_test() {
         {
@@ -233,6 +332,24 @@ mod test {
         assert_eq!(place, value);
     }
 
+    #[test]
+    fn place_serialize_deserialize_test_notes_and_tags() {
+        let place = Place {
+            notes: vec![Note {
+                time: Time::try_new(1, 8, 0, 0).unwrap(),
+                text: "The ale is watered down.".to_string(),
+            }]
+            .into(),
+            tags: vec!["tavern".to_string()].into(),
+            ..oaken_mermaid_inn()
+        };
+
+        let json = r#"{"uuid":"00000000-0000-0000-0000-000000000000","location_uuid":"00000000-0000-0000-0000-000000000000","subtype":"inn","name":"Oaken Mermaid Inn","description":"I am Mordenkainen","notes":[{"time":"1:08:00:00","text":"The ale is watered down."}],"tags":["tavern"]}"#;
+
+        assert_eq!(json, serde_json::to_string(&place).unwrap());
+        assert_eq!(place, serde_json::from_str::<Place>(json).unwrap());
+    }
+
     #[test]
     fn apply_diff_test_no_change() {
         let mut place = oaken_mermaid_inn();
@@ -273,6 +390,8 @@ mod test {
                 subtype: Field::Locked(None),
                 name: Field::Locked(None),
                 description: Field::Locked(None),
+                notes: Field::Locked(None),
+                tags: Field::Locked(None),
             },
             place,
         );
@@ -495,6 +614,8 @@ mod test {
 
             name: "Oaken Mermaid Inn".into(),
             description: "I am Mordenkainen".into(),
+            notes: None.into(),
+            tags: None.into(),
         }
     }
 }
@@ -1,3 +1,5 @@
+pub use geography::GeographyType;
+
 mod geography;
 mod political;
 
@@ -1,4 +1,5 @@
 use super::{Place, PlaceRelations, PlaceType};
+use crate::world::{display_tags, Note};
 use std::fmt;
 
 pub struct NameView<'a>(&'a Place);
@@ -107,12 +108,24 @@ impl<'a> fmt::Display for DetailsView<'a> {
             })
             .transpose()?;
 
+        if let Some(tags) = place.tags.value().filter(|tags| !tags.is_empty()) {
+            write!(f, "\n\n**Tags:** {}", display_tags(tags))?;
+        }
+
         place
             .description
             .value()
             .map(|description| write!(f, "\n\n{}", description))
             .transpose()?;
 
+        if let Some(notes) = place.notes.value() {
+            write!(
+                f,
+                "{}",
+                Note::display_list(notes, relations.calendar.as_ref())
+            )?;
+        }
+
         write!(f, "\n\n</div>")?;
 
         Ok(())
@@ -335,6 +348,7 @@ I am Mordenkainen.
                 },
                 None,
             )),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -371,6 +385,7 @@ I am Mordenkainen.
                     ..Default::default()
                 }),
             )),
+            ..Default::default()
         };
 
         assert_eq!(
@@ -0,0 +1,328 @@
+use crate::utils::{singularize, CaseInsensitiveStr};
+use crate::world::command::ParsedThing;
+use crate::world::npc::{Age, Ethnicity, Gender, Species};
+use crate::world::{merge_tags, parse_tags, Field, Npc, Place, PlaceUuid, Thing};
+use std::fmt;
+use std::str::FromStr;
+
+/// A search of the journal, eg. "elves", "inns in Waterdeep", or "women over 60". Only the fields
+/// of `thing` that have a value are compared, so "elves" matches every elf regardless of their
+/// age or gender.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ThingQuery {
+    /// The entries to match, or `None` to match characters and places alike.
+    pub thing: Option<Thing>,
+
+    /// Only match entries located directly within this place.
+    pub location_uuid: Option<PlaceUuid>,
+
+    pub min_age_years: Option<u16>,
+    pub max_age_years: Option<u16>,
+
+    /// Only match entries with all of these tags.
+    pub tags: Vec<String>,
+}
+
+/// A query as typed by the user. The location is given by name, and needs to be looked up and
+/// added to the query before it can be run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParsedQuery {
+    pub query: ThingQuery,
+    pub location: Option<String>,
+    raw: String,
+}
+
+impl ThingQuery {
+    pub fn matches(&self, thing: &Thing) -> bool {
+        if !self.tags.iter().all(|tag| thing.tags().contains(tag)) {
+            return false;
+        }
+
+        match (thing, &self.thing) {
+            (Thing::Npc(npc), None) => self.npc_matches(npc, &Npc::default()),
+            (Thing::Npc(npc), Some(Thing::Npc(pattern))) => self.npc_matches(npc, pattern),
+            (Thing::Place(place), None) => self.place_matches(place, &Place::default()),
+            (Thing::Place(place), Some(Thing::Place(pattern))) => {
+                self.place_matches(place, pattern)
+            }
+            _ => false,
+        }
+    }
+
+    fn npc_matches(&self, npc: &Npc, pattern: &Npc) -> bool {
+        name_matches(&npc.name, &pattern.name)
+            && field_matches(&npc.gender, &pattern.gender)
+            && field_matches(&npc.age, &pattern.age)
+            && field_matches(&npc.age_years, &pattern.age_years)
+            && field_matches(&npc.species, &pattern.species)
+            && field_matches(&npc.ethnicity, &pattern.ethnicity)
+            && self
+                .location_uuid
+                .as_ref()
+                .is_none_or(|uuid| npc.location_uuid.value() == Some(uuid))
+            && self
+                .min_age_years
+                .is_none_or(|min| npc.age_years.value().is_some_and(|&age| age >= min))
+            && self
+                .max_age_years
+                .is_none_or(|max| npc.age_years.value().is_some_and(|&age| age <= max))
+    }
+
+    fn place_matches(&self, place: &Place, pattern: &Place) -> bool {
+        name_matches(&place.name, &pattern.name)
+            && pattern.subtype.value().is_none_or(|pattern_subtype| {
+                place
+                    .subtype
+                    .value()
+                    .is_some_and(|subtype| pattern_subtype.includes(subtype))
+            })
+            && self
+                .location_uuid
+                .as_ref()
+                .is_none_or(|uuid| place.location_uuid.value() == Some(uuid))
+            && self.min_age_years.is_none()
+            && self.max_age_years.is_none()
+    }
+}
+
+impl FromStr for ParsedQuery {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut query = ThingQuery::default();
+        let (mut description, mut location) = (Vec::new(), Vec::new());
+        let mut in_location = false;
+        let mut words = input.split_whitespace().peekable();
+
+        while let Some(word) = words.next() {
+            if word.starts_with('#') {
+                query.tags = merge_tags(&query.tags, &parse_tags(word));
+            } else if word.in_ci(&["over", "under", "older", "younger"]) {
+                if word.in_ci(&["older", "younger"]) && words.next_if(|w| w.eq_ci("than")).is_none()
+                {
+                    return Err(());
+                }
+
+                let years: u16 = words.next().and_then(|w| w.parse().ok()).ok_or(())?;
+
+                if word.in_ci(&["over", "older"]) {
+                    query.min_age_years = Some(years.saturating_add(1));
+                } else {
+                    query.max_age_years = Some(years.checked_sub(1).ok_or(())?);
+                }
+
+                in_location = false;
+            } else if in_location {
+                location.push(word);
+            } else if word.eq_ci("in") && location.is_empty() {
+                in_location = true;
+            } else {
+                description.push(word);
+            }
+        }
+
+        if in_location && location.is_empty() {
+            return Err(());
+        }
+
+        if description.is_empty()
+            && location.is_empty()
+            && query.tags.is_empty()
+            && query.min_age_years.is_none()
+            && query.max_age_years.is_none()
+        {
+            return Err(());
+        }
+
+        if !description.is_empty() {
+            let words: Vec<String> = description.into_iter().map(singular_term).collect();
+            let parsed = words.join(" ").parse::<ParsedThing<Thing>>()?;
+
+            if !parsed.unknown_words.is_empty() {
+                return Err(());
+            }
+
+            query.thing = Some(without_implied_fields(parsed.thing, &words));
+        }
+
+        Ok(Self {
+            query,
+            location: if location.is_empty() {
+                None
+            } else {
+                Some(location.join(" "))
+            },
+            raw: input.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ParsedQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+fn field_matches<T: PartialEq>(field: &Field<T>, pattern: &Field<T>) -> bool {
+    pattern
+        .value()
+        .is_none_or(|value| field.value() == Some(value))
+}
+
+fn name_matches(field: &Field<String>, pattern: &Field<String>) -> bool {
+    pattern
+        .value()
+        .is_none_or(|value| field.value().is_some_and(|name| name.eq_ci(value)))
+}
+
+/// Find the form of the word that means something, eg. "elves" => "elf".
+fn singular_term(word: &str) -> String {
+    if is_term(word) {
+        word.to_string()
+    } else {
+        singularize(word)
+            .into_iter()
+            .find(|singular| is_term(singular))
+            .unwrap_or_else(|| word.to_string())
+    }
+}
+
+fn is_term(word: &str) -> bool {
+    word.parse::<ParsedThing<Thing>>()
+        .is_ok_and(|parsed| parsed.unknown_words.is_empty())
+}
+
+/// When creating a character, "woman" implies an adult and "elf" implies an elvish name, but that
+/// would be too strict for a search: an elderly woman is still a woman.
+fn without_implied_fields(thing: Thing, words: &[String]) -> Thing {
+    match thing {
+        Thing::Npc(mut npc) => {
+            if !words
+                .iter()
+                .any(|word| word.parse::<Age>().is_ok() && word.parse::<Gender>().is_err())
+            {
+                npc.age = Field::default();
+            }
+
+            if !words
+                .iter()
+                .any(|word| word.parse::<Ethnicity>().is_ok() && word.parse::<Species>().is_err())
+            {
+                npc.ethnicity = Field::default();
+            }
+
+            npc.into()
+        }
+        thing => thing,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world::npc::{Age, Gender, Species};
+    use crate::world::place::PlaceType;
+
+    #[test]
+    fn from_str_test() {
+        assert_eq!(
+            Some(Thing::from(Npc {
+                species: Field::new(Species::Elf),
+                ..Default::default()
+            })),
+            parse("elves").query.thing,
+        );
+
+        let parsed = parse("inns in The Yawning Portal");
+        assert_eq!(
+            Some(Thing::from(Place {
+                subtype: Field::new("inn".parse::<PlaceType>().unwrap()),
+                ..Default::default()
+            })),
+            parsed.query.thing,
+        );
+        assert_eq!(Some("The Yawning Portal".to_string()), parsed.location);
+
+        let parsed = parse("women over 60");
+        assert_eq!(
+            Some(Thing::from(Npc {
+                gender: Field::new(Gender::Feminine),
+                ..Default::default()
+            })),
+            parsed.query.thing,
+        );
+        assert_eq!(Some(61), parsed.query.min_age_years);
+        assert_eq!(None, parsed.query.max_age_years);
+
+        let parsed = parse("old dwarves younger than 100 #villain");
+        assert_eq!(
+            Some(Thing::from(Npc {
+                age: Field::new(Age::Elderly),
+                species: Field::new(Species::Dwarf),
+                ..Default::default()
+            })),
+            parsed.query.thing,
+        );
+        assert_eq!(Some(99), parsed.query.max_age_years);
+        assert_eq!(vec!["villain"], parsed.query.tags);
+
+        let parsed = parse("#villain");
+        assert_eq!(None, parsed.query.thing);
+        assert_eq!(vec!["villain"], parsed.query.tags);
+
+        assert_eq!("women over 60", parse(" women over 60 ").to_string());
+
+        assert_eq!(Err(()), "".parse::<ParsedQuery>());
+        assert_eq!(Err(()), "potatoes".parse::<ParsedQuery>());
+        assert_eq!(Err(()), "women over sixty".parse::<ParsedQuery>());
+        assert_eq!(Err(()), "inns in".parse::<ParsedQuery>());
+    }
+
+    #[test]
+    fn matches_test() {
+        let place_uuid = PlaceUuid::new();
+        let old_woman: Thing = Npc {
+            gender: Field::new(Gender::Feminine),
+            age: Field::new(Age::Elderly),
+            age_years: Field::new(70),
+            species: Field::new(Species::Human),
+            location_uuid: Field::new(place_uuid.clone()),
+            tags: Field::new(vec!["villain".to_string()]),
+            ..Default::default()
+        }
+        .into();
+        let inn: Thing = Place {
+            subtype: Field::new("inn".parse().unwrap()),
+            location_uuid: Field::new(place_uuid.clone()),
+            ..Default::default()
+        }
+        .into();
+
+        assert!(parse("women").query.matches(&old_woman));
+        assert!(parse("women over 60").query.matches(&old_woman));
+        assert!(!parse("women under 60").query.matches(&old_woman));
+        assert!(!parse("men").query.matches(&old_woman));
+        assert!(!parse("elves").query.matches(&old_woman));
+        assert!(parse("#villain").query.matches(&old_woman));
+        assert!(!parse("#ally").query.matches(&old_woman));
+        assert!(!parse("inns").query.matches(&old_woman));
+
+        assert!(parse("inns").query.matches(&inn));
+        assert!(parse("businesses").query.matches(&inn));
+        assert!(parse("buildings").query.matches(&inn));
+        assert!(parse("places").query.matches(&inn));
+        assert!(!parse("temples").query.matches(&inn));
+        assert!(!parse("women").query.matches(&inn));
+        assert!(!parse("places over 10").query.matches(&inn));
+
+        let mut query = parse("places").query;
+        query.location_uuid = Some(place_uuid);
+        assert!(query.matches(&inn));
+        query.location_uuid = Some(PlaceUuid::new());
+        assert!(!query.matches(&inn));
+    }
+
+    fn parse(input: &str) -> ParsedQuery {
+        input.parse().unwrap()
+    }
+}
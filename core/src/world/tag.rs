@@ -0,0 +1,89 @@
+use crate::storage::Repository;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A list of tags, displayed as eg. "#villain, #waterdeep".
+pub struct TagsView<'a>(&'a [String]);
+
+/// Parse a list of tags such as "villain, #waterdeep" or "#villain #waterdeep". Tags are single
+/// words, case-insensitive, and stored without the leading "#".
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let tags: BTreeSet<String> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|word| word.trim_start_matches('#').to_lowercase())
+        .filter(|word| !word.is_empty() && word != "and")
+        .collect();
+
+    tags.into_iter().collect()
+}
+
+/// Combine two lists of tags, keeping the result sorted and free of duplicates.
+pub fn merge_tags(existing: &[String], new: &[String]) -> Vec<String> {
+    let tags: BTreeSet<&String> = existing.iter().chain(new.iter()).collect();
+    tags.into_iter().cloned().collect()
+}
+
+/// Get every tag in use in the journal, sorted alphabetically.
+pub async fn journal_tags(repository: &Repository) -> Vec<String> {
+    let tags: BTreeSet<String> = repository
+        .journal()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|thing| thing.tags().iter().cloned())
+        .collect();
+
+    tags.into_iter().collect()
+}
+
+pub fn display_tags(tags: &[String]) -> TagsView {
+    TagsView(tags)
+}
+
+impl<'a> fmt::Display for TagsView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, tag) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "#{}", tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_tags_test() {
+        assert_eq!(
+            vec!["villain", "waterdeep"],
+            parse_tags("Waterdeep, villain and #WATERDEEP"),
+        );
+        assert_eq!(vec!["a", "b"], parse_tags("#a #b"));
+        assert!(parse_tags(" , #").is_empty());
+    }
+
+    #[test]
+    fn merge_tags_test() {
+        assert_eq!(
+            vec!["ally", "villain", "waterdeep"],
+            merge_tags(
+                &["villain".to_string(), "waterdeep".to_string()],
+                &["ally".to_string(), "villain".to_string()],
+            ),
+        );
+    }
+
+    #[test]
+    fn display_tags_test() {
+        assert_eq!(
+            "#villain, #waterdeep",
+            display_tags(&["villain".to_string(), "waterdeep".to_string()]).to_string(),
+        );
+        assert_eq!("", display_tags(&[]).to_string());
+    }
+}
@@ -1,4 +1,6 @@
-use super::{Demographics, Field, Generate, Npc, NpcRelations, Place, PlaceRelations};
+use super::{
+    Demographics, Field, Generate, Note, Npc, NpcRelations, Place, PlaceRelations, PlaceUuid,
+};
 use crate::world::command::ParsedThing;
 use crate::world::npc::{DetailsView as NpcDetailsView, Gender};
 use crate::world::place::DetailsView as PlaceDetailsView;
@@ -41,6 +43,13 @@ impl Thing {
         }
     }
 
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            Thing::Place(place) => place.name = Field::new(name),
+            Thing::Npc(npc) => npc.name = Field::new(name),
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Thing::Place(..) => "place",
@@ -88,6 +97,40 @@ impl Thing {
         }
     }
 
+    pub fn notes(&self) -> &[Note] {
+        match self {
+            Self::Npc(npc) => &npc.notes,
+            Self::Place(place) => &place.notes,
+        }
+        .value()
+        .map_or(&[], |notes| notes.as_slice())
+    }
+
+    pub fn tags(&self) -> &[String] {
+        match self {
+            Self::Npc(npc) => &npc.tags,
+            Self::Place(place) => &place.tags,
+        }
+        .value()
+        .map_or(&[], |tags| tags.as_slice())
+    }
+
+    /// The place this thing is located in, if any.
+    pub fn location_uuid(&self) -> Option<&PlaceUuid> {
+        match self {
+            Self::Npc(npc) => &npc.location_uuid,
+            Self::Place(place) => &place.location_uuid,
+        }
+        .value()
+    }
+
+    pub fn set_location_uuid(&mut self, location_uuid: PlaceUuid) {
+        match self {
+            Self::Npc(npc) => npc.location_uuid = location_uuid.into(),
+            Self::Place(place) => place.location_uuid = location_uuid.into(),
+        }
+    }
+
     pub fn place(&self) -> Option<&Place> {
         if let Self::Place(place) = self {
             Some(place)
@@ -142,6 +185,20 @@ impl Thing {
         }
     }
 
+    pub fn unlocked_fields(&self) -> Vec<&'static str> {
+        match self {
+            Self::Npc(npc) => npc.unlocked_fields(),
+            Self::Place(place) => place.unlocked_fields(),
+        }
+    }
+
+    pub fn unlock_fields(&mut self, fields: &[String]) {
+        match self {
+            Self::Npc(npc) => npc.unlock_fields(fields),
+            Self::Place(place) => place.unlock_fields(fields),
+        }
+    }
+
     #[allow(clippy::result_unit_err)]
     pub fn try_apply_diff(&mut self, diff: &mut Self) -> Result<(), ()> {
         match (self, diff) {
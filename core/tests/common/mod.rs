@@ -1,7 +1,16 @@
 use initiative_core::app::AutocompleteSuggestion;
-use initiative_core::{app, App, BackupData, DataStore, Event, MemoryDataStore, NullDataStore};
+use initiative_core::{
+    app, App, BackupData, DataStore, Event, MemoryDataStore, NullDataStore, SyncTransport,
+};
+use std::cell::RefCell;
 use tokio_test::block_on;
 
+thread_local! {
+    /// The last event dispatched by an app from `sync_app_with_events`. Each test runs on its own
+    /// thread, so tests running in parallel never see each other's events.
+    static LAST_EVENT: RefCell<Option<Event>> = const { RefCell::new(None) };
+}
+
 pub fn get_name(output: &str) -> String {
     output
         .lines()
@@ -11,6 +20,13 @@ pub fn get_name(output: &str) -> String {
         .to_string()
 }
 
+/// A small journal around Waterdeep, with places inside places, characters, a note, and a tag.
+/// The CLI's integration tests import the same file.
+#[allow(dead_code)]
+pub fn waterdeep() -> BackupData {
+    serde_json::from_str(include_str!("../fixtures/waterdeep.json")).unwrap()
+}
+
 #[allow(dead_code)]
 pub fn sync_app() -> SyncApp {
     sync_app_with_data_store(MemoryDataStore::default())
@@ -34,6 +50,22 @@ pub fn sync_app_with_dispatcher<F: Fn(Event)>(event_dispatcher: &'static F) -> S
     app
 }
 
+/// An app whose most recent event can be retrieved with `take_event`.
+#[allow(dead_code)]
+pub fn sync_app_with_events() -> SyncApp {
+    sync_app_with_dispatcher(&capture_event)
+}
+
+/// Take the last event dispatched on this thread by an app from `sync_app_with_events`.
+#[allow(dead_code)]
+pub fn take_event() -> Option<Event> {
+    LAST_EVENT.with(|event| event.borrow_mut().take())
+}
+
+fn capture_event(event: Event) {
+    LAST_EVENT.with(|last_event| *last_event.borrow_mut() = Some(event));
+}
+
 pub struct SyncApp(App);
 
 fn event_dispatcher(_event: Event) {}
@@ -62,4 +94,12 @@ impl SyncApp {
     pub fn bulk_import(&mut self, data: BackupData) -> Result<String, String> {
         block_on(self.0.bulk_import(data))
     }
+
+    pub fn bulk_import_csv(&mut self, csv: &str) -> Result<String, String> {
+        block_on(self.0.bulk_import_csv(csv))
+    }
+
+    pub fn sync(&mut self, transport: &mut dyn SyncTransport) -> Result<String, String> {
+        block_on(self.0.sync(transport))
+    }
 }
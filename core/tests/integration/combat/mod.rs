@@ -0,0 +1,243 @@
+use crate::common::sync_app;
+
+#[test]
+fn initiative_order_is_tracked() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "There is no fight in progress. Use `fight [name]` to add a combatant.",
+        app.command("initiative").unwrap_err(),
+    );
+
+    app.command("fight Bob 12").unwrap();
+    app.command("fight Goblin 8").unwrap();
+
+    {
+        let output = app.command("initiative Aria 17").unwrap();
+        assert_eq!(
+            "# Initiative: round 1\n\n* **17:** **Aria** _(current turn)_\n* **12:** Bob\n* **8:** Goblin (7/7 HP)\n\n_Aria joins the fight with an initiative of 17. Use `undo` to reverse._",
+            output,
+        );
+    }
+
+    {
+        let output = app.command("next").unwrap();
+        assert!(output.contains("**Bob** _(current turn)_"), "{}", output);
+        assert!(
+            output.ends_with("_It is Bob's turn. Use `undo` to reverse._"),
+            "{}",
+            output
+        );
+    }
+
+    app.command("next").unwrap();
+
+    {
+        let output = app.command("next").unwrap();
+        assert!(output.starts_with("# Initiative: round 2"), "{}", output);
+        assert!(
+            output.ends_with("_It is Aria's turn. One round has passed. Use `undo` to reverse._"),
+            "{}",
+            output,
+        );
+    }
+
+    assert_eq!(
+        "It is currently day 1 at 8:00:06 am.",
+        app.command("now").unwrap(),
+    );
+
+    assert_eq!(
+        "The fight is over after 2 rounds. Use `undo` to reverse.",
+        app.command("end fight").unwrap(),
+    );
+
+    assert!(app.command("initiative").is_err());
+    app.command("undo").unwrap();
+    assert!(app
+        .command("initiative")
+        .unwrap()
+        .starts_with("# Initiative: round 2"));
+}
+
+#[test]
+fn new_round_is_undone_in_one_step() {
+    let mut app = sync_app();
+
+    app.command("fight Bob 12").unwrap();
+
+    let output = app.command("next").unwrap();
+    assert!(output.starts_with("# Initiative: round 2"), "{}", output);
+    assert_eq!(
+        "It is currently day 1 at 8:00:06 am.",
+        app.command("now").unwrap(),
+    );
+
+    app.command("undo").unwrap();
+
+    assert!(app
+        .command("initiative")
+        .unwrap()
+        .starts_with("# Initiative: round 1"));
+    assert_eq!(
+        "It is currently day 1 at 8:00:00 am.",
+        app.command("now").unwrap(),
+    );
+}
+
+#[test]
+fn journal_characters_can_join() {
+    let mut app = sync_app();
+
+    app.command("elf named Aria Moonwhisper").unwrap();
+
+    let output = app.command("fight aria moonwhisper 14").unwrap();
+    assert!(
+        output.contains("**Aria Moonwhisper** _(current turn)_"),
+        "{}",
+        output,
+    );
+
+    assert_eq!(
+        "Aria Moonwhisper is already in the fight.",
+        app.command("fight Aria Moonwhisper").unwrap_err(),
+    );
+}
+
+#[test]
+fn srd_monsters_can_join() {
+    let mut app = sync_app();
+
+    {
+        let output = app.command("fight ogre 12").unwrap();
+        assert!(
+            output.contains("* **12:** **Ogre (59/59 HP)** _(current turn)_"),
+            "{}",
+            output,
+        );
+        assert!(
+            output.ends_with(
+                "_Ogre joins the fight with an initiative of 12 and 59 HP. Use `undo` to reverse._"
+            ),
+            "{}",
+            output,
+        );
+    }
+
+    for name in ["Goblin", "Goblin 2", "Goblin 3"] {
+        let output = app.command("fight goblin").unwrap();
+        let initiative: i32 = output
+            .rsplit_once(&format!("_{} joins the fight with an initiative of ", name))
+            .unwrap_or_else(|| panic!("{}", output))
+            .1
+            .split(' ')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert!((3..=22).contains(&initiative), "{}", output);
+        assert!(output.contains(&format!("{} (7/7 HP)", name)), "{}", output);
+    }
+
+    app.command("damage Goblin 2 3").unwrap();
+    assert!(app
+        .command("initiative")
+        .unwrap()
+        .contains("Goblin 2 (4/7 HP)"));
+}
+
+#[test]
+fn initiative_can_be_rolled() {
+    let mut app = sync_app();
+
+    for _ in 0..10 {
+        app.command("end fight").ok();
+
+        let output = app.command("fight goblin +2").unwrap();
+        let initiative: i32 = output
+            .lines()
+            .nth(2)
+            .unwrap()
+            .trim_start_matches("* **")
+            .split(':')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert!((3..=22).contains(&initiative), "{}", output);
+    }
+}
+
+#[test]
+fn hit_points_and_conditions() {
+    let mut app = sync_app();
+
+    app.command("fight Goblin Archer 10").unwrap();
+
+    assert_eq!(
+        "Goblin Archer's hit points aren't being tracked. Use `hp Goblin Archer [amount]` to set them.",
+        app.command("damage goblin 3").unwrap_err(),
+    );
+
+    app.command("hp goblin 7").unwrap();
+
+    {
+        let output = app.command("damage goblin 3").unwrap();
+        assert!(output.contains("Goblin Archer (4/7 HP)"), "{}", output);
+        assert!(
+            output.ends_with(
+                "_Goblin Archer takes 3 damage and has 4 HP remaining. Use `undo` to reverse._"
+            ),
+            "{}",
+            output,
+        );
+    }
+
+    {
+        let output = app.command("heal goblin 10").unwrap();
+        assert!(output.contains("Goblin Archer (7/7 HP)"), "{}", output);
+    }
+
+    {
+        let output = app.command("condition goblin prone").unwrap();
+        assert!(
+            output.contains("Goblin Archer (7/7 HP, prone)"),
+            "{}",
+            output
+        );
+        assert!(
+            output.ends_with("_Goblin Archer is now prone. Use `undo` to reverse._"),
+            "{}",
+            output,
+        );
+    }
+
+    {
+        let output = app.command("condition goblin prone").unwrap();
+        assert!(output.contains("Goblin Archer (7/7 HP)"), "{}", output);
+    }
+
+    {
+        let output = app.command("damage goblin 10").unwrap();
+        assert!(output.contains("Goblin Archer (down)"), "{}", output);
+    }
+
+    assert_eq!(
+        "There is no combatant named \"Bob\".",
+        app.command("damage Bob 3").unwrap_err(),
+    );
+    for command in [
+        "hp goblin 99999",
+        "damage goblin 70000",
+        "heal goblin 99999999999",
+    ] {
+        assert_eq!(
+            "That's more hit points than can be tracked. The most is 65535.",
+            app.command(command).unwrap_err(),
+            "{}",
+            command,
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::common::{sync_app, sync_app_with_data_store, sync_app_with_events, take_event};
+use initiative_core::{Event, MemoryDataStore};
+
+#[test]
+fn log_records_commands() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "# Session 1\n\n*Nothing has happened yet this session.*",
+        app.command("log").unwrap(),
+    );
+
+    app.command("+1h").unwrap();
+    app.command("time mark dinner").unwrap();
+    app.command("not a real command").unwrap_err();
+
+    assert_eq!(
+        "# Session 1\n\n* **day 1 at 8:00:00 am:** `+1h` — It is now day 1 at 9:00:00 am.\n* **day 1 at 9:00:00 am:** `time mark dinner` — Bookmarked dinner at day 1 at 9:00:00 am.\n\n*To download the full log as a campaign recap, use `log export`.*",
+        app.command("log").unwrap(),
+    );
+}
+
+#[test]
+fn log_sessions_and_days() {
+    let data_store = MemoryDataStore::default();
+
+    {
+        let mut app = sync_app_with_data_store(data_store.clone());
+        app.command("+1d").unwrap();
+        app.command("+1d").unwrap();
+    }
+
+    let mut app = sync_app_with_data_store(data_store);
+    app.command("now").unwrap();
+
+    let output = app.command("log").unwrap();
+    assert!(output.starts_with("# Session 2\n\n"), "{}", output);
+    assert!(
+        output.contains("`now` — It is currently day 3"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("`+1d`"), "{}", output);
+
+    let output = app.command("log session 1").unwrap();
+    assert!(output.starts_with("# Session 1\n\n"), "{}", output);
+    assert_eq!(2, output.matches("`+1d`").count(), "{}", output);
+
+    let output = app.command("log day 2").unwrap();
+    assert!(
+        output.starts_with("# Session 1\n\n* **day 2 at 8:00:00 am:** `+1d`"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("`now`"), "{}", output);
+
+    assert_eq!(
+        "There is nothing in the log for session 5.",
+        app.command("log session 5").unwrap_err(),
+    );
+    assert_eq!(
+        "There is nothing in the log for day 10.",
+        app.command("log day 10").unwrap_err(),
+    );
+}
+
+#[test]
+fn log_export() {
+    let mut app = sync_app_with_events();
+
+    assert_eq!(
+        "The session log is empty, so there's nothing to export yet.",
+        app.command("log export").unwrap(),
+    );
+
+    app.command("+1h").unwrap();
+
+    assert_eq!(
+        "The session log is exporting. Your download should begin shortly.",
+        app.command("log export").unwrap(),
+    );
+
+    let markdown = if let Some(Event::ExportLog(markdown)) = take_event() {
+        markdown
+    } else {
+        panic!("No export event.");
+    };
+
+    assert_eq!(
+        "# Session 1\n\n* **day 1 at 8:00:00 am:** `+1h` — It is now day 1 at 9:00:00 am.",
+        markdown,
+    );
+}
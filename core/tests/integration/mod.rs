@@ -1,5 +1,9 @@
 mod app;
+mod combat;
+mod log;
 mod reference;
 mod storage;
 mod time;
+mod travel;
+mod weather;
 mod world;
@@ -1,4 +1,4 @@
-use crate::common::{sync_app, sync_app_with_data_store};
+use crate::common::{sync_app, sync_app_with_data_store, waterdeep};
 use initiative_core::NullDataStore;
 
 #[test]
@@ -116,3 +116,96 @@ fn delete_works_with_unusable_data_store() {
         app.command("redo").unwrap(),
     );
 }
+
+#[test]
+fn place_with_contents_is_not_deleted() {
+    let mut app = sync_app_with_places();
+
+    let output = app.command("delete waterdeep").unwrap_err();
+    assert!(
+        output.starts_with(
+            "Waterdeep can't be deleted because the following entries are in it:\n\n* ",
+        ),
+        "{}",
+        output,
+    );
+    assert!(
+        output.find("`Laeral` (adult elf, she/her)").unwrap()
+            < output.find("`The Yawning Portal` (inn)").unwrap(),
+        "{}",
+        output,
+    );
+    assert!(!output.contains("Durnan"), "{}", output);
+    assert!(
+        output.ends_with("\n\nUse `delete Waterdeep and contents` to delete them as well, or `delete Waterdeep keep contents` to move them to Sword Coast."),
+        "{}",
+        output,
+    );
+
+    assert!(app.command("load Waterdeep").is_ok());
+}
+
+#[test]
+fn place_is_deleted_with_contents() {
+    let mut app = sync_app_with_places();
+
+    assert_eq!(
+        "Waterdeep and the 3 entries in it were successfully deleted. Use `undo` to reverse this.",
+        app.command("delete Waterdeep and contents").unwrap(),
+    );
+
+    for name in ["Waterdeep", "The Yawning Portal", "Durnan", "Laeral"] {
+        assert!(app.command(&format!("load {}", name)).is_err(), "{}", name);
+    }
+    assert!(app.command("load Sword Coast").is_ok());
+
+    app.command("undo").unwrap();
+
+    for name in ["Waterdeep", "The Yawning Portal", "Durnan", "Laeral"] {
+        assert!(app.command(&format!("load {}", name)).is_ok(), "{}", name);
+    }
+}
+
+#[test]
+fn place_is_deleted_keeping_contents() {
+    let mut app = sync_app_with_places();
+
+    assert_eq!(
+        "Waterdeep was successfully deleted, and the 2 entries in it were moved to Sword Coast. Use `undo` to reverse this.",
+        app.command("delete Waterdeep keep contents").unwrap(),
+    );
+
+    assert!(app.command("load Waterdeep").is_err());
+    assert!(app
+        .command("journal elves in Sword Coast")
+        .unwrap()
+        .contains("`Laeral`"));
+    assert!(app.command("journal inns in The Yawning Portal").is_ok());
+
+    app.command("undo").unwrap();
+
+    assert!(app
+        .command("journal elves in Waterdeep")
+        .unwrap()
+        .contains("`Laeral`"));
+    assert!(!app
+        .command("journal elves in Sword Coast")
+        .unwrap()
+        .contains("`Laeral`"));
+}
+
+#[test]
+fn place_without_contents_is_deleted_with_contents() {
+    let mut app = sync_app_with_places();
+
+    assert_eq!(
+        "Durnan was successfully deleted. Use `undo` to reverse this.",
+        app.command("delete Durnan and contents").unwrap(),
+    );
+}
+
+fn sync_app_with_places() -> crate::common::SyncApp {
+    let mut app = sync_app();
+    app.bulk_import(waterdeep()).unwrap();
+    app
+}
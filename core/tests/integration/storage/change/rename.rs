@@ -0,0 +1,107 @@
+use crate::common::{sync_app, sync_app_with_data_store};
+use initiative_core::MemoryDataStore;
+
+#[test]
+fn journal_entry_is_renamed() {
+    let data_store = MemoryDataStore::default();
+    let mut app = sync_app_with_data_store(data_store.clone());
+
+    app.command("npc named Bob").unwrap();
+
+    assert_eq!(
+        "Bob was successfully renamed to Robert. Use `undo` to reverse this.",
+        app.command("rename bob to Robert").unwrap(),
+    );
+    assert_eq!(
+        "Robert",
+        data_store
+            .things
+            .borrow()
+            .values()
+            .next()
+            .unwrap()
+            .name()
+            .value()
+            .unwrap(),
+    );
+    assert!(app.command("Robert").is_ok());
+    assert!(app.command("load Bob").is_err());
+
+    let output = app.command("undo").unwrap();
+    assert!(
+        output
+            .ends_with("_Successfully undid renaming Bob to Robert. Use `redo` to reverse this._"),
+        "{}",
+        output,
+    );
+    assert!(app.command("load Bob").is_ok());
+    assert!(app.command("load Robert").is_err());
+}
+
+#[test]
+fn recent_entry_is_renamed_and_save_alias_is_refreshed() {
+    let mut app = sync_app();
+
+    let generated_output = app.command("npc").unwrap();
+    let npc_name = generated_output
+        .lines()
+        .nth(2)
+        .unwrap()
+        .trim_start_matches("# ")
+        .to_string();
+
+    let output = app
+        .command(&format!("rename {} to Robert", npc_name))
+        .unwrap();
+    assert!(
+        output.starts_with(&format!(
+            "{} was successfully renamed to Robert. Use `undo` to reverse this.\n\n_Robert has not yet been saved.",
+            npc_name,
+        )),
+        "{}",
+        output,
+    );
+
+    assert_eq!(
+        "Robert was successfully saved. Use `undo` to reverse this.",
+        app.command("save").unwrap(),
+    );
+}
+
+#[test]
+fn name_with_to_is_renamed() {
+    let mut app = sync_app();
+
+    app.command("inn named Road to Nowhere").unwrap();
+
+    assert_eq!(
+        "Road to Nowhere was successfully renamed to Road to Somewhere. Use `undo` to reverse this.",
+        app.command("rename Road to Nowhere to Road to Somewhere")
+            .unwrap(),
+    );
+}
+
+#[test]
+fn name_collisions_are_rejected() {
+    let mut app = sync_app();
+
+    app.command("npc named Bob").unwrap();
+    app.command("npc named Robert").unwrap();
+
+    let output = app.command("rename Bob to robert").unwrap_err();
+    assert!(
+        output.starts_with("That name is already in use by "),
+        "{}",
+        output,
+    );
+
+    assert_eq!(
+        "Bob was successfully renamed to BOB. Use `undo` to reverse this.",
+        app.command("rename Bob to BOB").unwrap(),
+    );
+
+    assert_eq!(
+        "There is no entity named \"Alice\".",
+        app.command("rename Alice to Alicia").unwrap_err(),
+    );
+}
@@ -0,0 +1,106 @@
+use crate::common::sync_app;
+
+#[test]
+fn import_csv() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "Places: 2 created \\\nCharacters: 2 created, 1 failed\n\nSome entries couldn't be imported:\n\n* Row 5: unknown gender \"robot\".",
+        app.bulk_import_csv(
+            "Name,Type,Species,Gender,Age,Location,Tags\n\
+            Waterdeep,city,,,,,\n\
+            The Yawning Portal,inn,,,,Waterdeep,\n\
+            Durnan,npc,human,man,55,The Yawning Portal,\"innkeeper, retired\"\n\
+            Bob,npc,,robot,,,\n\
+            Laeral,,elf,woman,old,Waterdeep,\n",
+        )
+        .unwrap(),
+    );
+
+    let output = app.command("Durnan").unwrap();
+    assert!(output.contains("# Durnan"), "{}", output);
+    assert!(output.contains("human"), "{}", output);
+    assert!(output.contains("55"), "{}", output);
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+
+    let output = app.command("The Yawning Portal").unwrap();
+    assert!(output.contains("`Waterdeep`"), "{}", output);
+
+    let output = app.command("undo").unwrap();
+    assert!(output.contains("Successfully undid"), "{}", output);
+    assert_eq!(
+        "Unknown command: \"Durnan\"",
+        app.command("Durnan").unwrap_err(),
+    );
+}
+
+#[test]
+fn import_csv_into_existing_place() {
+    let mut app = sync_app();
+    app.bulk_import_csv("name,type\nWaterdeep,city\n").unwrap();
+
+    assert_eq!(
+        "Places: 1 failed \\\nCharacters: 1 created\n\nSome entries couldn't be imported:\n\n* Row 2: `Waterdeep` is already in your journal.",
+        app.bulk_import_csv(
+            "Name,Type,Location\n\
+            Waterdeep,city,\n\
+            Volo,npc,Waterdeep\n",
+        )
+        .unwrap(),
+    );
+
+    let output = app.command("Volo").unwrap();
+    assert!(output.contains("`Waterdeep`"), "{}", output);
+}
+
+#[test]
+fn import_csv_missing_location() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "Places: 2 failed \\\nCharacters: 1 failed\n\nSome entries couldn't be imported:\n\n* Row 2: there is no place named \"Skullport\".\n* Row 3: there is no place named \"The Rat Hole\".\n* Row 4: there is no place named \"Undermountain\".",
+        app.bulk_import_csv(
+            "Name,Type,Location\n\
+            The Rat Hole,inn,Skullport\n\
+            Xanathar,npc,The Rat Hole\n\
+            Skullport,city,Undermountain\n",
+        )
+        .unwrap(),
+    );
+
+    assert!(app.command("Xanathar").is_err());
+}
+
+#[test]
+fn import_csv_preview() {
+    let mut app = sync_app();
+    app.command("import --preview").unwrap();
+
+    assert_eq!(
+        "`import --preview` only works with journal backups.",
+        app.bulk_import_csv("name\nDurnan\n").unwrap_err(),
+    );
+
+    // The preview option only lasts for one import.
+    assert_eq!(
+        "Characters: 1 created",
+        app.bulk_import_csv("name\nDurnan\n").unwrap(),
+    );
+}
+
+#[test]
+fn import_csv_strategy() {
+    let mut app = sync_app();
+    app.command("import --keep-local").unwrap();
+
+    assert_eq!(
+        "`import --keep-local` only works with journal backups. Spreadsheet rows naming an entry that's already in your journal are never imported.",
+        app.bulk_import_csv("name\nDurnan\n").unwrap_err(),
+    );
+
+    // Like the preview option, the strategy only lasts for one import.
+    assert_eq!(
+        "Characters: 1 created",
+        app.bulk_import_csv("name\nDurnan\n").unwrap(),
+    );
+}
@@ -0,0 +1,142 @@
+use crate::common::{sync_app, sync_app_with_events, take_event, waterdeep};
+use initiative_core::{Event, ExportData};
+
+#[test]
+fn export_markdown_and_html() {
+    let mut app = sync_app_with_events();
+    app.bulk_import(waterdeep()).unwrap();
+
+    assert_eq!(
+        "The journal is exporting as a campaign document. Your download should begin shortly.",
+        app.command("export markdown").unwrap(),
+    );
+
+    let markdown = match take_event() {
+        Some(Event::Export(ExportData::Markdown(markdown))) => markdown,
+        event => panic!("{:?}", event),
+    };
+
+    assert_eq!(
+        "# Journal
+
+*As of day 5 at 8:00:00 am.*
+
+<div class=\"thing-box place\" id=\"sword-coast\">
+
+## Sword Coast
+*region*
+
+</div>
+
+<div class=\"thing-box place\" id=\"neverwinter\">
+
+### Neverwinter
+*city*
+
+**Location:** 👑 [Sword Coast](#sword-coast) (region)
+
+</div>
+
+<div class=\"thing-box place\" id=\"waterdeep\">
+
+### Waterdeep
+*city*
+
+**Location:** 👑 [Sword Coast](#sword-coast) (region)
+
+</div>
+
+<div class=\"thing-box place\" id=\"the-yawning-portal\">
+
+#### The Yawning Portal
+*inn*
+
+**Location:** 🏙 [Waterdeep](#waterdeep), 👑 [Sword Coast](#sword-coast)
+
+##### Notes
+
+* **day 1 at 8:00:00 am:** The well leads to Undermountain.
+
+</div>
+
+<div class=\"thing-box npc\" id=\"durnan\">
+
+##### Durnan
+*elderly human, he/him*
+
+**Species:** human\\
+**Gender:** masculine\\
+**Age:** 72 years\\
+**Location:** 🏨 [The Yawning Portal](#the-yawning-portal), 🏙 [Waterdeep](#waterdeep)\\
+**Tags:** #villain
+
+</div>
+
+<div class=\"thing-box npc\" id=\"laeral\">
+
+#### Laeral
+*adult elf, she/her*
+
+**Species:** elf\\
+**Gender:** feminine\\
+**Age:** 90 years\\
+**Location:** 🏙 [Waterdeep](#waterdeep), 👑 [Sword Coast](#sword-coast)
+
+</div>
+
+<div class=\"thing-box place\" id=\"ten-ghosts\">
+
+## Ten Ghosts
+*inn*
+
+</div>
+
+<div class=\"thing-box npc\" id=\"tamsil\">
+
+## Tamsil
+*young adult human, she/her*
+
+**Species:** human\\
+**Gender:** feminine\\
+**Age:** 25 years
+
+</div>
+
+<div class=\"thing-box npc\" id=\"volo\">
+
+## Volo
+*human*
+
+**Species:** human
+
+</div>",
+        markdown,
+    );
+
+    app.command("export html").unwrap();
+
+    let html = match take_event() {
+        Some(Event::Export(ExportData::Html(html))) => html,
+        event => panic!("{:?}", event),
+    };
+
+    assert!(html.starts_with("<!DOCTYPE html>\n"), "{}", html);
+    assert!(
+        html.contains("<div class=\"thing-box place\" id=\"waterdeep\">\n<h3>Waterdeep</h3>"),
+        "{}",
+        html,
+    );
+    assert!(
+        html.contains("<a href=\"#the-yawning-portal\">The Yawning Portal</a>"),
+        "{}",
+        html,
+    );
+}
+
+#[test]
+fn export_markdown_empty() {
+    assert_eq!(
+        "The journal is empty, so there's nothing to export yet.",
+        sync_app().command("export markdown").unwrap(),
+    );
+}
@@ -1,11 +1,11 @@
-use crate::common::{sync_app, sync_app_with_dispatcher, SyncApp};
-use initiative_core::Event;
-
-static mut LAST_EVENT: Option<Event> = None;
-
-fn event_dispatcher(event: Event) {
-    unsafe {
-        LAST_EVENT = Some(event);
+use crate::common::{sync_app, sync_app_with_events, take_event, waterdeep, SyncApp};
+use initiative_core::{BackupData, Event, ExportData};
+
+/// The backup from the last `export` run by an app from `sync_app_with_events`.
+fn exported_backup() -> BackupData {
+    match take_event() {
+        Some(Event::Export(ExportData::Backup(data))) => data,
+        event => panic!("No export event: {:?}", event),
     }
 }
 
@@ -35,27 +35,20 @@ fn inspect_journal(app: &mut SyncApp) -> String {
 
 #[test]
 fn export() {
-    let mut app = sync_app_with_dispatcher(&event_dispatcher);
+    let mut app = sync_app_with_events();
     app.command("inn named Foo").unwrap();
     app.command("npc named Blah").unwrap();
     app.command("+1d").unwrap();
     app.command("export").unwrap();
 
-    let data = unsafe {
-        if let Some(Event::Export(data)) = &LAST_EVENT {
-            Some(data)
-        } else {
-            None
-        }
-    }
-    .unwrap();
+    let data = exported_backup();
 
     assert_eq!(2, data.things.len());
 
     let data_json = serde_json::to_string(&data).unwrap();
 
     assert!(
-        data_json.starts_with(r#"{"_":"This document is exported from initiative.sh. Please note that this format is currently undocumented and no guarantees of forward compatibility are provided, although a reasonable effort will be made to ensure that older backups can be safely imported.","things":[{"#),
+        data_json.starts_with(r#"{"_":"This document is exported from initiative.sh. Its format is described by data/backup.schema.json in the initiative.sh source code, and backups made by older versions of initiative.sh are upgraded when imported.","version":3,"things":[{"#),
         "{}",
         data_json,
     );
@@ -72,7 +65,7 @@ fn export() {
 
 #[test]
 fn import_event() {
-    let mut app = sync_app_with_dispatcher(&event_dispatcher);
+    let mut app = sync_app_with_events();
     app.init();
 
     assert_eq!(
@@ -80,34 +73,23 @@ fn import_event() {
         app.command("import").unwrap(),
     );
 
-    let event = unsafe { &LAST_EVENT };
-    assert!(matches!(event, Some(Event::Import)));
+    assert!(matches!(take_event(), Some(Event::Import)));
 }
 
 #[test]
 fn export_and_import() {
     let (backup_data, journal_before) = {
-        let mut app = sync_app_with_dispatcher(&event_dispatcher);
+        let mut app = sync_app_with_events();
         app.command("inn named Foo").unwrap();
         app.command("npc named Blah").unwrap();
         app.command("+1d").unwrap();
         app.command("export").unwrap();
 
-        (
-            unsafe {
-                if let Some(Event::Export(data)) = LAST_EVENT.take() {
-                    Some(data)
-                } else {
-                    None
-                }
-            }
-            .unwrap(),
-            inspect_journal(&mut app),
-        )
+        (exported_backup(), inspect_journal(&mut app))
     };
 
     let journal_after = {
-        let mut app = sync_app_with_dispatcher(&event_dispatcher);
+        let mut app = sync_app_with_events();
         assert_eq!(
             "Places: 1 created \\\nCharacters: 1 created \\\nKey/values: 1 created",
             app.bulk_import(backup_data).unwrap(),
@@ -194,3 +176,442 @@ fn bulk_import_v2() {
         app.command("time").unwrap(),
     );
 }
+
+/// This is a backwards compatibility test. Do not update the source file.
+#[test]
+fn bulk_import_v3() {
+    let mut app = sync_app();
+    let backup_data = serde_json::from_str(include_str!("v3.json")).unwrap();
+
+    assert_eq!(
+        "Places: 5 created \\\nCharacters: 5 created \\\nKey/values: 2 created",
+        app.bulk_import(backup_data).unwrap(),
+    );
+
+    assert_eq!(
+        "# Journal
+
+## NPCs
+👨 `Faman Halin` (middle-aged human, he/him)\\
+👧 `Halynn Mardeka` (adolescent human, she/her)\\
+👴 `Losno Khayrysi` (elderly halfling, he/him)\\
+👩 `Myrcia Haskyr` (middle-aged human, she/her)\\
+👶 `Pino Nesgarth` (halfling infant, he/him)
+
+## Places
+🏨 `Book and Soldier` (inn)\\
+🏨 `Five Millers` (inn)\\
+🏨 `Raven and Fisherman` (inn)\\
+🏨 `Ten Ghosts` (inn)\\
+🏨 `The Moody Conjurer` (inn)
+
+*To export the contents of your journal, use `export`.*",
+        app.command("journal").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently 3 Hammer 1491 DR at 8:00:00 am. It is winter.",
+        app.command("now").unwrap(),
+    );
+
+    let output = app.command("Faman Halin").unwrap();
+    assert!(output.contains("owes the party 50gp"), "{}", output);
+    assert!(output.contains("villain"), "{}", output);
+
+    assert_eq!(
+        "# Journal: #waterdeep
+
+## NPCs
+👨 `Faman Halin` (middle-aged human, he/him)
+
+## Places
+🏨 `Five Millers` (inn)
+
+*To export the contents of your journal, use `export`.*",
+        app.command("journal #waterdeep").unwrap(),
+    );
+}
+
+#[test]
+fn bulk_import_v1_moves_parent_uuid_to_location_uuid() {
+    let mut app = sync_app();
+    let backup_data = serde_json::from_str(
+        r#"{"things":[
+            {"type":"Place","uuid":"00000000-0000-0000-0000-000000000001","parent_uuid":null,"subtype":"city","name":"Waterdeep","description":null},
+            {"type":"Place","uuid":"00000000-0000-0000-0000-000000000002","parent_uuid":"00000000-0000-0000-0000-000000000001","subtype":"inn","name":"Yawning Portal","description":null}
+        ],"keyValue":{"time":null}}"#,
+    )
+    .unwrap();
+
+    assert_eq!("Places: 2 created", app.bulk_import(backup_data).unwrap());
+
+    let output = app.command("load Yawning Portal").unwrap();
+    assert!(output.contains("`Waterdeep`"), "{}", output);
+}
+
+#[test]
+fn bulk_import_newer_version() {
+    let result = serde_json::from_str::<initiative_core::BackupData>(
+        r#"{"version":999,"things":[],"keyValue":{"time":null}}"#,
+    );
+
+    assert!(
+        result
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .starts_with("this backup is from a newer version of initiative.sh"),
+        "{:?}",
+        result,
+    );
+}
+
+#[test]
+fn backups_match_schema() {
+    let schema = jsonschema::JSONSchema::compile(
+        &serde_json::from_str(include_str!("../../../../../data/backup.schema.json")).unwrap(),
+    )
+    .unwrap();
+
+    let assert_valid = |value: serde_json::Value| {
+        if let Err(errors) = schema.validate(&value) {
+            panic!(
+                "{}\n\n{}",
+                errors.map(|e| e.to_string()).collect::<Vec<_>>().join("\n"),
+                value,
+            );
+        }
+    };
+
+    // The current version is valid as-is, and older versions once they've been upgraded.
+    assert_valid(serde_json::from_str(include_str!("v3.json")).unwrap());
+    for json in [include_str!("v1.json"), include_str!("v2.json")] {
+        let backup_data: initiative_core::BackupData = serde_json::from_str(json).unwrap();
+        assert_valid(serde_json::to_value(backup_data).unwrap());
+    }
+
+    let mut app = sync_app_with_events();
+    app.command("calendar gregorian").unwrap();
+    app.command("inn named Foo").unwrap();
+    app.command("npc named Blah").unwrap();
+    app.command("Blah is tagged villain").unwrap();
+    app.command("note Foo: the ale is watered down").unwrap();
+    app.command("export").unwrap();
+
+    assert_valid(serde_json::to_value(exported_backup()).unwrap());
+}
+
+#[test]
+fn export_and_import_with_calendar() {
+    let backup_data = {
+        let mut app = sync_app_with_events();
+        app.command("calendar harptos").unwrap();
+        app.command("+2d").unwrap();
+        app.command("export").unwrap();
+        exported_backup()
+    };
+
+    let data_json = serde_json::to_string(&backup_data).unwrap();
+    assert!(
+        data_json.contains(
+            r#""keyValue":{"time":"3 Hammer 1491 DR at 8:00:00 am","calendar":{"name":"Harptos","#
+        ),
+        "{}",
+        data_json,
+    );
+
+    let mut app = sync_app();
+    assert_eq!(
+        "Key/values: 2 created",
+        app.bulk_import(serde_json::from_str(&data_json).unwrap())
+            .unwrap(),
+    );
+    assert_eq!(
+        "It is currently 3 Hammer 1491 DR at 8:00:00 am. It is winter.",
+        app.command("now").unwrap(),
+    );
+}
+
+const CONFLICTING_BACKUP: &str = r#"{"version":3,"things":[
+    {"type":"Place","uuid":"00000000-0000-0000-0000-000000000001","subtype":"inn","name":"Foo","description":"The ale is watered down."},
+    {"type":"Npc","uuid":"00000000-0000-0000-0000-000000000002","name":"Blah","species":"elf"}
+],"keyValue":{"time":"5:08:00:00"}}"#;
+
+fn conflicting_app(options: &str) -> SyncApp {
+    let mut app = sync_app();
+    app.command("inn named Foo").unwrap();
+    app.command("human named Blah").unwrap();
+    app.command(options).unwrap();
+    app
+}
+
+#[test]
+fn import_preview() {
+    let mut app = conflicting_app("import --preview");
+    let journal_before = inspect_journal(&mut app);
+
+    let output = app
+        .bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+        .unwrap();
+
+    assert!(
+        output.starts_with(
+            "# Import preview
+
+Places: 1 updated \\
+Characters: 1 updated \\
+Key/values: 1 created
+
+* Update `Foo`: description: none → The ale is watered down.
+* Update `Blah`: "
+        ),
+        "{}",
+        output,
+    );
+    assert!(output.contains("species: human → elf"), "{}", output);
+    assert!(
+        output
+            .contains("\n* Set the time to day 5 at 8:00:00 am\n\n_Nothing has been imported yet."),
+        "{}",
+        output,
+    );
+
+    assert_eq!(journal_before, inspect_journal(&mut app));
+
+    // The options only apply to the next import.
+    assert_eq!(
+        "Places: 1 updated \\\nCharacters: 1 updated \\\nKey/values: 1 created",
+        app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+            .unwrap(),
+    );
+}
+
+#[test]
+fn import_take_incoming() {
+    let mut app = conflicting_app("import --take-incoming");
+
+    assert_eq!(
+        "Places: 1 updated \\\nCharacters: 1 updated \\\nKey/values: 1 created",
+        app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+            .unwrap(),
+    );
+
+    let output = app.command("Blah").unwrap();
+    assert!(output.contains("elf"), "{}", output);
+    assert!(!output.contains("human"), "{}", output);
+}
+
+#[test]
+fn import_keep_local() {
+    let mut app = conflicting_app("import --keep-local");
+    let blah_before = app.command("Blah").unwrap();
+
+    assert_eq!(
+        "Places: 1 skipped \\\nCharacters: 1 skipped \\\nKey/values: 1 created",
+        app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+            .unwrap(),
+    );
+
+    assert_eq!(blah_before, app.command("Blah").unwrap());
+    assert!(!app
+        .command("Foo")
+        .unwrap()
+        .contains("The ale is watered down."));
+}
+
+#[test]
+fn import_rename_incoming() {
+    let mut app = conflicting_app("import --rename-incoming");
+    let blah_before = app.command("Blah").unwrap();
+
+    assert_eq!(
+        "Places: 1 created \\\nCharacters: 1 created \\\nKey/values: 1 created",
+        app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+            .unwrap(),
+    );
+
+    assert_eq!(blah_before, app.command("Blah").unwrap());
+
+    let output = app.command("Blah (2)").unwrap();
+    assert!(output.contains("elf"), "{}", output);
+
+    let output = app.command("Foo (2)").unwrap();
+    assert!(output.contains("The ale is watered down."), "{}", output);
+}
+
+#[test]
+fn import_rename_incoming_keeps_contents_together() {
+    let mut app = sync_app_with_events();
+    app.bulk_import(waterdeep()).unwrap();
+    app.command("export").unwrap();
+    let backup_data = exported_backup();
+
+    app.command("import --rename-incoming").unwrap();
+    assert_eq!(
+        "Places: 5 created \\\nCharacters: 4 created \\\nKey/values: 1 skipped",
+        app.bulk_import(backup_data).unwrap(),
+    );
+
+    let output = app.command("Durnan (2)").unwrap();
+    assert!(
+        output.contains("`The Yawning Portal (2)`, 🏙 `Waterdeep (2)`"),
+        "{}",
+        output,
+    );
+
+    let output = app.command("Waterdeep (2)").unwrap();
+    assert!(output.contains("`Sword Coast (2)`"), "{}", output);
+
+    let output = app.command("Durnan").unwrap();
+    assert!(
+        output.contains("`The Yawning Portal`, 🏙 `Waterdeep`"),
+        "{}",
+        output,
+    );
+}
+
+#[test]
+fn import_merge() {
+    let mut app = conflicting_app("import --merge");
+    let blah_before = app.command("Blah").unwrap();
+
+    assert_eq!(
+        "Places: 1 updated \\\nCharacters: 1 updated \\\nKey/values: 1 created",
+        app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+            .unwrap(),
+    );
+
+    // Blah's species was chosen locally, so it stays put, but Foo's description was blank.
+    assert_eq!(blah_before, app.command("Blah").unwrap());
+
+    let output = app.command("Foo").unwrap();
+    assert!(output.contains("The ale is watered down."), "{}", output);
+}
+
+#[test]
+fn import_is_undone_in_one_step() {
+    let mut app = conflicting_app("import");
+    let journal_before = inspect_journal(&mut app);
+
+    app.bulk_import(serde_json::from_str(CONFLICTING_BACKUP).unwrap())
+        .unwrap();
+    assert_ne!(journal_before, inspect_journal(&mut app));
+
+    let output = app.command("undo").unwrap();
+    assert!(
+        output.ends_with("_Successfully undid editing Foo, editing Blah, and changing the time. Use `redo` to reverse this._"),
+        "{}",
+        output,
+    );
+    assert_eq!(journal_before, inspect_journal(&mut app));
+}
+
+#[test]
+fn export_selection() {
+    let mut app = sync_app_with_events();
+    app.bulk_import(waterdeep()).unwrap();
+
+    let mut export_names = |command: &str| -> Vec<String> {
+        let output = app.command(command).unwrap();
+
+        let data = exported_backup();
+
+        assert_eq!(
+            format!(
+                "{} from your journal {} exporting. Your download should begin shortly.",
+                if data.things.len() == 1 {
+                    "1 entry".to_string()
+                } else {
+                    format!("{} entries", data.things.len())
+                },
+                if data.things.len() == 1 { "is" } else { "are" },
+            ),
+            output,
+        );
+        assert_eq!(None, data.key_value.time);
+
+        data.things
+            .iter()
+            .map(|thing| thing.name().to_string())
+            .collect()
+    };
+
+    {
+        // The contents of a place aren't in any particular order.
+        let mut names = export_names("export Waterdeep and contents");
+        names[2..].sort();
+        assert_eq!(
+            vec![
+                "Sword Coast",
+                "Waterdeep",
+                "Durnan",
+                "Laeral",
+                "The Yawning Portal",
+            ],
+            names,
+        );
+    }
+
+    assert_eq!(
+        vec!["Sword Coast", "Waterdeep", "Laeral"],
+        export_names("export laeral"),
+    );
+
+    assert_eq!(
+        vec!["Sword Coast", "Waterdeep", "The Yawning Portal", "Durnan"],
+        export_names("export #Villain"),
+    );
+
+    assert_eq!(
+        r#"There is nothing named "Elminster" in your journal."#,
+        app.command("export Elminster").unwrap_err(),
+    );
+
+    assert_eq!(
+        r#"There is nothing named "Elminster" in your journal."#,
+        app.command("export Elminster and contents").unwrap_err(),
+    );
+
+    assert_eq!(
+        "Nothing in your journal is tagged #hero.",
+        app.command("export #hero").unwrap_err(),
+    );
+}
+
+#[test]
+fn export_selection_and_import() {
+    let backup_data = {
+        let mut app = sync_app_with_events();
+        app.bulk_import(waterdeep()).unwrap();
+        app.command("export Waterdeep and contents").unwrap();
+        exported_backup()
+    };
+
+    let mut app = sync_app();
+    app.command("+1d").unwrap();
+
+    assert_eq!(
+        "Places: 3 created \\\nCharacters: 2 created",
+        app.bulk_import(
+            serde_json::from_str(&serde_json::to_string(&backup_data).unwrap()).unwrap()
+        )
+        .unwrap(),
+    );
+
+    let output = app.command("load Durnan").unwrap();
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+
+    let output = app.command("load The Yawning Portal").unwrap();
+    assert!(output.contains("`Waterdeep`"), "{}", output);
+
+    let output = app.command("load Waterdeep").unwrap();
+    assert!(output.contains("`Sword Coast`"), "{}", output);
+
+    assert!(app.command("load Neverwinter").is_err());
+
+    // The time isn't part of a partial export.
+    assert_eq!(
+        "It is currently day 2 at 8:00:00 am.",
+        app.command("time").unwrap(),
+    );
+}
@@ -1,4 +1,4 @@
-use crate::common::{sync_app, sync_app_with_invalid_data_store};
+use crate::common::{sync_app, sync_app_with_invalid_data_store, waterdeep};
 
 #[test]
 fn it_shows_a_message_when_empty() {
@@ -112,3 +112,39 @@ fn it_shows_alphabetized_results() {
 
     assert!(output_iter.next().is_none());
 }
+
+#[test]
+fn it_searches_by_description_location_and_age() {
+    let mut app = sync_app();
+    app.bulk_import(waterdeep()).unwrap();
+
+    let output = app.command("journal elves").unwrap();
+    assert!(output.starts_with("# Journal: elves\n\n"), "{}", output);
+    assert!(output.contains("`Laeral`"), "{}", output);
+    assert!(!output.contains("`Durnan`"), "{}", output);
+    assert!(!output.contains("`Tamsil`"), "{}", output);
+
+    let output = app.command("journal inns in Waterdeep").unwrap();
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+    assert!(!output.contains("`Ten Ghosts`"), "{}", output);
+
+    let output = app.command("journal women over 60").unwrap();
+    assert!(output.contains("`Laeral`"), "{}", output);
+    assert!(!output.contains("`Tamsil`"), "{}", output);
+    assert!(!output.contains("`Durnan`"), "{}", output);
+
+    let output = app.command("journal inns").unwrap();
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+    assert!(output.contains("`Ten Ghosts`"), "{}", output);
+    assert!(!output.contains("`Waterdeep`"), "{}", output);
+
+    assert_eq!(
+        "# Journal: dwarves\n\n*Nothing in your journal matches \"dwarves\".*",
+        app.command("journal dwarves").unwrap(),
+    );
+
+    assert_eq!(
+        "There is no place named \"Baldur's Gate\" in your journal.",
+        app.command("journal inns in Baldur's Gate").unwrap_err(),
+    );
+}
@@ -1,7 +1,10 @@
 mod change;
+mod csv_import;
+mod export_document;
 mod export_import;
 mod journal;
 mod load;
+mod sync;
 mod undo_redo;
 
 use crate::common::SyncApp;
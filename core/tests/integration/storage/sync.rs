@@ -0,0 +1,205 @@
+use crate::common::{sync_app, sync_app_with_data_store, waterdeep, SyncApp};
+use initiative_core::{MemoryDataStore, MemorySyncServer, SyncChange, SyncTransport};
+use tokio_test::block_on;
+
+/// Two devices syncing through the same server, each of which has synced the contents of
+/// the `waterdeep` fixture.
+fn devices() -> (SyncApp, SyncApp, MemorySyncServer) {
+    let server = MemorySyncServer::default();
+    let mut laptop = sync_app();
+    let mut phone = sync_app();
+
+    laptop.bulk_import(waterdeep()).unwrap();
+
+    assert_eq!(
+        "Your journal is in sync. Sent 1 change and received 0 changes.",
+        laptop.sync(&mut server.clone()).unwrap(),
+    );
+    assert_eq!(
+        "Your journal is in sync. Sent 0 changes and received 1 change.",
+        phone.sync(&mut server.clone()).unwrap(),
+    );
+
+    (laptop, phone, server)
+}
+
+#[test]
+fn sync_copies_the_journal() {
+    let (_, mut phone, _) = devices();
+
+    let output = phone.command("Durnan").unwrap();
+    assert!(output.contains("# Durnan"), "{}", output);
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+    assert!(phone.command("time").unwrap().contains("day 5"));
+}
+
+#[test]
+fn sync_merges_edits_to_different_fields() {
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("Durnan is an elf").unwrap();
+    phone.command("Durnan is tagged innkeeper").unwrap();
+
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+
+    for app in [&mut laptop, &mut phone] {
+        let output = app.command("Durnan").unwrap();
+        assert!(output.contains("elf"), "{}", output);
+        assert!(output.contains("#innkeeper"), "{}", output);
+    }
+}
+
+#[test]
+fn sync_clears_fields() {
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("Laeral is tagged ally").unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+    assert!(phone.command("Laeral").unwrap().contains("#ally"));
+
+    laptop.command("undo").unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+
+    let output = phone.command("Laeral").unwrap();
+    assert!(!output.contains("Tags:"), "{}", output);
+}
+
+#[test]
+fn sync_keeps_the_latest_edit_to_a_field() {
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("Durnan is an elf").unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.command("Durnan is a dwarf").unwrap();
+    phone.command("Durnan is a halfling").unwrap();
+
+    phone.sync(&mut server.clone()).unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+
+    for app in [&mut laptop, &mut phone] {
+        let output = app.command("Durnan").unwrap();
+        assert!(output.contains("halfling"), "{}", output);
+    }
+}
+
+#[test]
+fn sync_deletes_win_over_concurrent_edits() {
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("delete Durnan").unwrap();
+    phone.command("Durnan is an elf").unwrap();
+
+    phone.sync(&mut server.clone()).unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+
+    for app in [&mut laptop, &mut phone] {
+        assert_eq!(
+            "Unknown command: \"Durnan\"",
+            app.command("Durnan").unwrap_err(),
+        );
+    }
+
+    // Undoing the deletion brings it back everywhere.
+    laptop.command("undo").unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+
+    assert!(phone.command("Durnan").unwrap().contains("# Durnan"));
+}
+
+#[test]
+fn sync_renames_entries_with_the_same_name() {
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("elf named Elminster").unwrap();
+    phone.command("human named Elminster").unwrap();
+
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+    laptop.sync(&mut server.clone()).unwrap();
+
+    // Each device keeps its own entry's name and renames the other.
+    for (app, local, incoming) in [(&mut laptop, "elf", "human"), (&mut phone, "human", "elf")] {
+        let output = app.command("Elminster").unwrap();
+        assert!(output.contains(local), "{}", output);
+
+        let output = app.command("Elminster (2)").unwrap();
+        assert!(output.contains(incoming), "{}", output);
+    }
+}
+
+#[test]
+fn sync_retries_after_failing() {
+    struct Offline;
+
+    #[async_trait::async_trait(?Send)]
+    impl SyncTransport for Offline {
+        async fn push(&mut self, _changes: &[SyncChange]) -> Result<(), ()> {
+            Err(())
+        }
+
+        async fn pull(&mut self, _cursor: u64) -> Result<(Vec<SyncChange>, u64), ()> {
+            Err(())
+        }
+    }
+
+    let (mut laptop, mut phone, server) = devices();
+
+    laptop.command("Durnan is an elf").unwrap();
+    assert_eq!(
+        "Couldn't reach the sync server. Your changes will be sent next time.",
+        laptop.sync(&mut Offline).unwrap_err(),
+    );
+
+    laptop.sync(&mut server.clone()).unwrap();
+    phone.sync(&mut server.clone()).unwrap();
+    assert!(phone.command("Durnan").unwrap().contains("elf"));
+}
+
+#[test]
+fn sync_only_sends_each_change_once() {
+    let (mut laptop, mut phone, mut server) = devices();
+
+    phone.command("Durnan is an elf").unwrap();
+    assert_eq!(
+        "Your journal is in sync. Sent 1 change and received 0 changes.",
+        phone.sync(&mut server).unwrap(),
+    );
+    assert_eq!(
+        "Your journal is in sync. Sent 0 changes and received 1 change.",
+        laptop.sync(&mut server).unwrap(),
+    );
+    assert_eq!(
+        "Your journal is in sync. Sent 0 changes and received 0 changes.",
+        phone.sync(&mut server).unwrap(),
+    );
+
+    let (changes, _) = block_on(server.pull(0)).unwrap();
+    assert_eq!(2, changes.len());
+}
+
+#[test]
+fn sync_resumes_after_reload() {
+    let (_, mut phone, mut server) = devices();
+    let data_store = MemoryDataStore::default();
+
+    {
+        let mut laptop = sync_app_with_data_store(data_store.clone());
+        laptop.sync(&mut server).unwrap();
+        laptop.command("Durnan is an elf").unwrap();
+    }
+
+    let mut laptop = sync_app_with_data_store(data_store);
+    assert_eq!(
+        "Your journal is in sync. Sent 1 change and received 0 changes.",
+        laptop.sync(&mut server).unwrap(),
+    );
+
+    phone.sync(&mut server).unwrap();
+    assert!(phone.command("Durnan").unwrap().contains("elf"));
+}
@@ -141,3 +141,54 @@ fn set_key_value() {
     assert_eq!(a, c);
     assert_eq!(b, d);
 }
+
+#[test]
+fn multiple() {
+    let mut app = sync_app();
+    app.command("+1h").unwrap();
+    app.command("+1h").unwrap();
+    app.command("+1h").unwrap();
+
+    assert_eq!(
+        "# Undo history\n\n1. changing the time\n2. changing the time\n3. changing the time\n\n*Use `undo [number]` or `redo [number]` to reverse several changes at once.*",
+        app.command("undo history").unwrap(),
+    );
+
+    assert_eq!(
+        "Successfully undid 2 changes: changing the time, changing the time. Use `redo 2` to reverse this.",
+        app.command("undo 2").unwrap(),
+    );
+    assert!(
+        app.command("now").unwrap().contains("9:00:00 am"),
+        "{}",
+        app.command("now").unwrap(),
+    );
+
+    assert_eq!(
+        "# Undo history\n\n1. changing the time\n\n## Redo\n\n1. changing the time\n2. changing the time\n\n*Use `undo [number]` or `redo [number]` to reverse several changes at once.*",
+        app.command("undo history").unwrap(),
+    );
+
+    assert_eq!(
+        "Successfully redid changing the time. Use `undo` to reverse this.",
+        app.command("redo").unwrap(),
+    );
+    assert_eq!(
+        "Successfully redid changing the time. Use `undo` to reverse this.",
+        app.command("redo 5").unwrap(),
+    );
+    assert!(
+        app.command("now").unwrap().contains("11:00:00 am"),
+        "{}",
+        app.command("now").unwrap(),
+    );
+    assert_eq!("Nothing to redo.", app.command("redo").unwrap_err());
+}
+
+#[test]
+fn history_empty() {
+    assert_eq!(
+        "# Undo history\n\n*Nothing to undo.*",
+        sync_app().command("undo history").unwrap(),
+    );
+}
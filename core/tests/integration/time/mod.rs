@@ -70,3 +70,276 @@ fn time_is_persisted() {
         );
     }
 }
+
+#[test]
+fn effects_expire_with_time() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "There are no active effects. Add one with eg. `Bless on Aria 1 minute` or `torch 1h`.",
+        app.command("effects").unwrap(),
+    );
+
+    assert_eq!(
+        "**Bless** on Aria _(concentration)_ will last for 1 minute, until day 1 at 8:01:00 am. Use `undo` to reverse.",
+        app.command("Bless on Aria").unwrap(),
+    );
+
+    assert_eq!(
+        "**torch** will last for 1 hour, until day 1 at 9:00:00 am. Use `undo` to reverse.",
+        app.command("torch 1h").unwrap(),
+    );
+
+    assert_eq!(
+        "# Active effects\n\n* **Bless** on Aria _(concentration)_: 1 minute remaining\n* **torch**: 1 hour remaining",
+        app.command("effects").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now day 1 at 8:05:00 am. Use `undo` to reverse.\n\nThe following effects have ended:\n\n* **Bless** on Aria _(concentration)_",
+        app.command("+5m").unwrap(),
+    );
+
+    assert_eq!(
+        "# Active effects\n\n* **torch**: 55 minutes remaining",
+        app.command("effects").unwrap(),
+    );
+
+    app.command("undo").unwrap();
+
+    assert_eq!(
+        "# Active effects\n\n* **Bless** on Aria _(concentration)_: 1 minute remaining\n* **torch**: 1 hour remaining",
+        app.command("effects").unwrap(),
+    );
+
+    assert_eq!(
+        "torch has ended. Use `undo` to reverse.",
+        app.command("end torch").unwrap(),
+    );
+
+    assert_eq!(
+        "# Active effects\n\n* **Bless** on Aria _(concentration)_: 1 minute remaining",
+        app.command("effects").unwrap(),
+    );
+}
+
+#[test]
+fn calendar_presets() {
+    let mut app = sync_app();
+
+    assert!(app
+        .command("calendar")
+        .unwrap()
+        .starts_with("There is no calendar set up"));
+
+    assert_eq!(
+        "Now using the Harptos calendar. It is currently 1 Hammer 1491 DR at 8:00:00 am. Use `undo` to reverse.",
+        app.command("calendar harptos").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now Midwinter 1491 DR at 8:00:00 am. Use `undo` to reverse.",
+        app.command("+30d").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently Midwinter 1491 DR at 8:00:00 am. It is winter.",
+        app.command("now").unwrap(),
+    );
+
+    assert!(app
+        .command("calendar")
+        .unwrap()
+        .starts_with("# Calendar: Harptos\n\n* **Hammer:** 30 days\n* **Midwinter:** 1 day\n"));
+
+    assert_eq!(
+        "Successfully undid changing the time. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+    assert_eq!(
+        "Successfully undid changing the calendar. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently day 1 at 8:00:00 am.",
+        app.command("now").unwrap(),
+    );
+}
+
+#[test]
+fn calendar_custom() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "There is no calendar set up yet. Start with a preset like `calendar harptos`, or create your own with `calendar months [name] [days], [name] [days], ...`.",
+        app.command("calendar weekdays Sun, Moon").unwrap_err(),
+    );
+
+    assert_eq!(
+        "A new calendar has been created. It is currently 1 Frost 1 at 8:00:00 am. Use `undo` to reverse.",
+        app.command("calendar months Frost 20, Thaw 10, Leapday 0, Bloom 20")
+            .unwrap(),
+    );
+
+    app.command("calendar weekdays Sun, Moon, Star").unwrap();
+    app.command("calendar epoch 300 AF").unwrap();
+    app.command("calendar seasons Winter from 1 Frost, Spring from 1 Bloom")
+        .unwrap();
+
+    assert_eq!(
+        "Every 2 years, Leapday will have an extra day. It is currently Sun, 1 Frost 300 AF at 8:00:00 am. Use `undo` to reverse.",
+        app.command("calendar leap leapday every 2").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now Sun, Leapday 300 AF at 8:00:00 am. Use `undo` to reverse.",
+        app.command("+30d").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently Moon, 1 Bloom 300 AF at 8:00:00 am. It is spring.",
+        {
+            app.command("+1d").unwrap();
+            app.command("now").unwrap()
+        },
+    );
+
+    assert_eq!(
+        "\"31 Bloom\" isn't a valid date in the Custom calendar.",
+        app.command("calendar seasons Summer from 31 Bloom")
+            .unwrap_err(),
+    );
+}
+
+#[test]
+fn time_can_be_set() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "It is now day 12 at 2:30:00 pm. Use `undo` to reverse.",
+        app.command("time set day 12 14:30").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now day 12 at 6:00:00 am. Use `undo` to reverse.",
+        app.command("time set dawn").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now day 13 at 12:00:00 am. Use `undo` to reverse.",
+        app.command("until midnight").unwrap(),
+    );
+
+    assert_eq!(
+        "That time has already passed. It is currently day 13 at 12:00:00 am.",
+        app.command("until day 2").unwrap_err(),
+    );
+
+    assert_eq!(
+        "\"teatime\" isn't a time I understand. Try eg. `time set day 3 at 8:00 am` or `until dawn`.",
+        app.command("time set teatime").unwrap_err(),
+    );
+
+    assert_eq!(
+        "Successfully undid changing the time. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently day 12 at 6:00:00 am.",
+        app.command("now").unwrap(),
+    );
+}
+
+#[test]
+fn bookmarks() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "There are no bookmarks. Create one with eg. `time mark left Waterdeep`.",
+        app.command("time since").unwrap(),
+    );
+
+    assert_eq!(
+        "Bookmarked left Waterdeep at day 1 at 8:00:00 am. Use `time since left Waterdeep` to see how long ago it was, or `undo` to reverse.",
+        app.command("time mark left Waterdeep").unwrap(),
+    );
+
+    assert_eq!(
+        "left Waterdeep is right now.",
+        app.command("time since left waterdeep").unwrap(),
+    );
+
+    app.command("+1d2h30m").unwrap();
+
+    assert_eq!(
+        "It has been 1 day, 2 hours, 30 minutes since left Waterdeep.",
+        app.command("time since left Waterdeep").unwrap(),
+    );
+
+    assert_eq!(
+        "# Bookmarks\n\n* **left Waterdeep**: day 1 at 8:00:00 am",
+        app.command("time since").unwrap(),
+    );
+
+    assert_eq!(
+        "There is no bookmark named \"the dragon\". Create one with `time mark the dragon`.",
+        app.command("time since the dragon").unwrap_err(),
+    );
+
+    app.command("undo").unwrap();
+    app.command("undo").unwrap();
+
+    assert_eq!(
+        "There are no bookmarks. Create one with eg. `time mark left Waterdeep`.",
+        app.command("time since").unwrap(),
+    );
+}
+
+#[test]
+fn scheduled_events() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "Nothing is scheduled. Add an event with eg. `in 3d the ritual completes` or `at dawn the caravan leaves`.",
+        app.command("agenda").unwrap(),
+    );
+
+    assert_eq!(
+        "Scheduled \"the ritual completes\" for day 4 at 8:00:00 am, 3 days from now. Use `agenda` to see upcoming events, or `undo` to reverse.",
+        app.command("in 3d the ritual completes").unwrap(),
+    );
+
+    assert_eq!(
+        "Scheduled \"the caravan leaves\" for day 2 at 6:00:00 am, 22 hours from now. Use `agenda` to see upcoming events, or `undo` to reverse.",
+        app.command("at dawn the caravan leaves").unwrap(),
+    );
+
+    assert_eq!(
+        "That time has already passed. It is currently day 1 at 8:00:00 am.",
+        app.command("at day 1 6:00 the sun rises").unwrap_err(),
+    );
+
+    assert_eq!(
+        "# Agenda\n\n* **day 2 at 6:00:00 am**: the caravan leaves _(in 22 hours)_\n* **day 4 at 8:00:00 am**: the ritual completes _(in 3 days)_",
+        app.command("agenda").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now day 3 at 8:00:00 am. Use `undo` to reverse.\n\nThe following events have occurred:\n\n* the caravan leaves",
+        app.command("+2d").unwrap(),
+    );
+
+    assert_eq!(
+        "It is now day 5 at 12:00:00 am. Use `undo` to reverse.\n\nThe following events have occurred:\n\n* the ritual completes",
+        app.command("until day 5").unwrap(),
+    );
+
+    app.command("undo").unwrap();
+
+    assert_eq!(
+        "# Agenda\n\n* **day 4 at 8:00:00 am**: the ritual completes _(in 1 day)_",
+        app.command("agenda").unwrap(),
+    );
+}
@@ -0,0 +1,95 @@
+use crate::common::sync_app;
+
+#[test]
+fn travel_advances_time() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "Travelling 30 miles by foot at a fast pace takes 7 hours, 30 minutes.\n\n**Fast pace:** −5 penalty to passive Wisdom (Perception) scores.\n\nIt is now day 1 at 3:30:00 pm. Use `undo` to reverse.",
+        app.command("travel 30 miles fast").unwrap(),
+    );
+
+    assert_eq!(
+        "Travelling 100 miles by foot at a normal pace takes 1 day, 9 hours, 20 minutes over 5 days.\n\n**Normal pace:** No special effect.\n\nIt is now day 5 at 4:50:00 pm. Use `undo` to reverse.",
+        app.command("travel 100 miles").unwrap(),
+    );
+
+    assert_eq!(
+        "Successfully undid changing the time. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+
+    assert_eq!(
+        "It is currently day 1 at 3:30:00 pm.",
+        app.command("now").unwrap(),
+    );
+}
+
+#[test]
+fn travel_forced_march() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "Travelling 40 miles by horse at a fast pace takes 10 hours.\n\n**Fast pace:** −5 penalty to passive Wisdom (Perception) scores.\n\n**Forced march:** Travelling for more than 8 hours in a day risks exhaustion. At the end of each hour past the 8th, each character must succeed on a Constitution saving throw or gain one level of exhaustion. The DC is 11 after the 9th hour, rising to 12 after the 10th.\n\nIt is now day 1 at 6:00:00 pm. Use `undo` to reverse.",
+        app.command("travel 40 miles fast by horse forced").unwrap(),
+    );
+
+    let output = app.command("travel 84 miles fast forced").unwrap();
+    assert!(
+        output.contains("rising to 23 after the 21st."),
+        "{}",
+        output,
+    );
+}
+
+#[test]
+fn travel_to_place() {
+    let mut app = sync_app();
+
+    app.bulk_import(
+        serde_json::from_str(
+            r#"{
+                "things": [
+                    {
+                        "type": "Place",
+                        "uuid": "00000000-0000-0000-0000-000000000001",
+                        "location_uuid": null,
+                        "subtype": "kingdom",
+                        "name": "Luskan",
+                        "description": null
+                    },
+                    {
+                        "type": "Place",
+                        "uuid": "00000000-0000-0000-0000-000000000002",
+                        "location_uuid": "00000000-0000-0000-0000-000000000001",
+                        "subtype": "desert",
+                        "name": "Sea of Glass",
+                        "description": null
+                    }
+                ],
+                "keyValue": {}
+            }"#,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    let output = app
+        .command("travel to Sea of Glass 12 miles by ship with encounters")
+        .unwrap();
+    assert!(
+        output.starts_with("Travelling 12 miles by ship takes 6 hours. You arrive at 🏜 `Sea of Glass`, in 👑 `Luskan`.\n\n**Random encounters:** "),
+        "{}",
+        output,
+    );
+    assert!(
+        output.ends_with("\n\nIt is now day 1 at 2:00:00 pm. Use `undo` to reverse."),
+        "{}",
+        output,
+    );
+
+    assert_eq!(
+        "Travelling 24 miles by foot at a normal pace takes 8 hours. With no distance given, the journey is assumed to take a day. You arrive at 🏜 `Sea of Glass`, in 👑 `Luskan`.\n\n**Normal pace:** No special effect.\n\nIt is now day 1 at 10:00:00 pm. Use `undo` to reverse.",
+        app.command("travel to Sea of Glass by foot").unwrap(),
+    );
+}
@@ -0,0 +1,61 @@
+use crate::common::sync_app;
+
+#[test]
+fn weather_persists_for_the_day() {
+    let mut app = sync_app();
+
+    let output = app.command("weather").unwrap();
+    assert!(output.starts_with("# Weather\n\n"), "{}", output);
+    assert!(output.contains("**Terrain:** plain\\\n"), "{}", output);
+    assert!(output.contains("**Season:** winter\\\n"), "{}", output);
+    assert_eq!(output, app.command("weather").unwrap());
+
+    app.command("+1d").unwrap();
+    let output = app.command("weather").unwrap();
+    assert!(output.contains("**Terrain:** plain\\\n"), "{}", output);
+    assert_eq!(output, app.command("weather").unwrap());
+}
+
+#[test]
+fn weather_follows_terrain() {
+    let mut app = sync_app();
+    let plain = app.command("weather").unwrap();
+
+    let output = app.command("weather in tundra").unwrap();
+    assert!(output.contains("**Terrain:** tundra\\\n"), "{}", output);
+    assert!(
+        !output.contains("Warm") && !output.contains("Hot"),
+        "{}",
+        output
+    );
+
+    assert_eq!(
+        "Successfully undid changing the weather. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+    assert_eq!(plain, app.command("weather").unwrap());
+
+    app.command("desert named Sea of Glass").unwrap();
+    let output = app.command("weather Sea of Glass").unwrap();
+    assert!(output.contains("**Terrain:** desert\\\n"), "{}", output);
+
+    assert_eq!(
+        "\"teacup\" isn't a terrain I know. Try eg. `weather desert`, or the name of a region in your journal.",
+        app.command("weather teacup").unwrap_err(),
+    );
+}
+
+#[test]
+fn viewing_the_weather_cannot_be_undone() {
+    let mut app = sync_app();
+
+    app.command("+1d").unwrap();
+    let output = app.command("weather").unwrap();
+
+    assert_eq!(
+        "Successfully undid changing the time. Use `redo` to reverse this.",
+        app.command("undo").unwrap(),
+    );
+    app.command("+1d").unwrap();
+    assert_eq!(output, app.command("weather").unwrap());
+}
@@ -1,6 +1,8 @@
 mod create;
 mod create_multiple;
 mod edit;
+mod note;
+mod tag;
 
 use crate::common::{get_name, sync_app};
 
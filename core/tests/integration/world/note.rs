@@ -0,0 +1,150 @@
+use crate::common::{get_name, sync_app, sync_app_with_events, take_event};
+use initiative_core::{Event, ExportData};
+
+#[test]
+fn note_is_added_and_shown() {
+    let mut app = sync_app();
+    app.command("npc named Bob").unwrap();
+
+    let output = app.command("note Bob: owes the party 50gp").unwrap();
+    assert!(
+        output.ends_with("\n\n## Notes\n\n* **day 1 at 8:00:00 am:** owes the party 50gp\n\n</div>\n\n_Added a note to Bob. Use `undo` to reverse this._"),
+        "{}",
+        output,
+    );
+
+    app.command("+2h").unwrap();
+    app.command("note bob: paid back 20gp").unwrap();
+
+    let output = app.command("Bob").unwrap();
+    assert!(
+        output.contains("## Notes\n\n* **day 1 at 8:00:00 am:** owes the party 50gp\n* **day 1 at 10:00:00 am:** paid back 20gp"),
+        "{}",
+        output,
+    );
+
+    app.command("undo").unwrap();
+    let output = app.command("Bob").unwrap();
+    assert!(output.contains("owes the party 50gp"), "{}", output);
+    assert!(!output.contains("paid back"), "{}", output);
+
+    assert_eq!(
+        "There is no character or place named \"Nobody\".",
+        app.command("note Nobody: hello").unwrap_err(),
+    );
+}
+
+#[test]
+fn note_times_use_the_calendar() {
+    let mut app = sync_app();
+    app.command("calendar harptos").unwrap();
+    app.command("npc named Bob").unwrap();
+
+    let output = app.command("note Bob: owes the party 50gp").unwrap();
+    assert!(
+        output.contains("* **1 Hammer 1491 DR at 8:00:00 am:** owes the party 50gp"),
+        "{}",
+        output,
+    );
+
+    let output = app.command("notes").unwrap();
+    assert!(
+        output.contains("* **1 Hammer 1491 DR at 8:00:00 am:** owes the party 50gp"),
+        "{}",
+        output,
+    );
+}
+
+#[test]
+fn note_saves_recent_entry_to_journal() {
+    let mut app = sync_app();
+    let name = get_name(&app.command("inn").unwrap());
+
+    let output = app
+        .command(&format!("note {}: the ale is watered down", name))
+        .unwrap();
+    assert!(
+        output.ends_with(&format!(
+            "_Added a note to {} and automatically saved it to your `journal`. Use `undo` to reverse this._",
+            name,
+        )),
+        "{}",
+        output,
+    );
+
+    let output = app.command("journal").unwrap();
+    assert!(output.contains(&name), "{}", output);
+}
+
+#[test]
+fn notes_are_searchable() {
+    let mut app = sync_app();
+
+    assert_eq!(
+        "# Notes\n\n*There are no notes in your journal yet. Use `note [name]: [text]` to add one.*",
+        app.command("notes").unwrap(),
+    );
+
+    app.command("npc named Bob").unwrap();
+    app.command("inn named The Prancing Pony").unwrap();
+    app.command("note Bob: owes the party 50gp").unwrap();
+    app.command("note Bob: afraid of spiders").unwrap();
+    app.command("note The Prancing Pony: Bob drinks here")
+        .unwrap();
+
+    let output = app.command("notes").unwrap();
+    assert!(output.starts_with("# Notes\n\n"), "{}", output);
+    assert_eq!(3, output.matches("\n* ").count(), "{}", output);
+
+    let output = app.command("notes GP").unwrap();
+    assert!(
+        output.starts_with("# Notes matching \"GP\"\n\n"),
+        "{}",
+        output
+    );
+    assert!(output.contains("owes the party 50gp"), "{}", output);
+    assert!(!output.contains("spiders"), "{}", output);
+    assert!(!output.contains("Prancing"), "{}", output);
+
+    // Searching by name finds all of Bob's notes, plus any note mentioning him.
+    let output = app.command("notes bob").unwrap();
+    assert_eq!(3, output.matches("\n* ").count(), "{}", output);
+
+    assert_eq!(
+        "# Notes matching \"dragons\"\n\n*No notes match \"dragons\".*",
+        app.command("notes dragons").unwrap(),
+    );
+}
+
+#[test]
+fn notes_are_exported() {
+    let mut app = sync_app_with_events();
+    app.command("npc named Bob").unwrap();
+    app.command("note Bob: owes the party 50gp").unwrap();
+    app.command("export").unwrap();
+
+    let data = take_event();
+    let data = if let Some(Event::Export(ExportData::Backup(data))) = data {
+        data
+    } else {
+        panic!("{:?}", data);
+    };
+
+    let json = serde_json::to_string(&data).unwrap();
+    assert!(
+        json.contains(r#""notes":[{"time":"1:08:00:00","text":"owes the party 50gp"}]"#),
+        "{}",
+        json,
+    );
+
+    let mut app = sync_app();
+    app.bulk_import(serde_json::from_str(&json).unwrap())
+        .unwrap();
+
+    let output = app.command("Bob").unwrap();
+    assert!(
+        output.contains("* **day 1 at 8:00:00 am:** owes the party 50gp"),
+        "{}",
+        output,
+    );
+}
@@ -0,0 +1,137 @@
+use crate::common::{sync_app, sync_app_with_events, take_event};
+use initiative_core::app::AutocompleteSuggestion;
+use initiative_core::{Event, ExportData};
+
+#[test]
+fn tags_are_added_and_removed() {
+    let mut app = sync_app();
+    app.command("npc named Bob").unwrap();
+
+    let output = app.command("Bob is tagged Villain, #waterdeep").unwrap();
+    assert!(
+        output.contains("\\\n**Tags:** #villain, #waterdeep\n"),
+        "{}",
+        output,
+    );
+    assert!(
+        output.ends_with(
+            "_Bob was successfully tagged #villain, #waterdeep. Use `undo` to reverse this._"
+        ),
+        "{}",
+        output,
+    );
+
+    app.command("Bob is tagged ally villain").unwrap();
+    let output = app.command("Bob").unwrap();
+    assert!(
+        output.contains("**Tags:** #ally, #villain, #waterdeep"),
+        "{}",
+        output,
+    );
+
+    let output = app.command("Bob is not tagged villain").unwrap();
+    assert!(output.contains("**Tags:** #ally, #waterdeep"), "{}", output);
+
+    app.command("undo").unwrap();
+    let output = app.command("Bob").unwrap();
+    assert!(
+        output.contains("**Tags:** #ally, #villain, #waterdeep"),
+        "{}",
+        output,
+    );
+
+    assert_eq!(
+        "There is no character or place named \"Nobody\".",
+        app.command("Nobody is tagged villain").unwrap_err(),
+    );
+}
+
+#[test]
+fn journal_is_filtered_by_tag() {
+    let mut app = sync_app();
+    app.command("npc named Bob").unwrap();
+    app.command("npc named Alice").unwrap();
+    app.command("inn named The Yawning Portal").unwrap();
+    app.command("Bob is tagged villain, waterdeep").unwrap();
+    app.command("The Yawning Portal is tagged waterdeep")
+        .unwrap();
+
+    let output = app.command("journal #waterdeep").unwrap();
+    assert!(
+        output.starts_with("# Journal: #waterdeep\n\n"),
+        "{}",
+        output
+    );
+    assert!(output.contains("`Bob`"), "{}", output);
+    assert!(output.contains("`The Yawning Portal`"), "{}", output);
+    assert!(!output.contains("`Alice`"), "{}", output);
+
+    let output = app.command("journal #Villain #waterdeep").unwrap();
+    assert!(output.contains("`Bob`"), "{}", output);
+    assert!(!output.contains("`The Yawning Portal`"), "{}", output);
+
+    assert_eq!(
+        "# Journal: #dragon\n\n*Nothing in your journal matches \"#dragon\".*",
+        app.command("journal #dragon").unwrap(),
+    );
+}
+
+#[test]
+fn tags_are_autocompleted() {
+    let mut app = sync_app();
+    app.command("npc named Bob").unwrap();
+    app.command("npc named Alice").unwrap();
+    app.command("Bob is tagged villain, waterdeep").unwrap();
+
+    assert_eq!(
+        vec![AutocompleteSuggestion::new(
+            "journal #villain",
+            "entries tagged #villain",
+        )],
+        app.autocomplete("journal #vi"),
+    );
+
+    assert_eq!(
+        vec![
+            AutocompleteSuggestion::new("Alice is tagged villain", "tag character"),
+            AutocompleteSuggestion::new("Alice is tagged waterdeep", "tag character"),
+        ],
+        app.autocomplete("Alice is tagged ")
+            .into_iter()
+            .filter(|suggestion| suggestion.summary == "tag character")
+            .collect::<Vec<_>>(),
+    );
+
+    assert_eq!(
+        vec![AutocompleteSuggestion::new(
+            "Bob is not tagged #waterdeep",
+            "untag character",
+        )],
+        app.autocomplete("Bob is not tagged #w"),
+    );
+}
+
+#[test]
+fn tags_are_exported() {
+    let mut app = sync_app_with_events();
+    app.command("npc named Bob").unwrap();
+    app.command("Bob is tagged villain").unwrap();
+    app.command("export").unwrap();
+
+    let data = take_event();
+    let data = if let Some(Event::Export(ExportData::Backup(data))) = data {
+        data
+    } else {
+        panic!("{:?}", data);
+    };
+
+    let json = serde_json::to_string(&data).unwrap();
+    assert!(json.contains(r#""tags":["villain"]"#), "{}", json);
+
+    let mut app = sync_app();
+    app.bulk_import(serde_json::from_str(&json).unwrap())
+        .unwrap();
+
+    let output = app.command("journal #villain").unwrap();
+    assert!(output.contains("`Bob`"), "{}", output);
+}
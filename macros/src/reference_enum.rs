@@ -143,6 +143,17 @@ pub fn run(input: TokenStream) -> Result<TokenStream, String> {
                     .unwrap()
             })
             .collect(),
+        "Monster" => srd_5e::monsters()?
+            .iter()
+            .map(|monster| {
+                EntryBuilder::default()
+                    .with_ident(&monster.token())
+                    .with_name(monster.name.to_owned())
+                    .with_details(&monster.display_details())
+                    .into_entry()
+                    .unwrap()
+            })
+            .collect(),
         "Spell" => srd_5e::spells()?
             .iter()
             .map(|spell| {
@@ -199,19 +210,72 @@ pub fn run(input: TokenStream) -> Result<TokenStream, String> {
         quote! { #ident::#variant => #output }
     });
 
-    let get_list = if ident == "Spell" {
+    let extra_methods = if ident == "Spell" {
+        let spells = srd_5e::spells()?;
+
         let list_output: String = std::iter::once(format!("# {}s", ident))
             .chain(
-                srd_5e::spells()?
+                spells
                     .iter()
                     .map(|spell| format!("\n* {}", spell.display_summary())),
             )
             .collect();
 
+        let get_duration_cases = spells.iter().map(|spell| {
+            let variant: syn::Ident = syn::parse_str(&spell.token()).unwrap();
+            let duration = spell.duration();
+            quote! { #ident::#variant => #duration }
+        });
+
+        let concentration_variants = spells
+            .iter()
+            .filter(|spell| spell.concentration())
+            .map(|spell| -> syn::Ident { syn::parse_str(&spell.token()).unwrap() });
+
         quote! {
             pub fn get_list() -> &'static str {
                 #list_output
             }
+
+            /// The spell's duration as written in the SRD, eg. "Up to 1 minute".
+            pub fn get_duration(&self) -> &'static str {
+                match self {
+                    #(#get_duration_cases),*
+                }
+            }
+
+            pub fn is_concentration(&self) -> bool {
+                matches!(self, #(#ident::#concentration_variants)|*)
+            }
+        }
+    } else if ident == "Monster" {
+        let monsters = srd_5e::monsters()?;
+
+        let get_hit_points_cases = monsters.iter().map(|monster| {
+            let variant: syn::Ident = syn::parse_str(&monster.token()).unwrap();
+            let hit_points = i32::from(monster.hit_points);
+            quote! { #ident::#variant => #hit_points }
+        });
+
+        let get_initiative_bonus_cases = monsters.iter().map(|monster| {
+            let variant: syn::Ident = syn::parse_str(&monster.token()).unwrap();
+            let initiative_bonus = monster.initiative_bonus();
+            quote! { #ident::#variant => #initiative_bonus }
+        });
+
+        quote! {
+            pub fn get_hit_points(&self) -> i32 {
+                match self {
+                    #(#get_hit_points_cases),*
+                }
+            }
+
+            /// The bonus added to the monster's initiative rolls, ie. its Dexterity modifier.
+            pub fn get_initiative_bonus(&self) -> i32 {
+                match self {
+                    #(#get_initiative_bonus_cases),*
+                }
+            }
         }
     } else {
         quote! {}
@@ -234,7 +298,7 @@ pub fn run(input: TokenStream) -> Result<TokenStream, String> {
                 [#(#words)*].into_iter()
             }
 
-            #get_list
+            #extra_methods
 
             pub fn get_name(&self) -> &'static str {
                 match self {
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Debug, Deserialize)]
+pub struct Monster {
+    index: String,
+    pub name: String,
+
+    size: String,
+
+    #[serde(rename = "type")]
+    creature_type: String,
+
+    pub hit_points: u16,
+    hit_dice: String,
+
+    dexterity: u8,
+}
+
+pub struct DetailsView<'a>(&'a Monster);
+
+impl Monster {
+    pub fn token(&self) -> String {
+        crate::to_camel_case(&self.index)
+    }
+
+    pub fn display_details(&self) -> DetailsView<'_> {
+        DetailsView(self)
+    }
+
+    /// The bonus added to the monster's initiative rolls, ie. its Dexterity modifier.
+    pub fn initiative_bonus(&self) -> i32 {
+        (i32::from(self.dexterity) - 10).div_euclid(2)
+    }
+}
+
+impl<'a> fmt::Display for DetailsView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let monster = self.0;
+
+        writeln!(f, "# {}", monster.name)?;
+        writeln!(f, "*{} {}*", monster.size, monster.creature_type)?;
+        write!(
+            f,
+            "\n**Hit Points:** {} ({})\\\n**Dexterity:** {} ({:+})",
+            monster.hit_points,
+            monster.hit_dice,
+            monster.dexterity,
+            monster.initiative_bonus(),
+        )
+    }
+}
@@ -47,6 +47,14 @@ impl Spell {
         crate::to_camel_case(&self.index)
     }
 
+    pub fn duration(&self) -> &str {
+        &self.duration
+    }
+
+    pub fn concentration(&self) -> bool {
+        self.concentration
+    }
+
     pub fn display_summary(&self) -> SummaryView {
         SummaryView(self)
     }
@@ -0,0 +1,252 @@
+//! Just enough HTTP/1.1 for the sync API. Each connection carries a single request, whose body is
+//! sized by its `Content-Length` header, and is closed once the response has been sent.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// The largest request line and headers accepted, in bytes.
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RequestError {
+    /// The client stopped sending before the request was complete.
+    TimedOut,
+    Invalid(&'static str),
+    TooLarge,
+    /// The connection failed, so there's no one to respond to.
+    Closed,
+}
+
+#[derive(Debug)]
+pub struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// The value of a header, whose name is matched without regard to case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Response {
+    pub fn new(status: u16, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn with_header(mut self, field: &'static str, value: &str) -> Self {
+        self.headers.push((field, value.to_string()));
+        self
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+
+        for (field, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", field, value));
+        }
+
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len(),
+        ));
+
+        writer.write_all(head.as_bytes())?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+/// Read a request, refusing any whose body is longer than `max_body_size` without reading it.
+pub fn read_request(
+    reader: &mut impl BufRead,
+    max_body_size: u64,
+) -> Result<Request, RequestError> {
+    let mut head = reader.by_ref().take(MAX_HEAD_SIZE);
+
+    let request_line = read_line(&mut head)?;
+    let (method, url) = match request_line.split(' ').collect::<Vec<_>>()[..] {
+        [method, url, version] if version.starts_with("HTTP/1.") => {
+            (method.to_string(), url.to_string())
+        }
+        _ => return Err(RequestError::Invalid("Malformed request line.")),
+    };
+
+    let mut headers = Vec::new();
+
+    loop {
+        let line = read_line(&mut head)?;
+
+        if line.is_empty() {
+            break;
+        }
+
+        match line.split_once(':') {
+            Some((field, value)) => headers.push((field.to_string(), value.trim().to_string())),
+            None => return Err(RequestError::Invalid("Malformed header.")),
+        }
+    }
+
+    let mut request = Request {
+        method,
+        url,
+        headers,
+        body: Vec::new(),
+    };
+
+    if request.header("Transfer-Encoding").is_some() {
+        return Err(RequestError::Invalid(
+            "Requests must have a Content-Length.",
+        ));
+    }
+
+    let content_length = match request.header("Content-Length").map(str::parse::<u64>) {
+        Some(Ok(content_length)) => content_length,
+        None => 0,
+        Some(Err(_)) => return Err(RequestError::Invalid("Malformed Content-Length.")),
+    };
+
+    if content_length > max_body_size {
+        return Err(RequestError::TooLarge);
+    }
+
+    reader
+        .by_ref()
+        .take(content_length)
+        .read_to_end(&mut request.body)
+        .map_err(io_error)?;
+
+    if request.body.len() as u64 == content_length {
+        Ok(request)
+    } else {
+        Err(RequestError::Closed)
+    }
+}
+
+/// A line of the request head, without its line ending.
+fn read_line(reader: &mut impl BufRead) -> Result<String, RequestError> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line).map_err(io_error)?;
+
+    match line.strip_suffix(b"\n") {
+        Some(line) => String::from_utf8(line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+            .map_err(|_| RequestError::Invalid("Malformed header.")),
+        None if line.len() as u64 >= MAX_HEAD_SIZE => Err(RequestError::TooLarge),
+        None => Err(RequestError::Closed),
+    }
+}
+
+fn io_error(e: io::Error) -> RequestError {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => RequestError::TimedOut,
+        _ => RequestError::Closed,
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Content Too Large",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read(raw: &str) -> Result<Request, RequestError> {
+        read_request(&mut Cursor::new(raw.as_bytes()), 10)
+    }
+
+    #[test]
+    fn read_request_test() {
+        let request = read(
+            "POST /changes?cursor=3 HTTP/1.1\r\nauthorization: Bearer abc\r\nContent-Length: 2\r\n\r\n{}",
+        )
+        .unwrap();
+
+        assert_eq!("POST", request.method);
+        assert_eq!("/changes?cursor=3", request.url);
+        assert_eq!(Some("Bearer abc"), request.header("Authorization"));
+        assert_eq!(None, request.header("Content-Type"));
+        assert_eq!(b"{}".to_vec(), request.body);
+
+        let request = read("GET /changes HTTP/1.0\n\n").unwrap();
+        assert_eq!("GET", request.method);
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn read_request_test_errors() {
+        assert_eq!(
+            Err(RequestError::Invalid("Malformed request line.")),
+            read("GET /changes\r\n\r\n"),
+        );
+        assert_eq!(
+            Err(RequestError::Invalid("Malformed header.")),
+            read("GET /changes HTTP/1.1\r\nAuthorization\r\n\r\n"),
+        );
+        assert_eq!(
+            Err(RequestError::Invalid("Malformed Content-Length.")),
+            read("POST /changes HTTP/1.1\r\nContent-Length: two\r\n\r\n{}"),
+        );
+        assert_eq!(
+            Err(RequestError::Invalid(
+                "Requests must have a Content-Length."
+            )),
+            read("POST /changes HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"),
+        );
+        assert_eq!(
+            Err(RequestError::TooLarge),
+            read("POST /changes HTTP/1.1\r\nContent-Length: 11\r\n\r\n"),
+        );
+        assert_eq!(
+            Err(RequestError::TooLarge),
+            read(&format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(20000))),
+        );
+        assert_eq!(
+            Err(RequestError::Closed),
+            read("POST /changes HTTP/1.1\r\nContent-Length: 5\r\n\r\n{}"),
+        );
+        assert_eq!(Err(RequestError::Closed), read("GET /changes HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn write_to_test() {
+        let mut output = Vec::new();
+        Response::new(200, b"{}".to_vec())
+            .with_header("Content-Type", "application/json")
+            .write_to(&mut output)
+            .unwrap();
+
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+            String::from_utf8(output).unwrap(),
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use super::tokens::{create_private_dir, is_valid_user};
+use initiative_core::{compact_changes, SyncChange};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// How many changes accumulate in a user's log before they're folded into the snapshot.
+pub const DEFAULT_SNAPSHOT_INTERVAL: usize = 500;
+
+/// The change logs of every user, kept on disk. Each user has a directory containing:
+///
+/// * `snapshot.json`: the start of their log, compacted so that it only contains what hasn't
+///   since been overwritten.
+/// * `changes.jsonl`: the changes received since the snapshot was taken, one per line.
+///
+/// A cursor counts every change the server has ever received for the user, including those that
+/// have since been compacted away. Clients that are behind the snapshot get the whole snapshot,
+/// which is safe because applying a change that's already been applied does nothing.
+///
+/// Requests for different users can be handled at the same time, but each user's journal is only
+/// read or written by one request at a time.
+pub struct Journals {
+    dir: PathBuf,
+    snapshot_interval: usize,
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Snapshot {
+    /// The number of changes that had been received when the snapshot was taken.
+    cursor: u64,
+    changes: Vec<SyncChange>,
+}
+
+/// A line of `changes.jsonl`. Recording the position of each change means that a log that was
+/// already folded into the snapshot, but couldn't be cleared afterwards, is ignored rather than
+/// counted twice.
+#[derive(Deserialize)]
+struct LogEntry {
+    position: u64,
+    change: SyncChange,
+}
+
+#[derive(Serialize)]
+struct LogEntryRef<'a> {
+    position: u64,
+    change: &'a SyncChange,
+}
+
+impl Journals {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            locks: Mutex::default(),
+        }
+    }
+
+    pub fn with_snapshot_interval(mut self, snapshot_interval: usize) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    /// The changes after `cursor`, and the cursor to use next time. A cursor from beyond the end
+    /// of the log, eg. if the server's data was lost, gets everything from the start.
+    pub fn pull(&self, user: &str, cursor: u64) -> io::Result<(Vec<SyncChange>, u64)> {
+        let lock = self.lock(user);
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let snapshot = self.read_snapshot(user)?;
+        let (log, _) = self.read_log(user, snapshot.cursor)?;
+        let end = snapshot.cursor + log.len() as u64;
+
+        let changes = if cursor < snapshot.cursor || cursor > end {
+            snapshot.changes.into_iter().chain(log).collect()
+        } else {
+            log.into_iter()
+                .skip((cursor - snapshot.cursor) as usize)
+                .collect()
+        };
+
+        Ok((changes, end))
+    }
+
+    /// Add changes to the end of the log, taking a new snapshot if the log has grown long enough.
+    pub fn push(&self, user: &str, changes: &[SyncChange]) -> io::Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let lock = self.lock(user);
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let snapshot = self.read_snapshot(user)?;
+        let (log, log_len) = self.read_log(user, snapshot.cursor)?;
+        let end = snapshot.cursor + log.len() as u64;
+
+        create_private_dir(&self.user_dir(user)?)?;
+
+        {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.log_path(user)?)?;
+
+            // Drop anything left behind by a write that didn't finish, so that the new changes
+            // start on a line of their own.
+            if file.metadata()?.len() > log_len {
+                file.set_len(log_len)?;
+            }
+
+            let mut lines = Vec::new();
+
+            for (position, change) in (end..).zip(changes) {
+                serde_json::to_writer(&mut lines, &LogEntryRef { position, change })?;
+                lines.push(b'\n');
+            }
+
+            file.write_all(&lines)?;
+            file.sync_data()?;
+        }
+
+        if log.len() + changes.len() >= self.snapshot_interval {
+            self.write_snapshot(
+                user,
+                &Snapshot {
+                    cursor: end + changes.len() as u64,
+                    changes: compact_changes(
+                        snapshot
+                            .changes
+                            .into_iter()
+                            .chain(log)
+                            .chain(changes.iter().cloned())
+                            .collect(),
+                    ),
+                },
+            )?;
+
+            File::create(self.log_path(user)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_snapshot(&self, user: &str) -> io::Result<Snapshot> {
+        match fs::read_to_string(self.snapshot_path(user)?) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Snapshot::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The changes in the log from position `start` onwards, and the length in bytes of the part
+    /// of the log that was read. Every line ends with a newline, so anything after the last one
+    /// was left by a write that didn't finish, and is ignored.
+    fn read_log(&self, user: &str, start: u64) -> io::Result<(Vec<SyncChange>, u64)> {
+        let contents = match fs::read(self.log_path(user)?) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+            Err(e) => return Err(e),
+        };
+
+        let len = contents
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |i| i + 1);
+        let mut changes = Vec::new();
+
+        for line in contents[..len].split(|&byte| byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+
+            let entry: LogEntry = serde_json::from_slice(line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            if entry.position >= start {
+                changes.push(entry.change);
+            }
+        }
+
+        Ok((changes, len as u64))
+    }
+
+    /// The lock guarding a user's journal, created the first time it's needed.
+    fn lock(&self, user: &str) -> Arc<Mutex<()>> {
+        self.locks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(user.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// The snapshot is written to a temporary file that's renamed into place, so that it's never
+    /// left half-written.
+    fn write_snapshot(&self, user: &str, snapshot: &Snapshot) -> io::Result<()> {
+        let path = self.snapshot_path(user)?;
+        let temp_path = path.with_extension("json.tmp");
+
+        {
+            let mut file = File::create(&temp_path)?;
+            serde_json::to_writer(&mut file, snapshot)?;
+            file.sync_all()?;
+        }
+
+        fs::rename(temp_path, path)
+    }
+
+    fn user_dir(&self, user: &str) -> io::Result<PathBuf> {
+        if is_valid_user(user) {
+            Ok(self.dir.join(user))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid user name: {:?}", user),
+            ))
+        }
+    }
+
+    fn snapshot_path(&self, user: &str) -> io::Result<PathBuf> {
+        Ok(self.user_dir(user)?.join("snapshot.json"))
+    }
+
+    fn log_path(&self, user: &str) -> io::Result<PathBuf> {
+        Ok(self.user_dir(user)?.join("changes.jsonl"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use initiative_core::Uuid;
+
+    fn change(counter: u64, time: &str) -> SyncChange {
+        serde_json::from_value(serde_json::json!({
+            "timestamp": {"counter": counter, "device": Uuid::from_u128(1)},
+            "operations": [{"SetKeyValue": {"key": "time", "value": time}}],
+        }))
+        .unwrap()
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("initiative-journals-{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn push_pull_test() {
+        let dir = temp_dir();
+        let journals = Journals::new(&dir);
+
+        assert_eq!((Vec::new(), 0), journals.pull("alice", 0).unwrap());
+
+        journals
+            .push("alice", &[change(1, "1:00:00:00"), change(2, "2:00:00:00")])
+            .unwrap();
+        journals.push("alice", &[change(3, "3:00:00:00")]).unwrap();
+
+        assert_eq!(
+            (
+                vec![
+                    change(1, "1:00:00:00"),
+                    change(2, "2:00:00:00"),
+                    change(3, "3:00:00:00"),
+                ],
+                3,
+            ),
+            journals.pull("alice", 0).unwrap(),
+        );
+        assert_eq!(
+            (vec![change(3, "3:00:00:00")], 3),
+            journals.pull("alice", 2).unwrap(),
+        );
+        assert_eq!((Vec::new(), 3), journals.pull("alice", 3).unwrap());
+        assert_eq!(3, journals.pull("alice", 10).unwrap().0.len());
+        assert_eq!((Vec::new(), 0), journals.pull("bob", 0).unwrap());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let dir = temp_dir();
+        let journals = Journals::new(&dir).with_snapshot_interval(3);
+
+        journals
+            .push("alice", &[change(1, "1:00:00:00"), change(2, "2:00:00:00")])
+            .unwrap();
+        journals.push("alice", &[change(3, "3:00:00:00")]).unwrap();
+        journals.push("alice", &[change(4, "4:00:00:00")]).unwrap();
+
+        assert_eq!(
+            (vec![change(3, "3:00:00:00"), change(4, "4:00:00:00")], 4),
+            journals.pull("alice", 0).unwrap(),
+            "the snapshot only keeps the latest time",
+        );
+        assert_eq!(
+            (vec![change(3, "3:00:00:00"), change(4, "4:00:00:00")], 4),
+            journals.pull("alice", 2).unwrap(),
+            "clients behind the snapshot get all of it",
+        );
+        assert_eq!(
+            (vec![change(4, "4:00:00:00")], 4),
+            journals.pull("alice", 3).unwrap(),
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn torn_write_test() {
+        let dir = temp_dir();
+        let journals = Journals::new(&dir);
+
+        journals.push("alice", &[change(1, "1:00:00:00")]).unwrap();
+
+        // A push that was interrupted partway through writing its line.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journals.log_path("alice").unwrap())
+            .unwrap();
+        file.write_all(b"{\"position\":1,\"chan").unwrap();
+
+        assert_eq!(
+            (vec![change(1, "1:00:00:00")], 1),
+            journals.pull("alice", 0).unwrap(),
+        );
+
+        journals.push("alice", &[change(2, "2:00:00:00")]).unwrap();
+
+        assert_eq!(
+            (vec![change(1, "1:00:00:00"), change(2, "2:00:00:00")], 2),
+            journals.pull("alice", 0).unwrap(),
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_user_test() {
+        let journals = Journals::new(temp_dir());
+
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            journals.pull("../alice", 0).unwrap_err().kind(),
+        );
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            journals
+                .push("../alice", &[change(1, "1:00:00:00")])
+                .unwrap_err()
+                .kind(),
+        );
+    }
+}
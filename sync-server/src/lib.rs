@@ -0,0 +1,241 @@
+//! A self-hosted server that keeps initiative.sh journals in sync between devices. Each user's
+//! changes are kept in a log on disk, and clients authenticate with a token.
+//!
+//! The API has a single resource:
+//!
+//! * `GET /changes?cursor=N` returns `{"changes": [...], "cursor": M}`, the changes received after
+//!   position `N` of the log and the position to ask for next time.
+//! * `POST /changes` with a body of `{"changes": [...]}` adds changes to the end of the log.
+//!
+//! Requests must include an `Authorization: Bearer <token>` header.
+
+mod http;
+mod journals;
+mod tokens;
+
+pub use journals::{Journals, DEFAULT_SNAPSHOT_INTERVAL};
+pub use tokens::Tokens;
+
+use http::{Request, RequestError, Response};
+use initiative_core::SyncChange;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufReader, Read};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The largest request body accepted, in bytes.
+pub const MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// How many requests are handled at once. Connections beyond that wait their turn.
+pub const DEFAULT_WORKERS: usize = 8;
+
+/// How long a client has to send its whole request before it's abandoned.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct Server {
+    listener: TcpListener,
+    journals: Journals,
+    tokens: Tokens,
+    workers: usize,
+    request_timeout: Duration,
+}
+
+/// Reads from a connection until a fixed point in time, so that a client can't hold on to a
+/// worker by sending its request a little at a time.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+#[derive(Serialize)]
+struct PullResponse {
+    changes: Vec<SyncChange>,
+    cursor: u64,
+}
+
+#[derive(Deserialize)]
+struct PushRequest {
+    changes: Vec<SyncChange>,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, journals: Journals, tokens: Tokens) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            journals,
+            tokens,
+            workers: DEFAULT_WORKERS,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        })
+    }
+
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// The address the server is listening on, useful when it was bound to port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listener
+            .local_addr()
+            .expect("the server is bound to an address")
+    }
+
+    /// Handle requests until the process exits. A fixed number of workers take turns with the
+    /// connections, so a client that's slow to send its changes holds up at most one of them,
+    /// and only until it times out. Requests for the same user take turns with their journal.
+    pub fn run(&self) {
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(self.workers);
+        let receiver = Mutex::new(receiver);
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers {
+                scope.spawn(|| loop {
+                    let stream = receiver
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .recv();
+
+                    match stream {
+                        Ok(stream) => self.handle(stream),
+                        Err(_) => return,
+                    }
+                });
+            }
+
+            for stream in self.listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if sender.send(stream).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => eprintln!("Couldn't accept connection: {}", e),
+                }
+            }
+        });
+    }
+
+    fn handle(&self, stream: TcpStream) {
+        if let Err(e) = stream.set_write_timeout(Some(self.request_timeout)) {
+            eprintln!("Couldn't set up connection: {}", e);
+            return;
+        }
+
+        let mut reader = BufReader::new(Deadline {
+            stream: &stream,
+            deadline: Instant::now() + self.request_timeout,
+        });
+
+        let mut response = match http::read_request(&mut reader, MAX_BODY_SIZE) {
+            Ok(request) => self.respond(&request),
+            Err(RequestError::TimedOut) => error(408, "The request took too long to arrive."),
+            Err(RequestError::Invalid(message)) => error(400, message),
+            Err(RequestError::TooLarge) => error(413, "Too many changes at once."),
+            Err(RequestError::Closed) => return,
+        };
+
+        // The web client runs on another origin.
+        for (field, value) in [
+            ("Access-Control-Allow-Origin", "*"),
+            (
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            ),
+            ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+        ] {
+            response = response.with_header(field, value);
+        }
+
+        if let Err(e) = response.write_to(&stream) {
+            eprintln!("Couldn't send response: {}", e);
+        }
+    }
+
+    fn respond(&self, request: &Request) -> Response {
+        let (path, query) = request.url.split_once('?').unwrap_or((&request.url, ""));
+
+        if path != "/changes" {
+            return error(404, "Not found.");
+        }
+
+        if request.method == "OPTIONS" {
+            return Response::new(204, Vec::new());
+        }
+
+        let user = match request
+            .header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.tokens.user(token.trim()))
+        {
+            Some(user) => user.to_string(),
+            None => return error(401, "A valid token is required."),
+        };
+
+        let result = match request.method.as_str() {
+            "GET" => self.pull(&user, query),
+            "POST" => self.push(&user, &request.body),
+            _ => return error(405, "Method not allowed."),
+        };
+
+        result.unwrap_or_else(|e| {
+            eprintln!("Couldn't handle request for {}: {}", user, e);
+            error(500, "The server couldn't read or save the journal.")
+        })
+    }
+
+    fn pull(&self, user: &str, query: &str) -> io::Result<Response> {
+        let cursor = match query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("cursor="))
+            .map(|cursor| cursor.parse())
+        {
+            Some(Ok(cursor)) => cursor,
+            None => 0,
+            Some(Err(_)) => return Ok(error(400, "The cursor must be a number.")),
+        };
+
+        let (changes, cursor) = self.journals.pull(user, cursor)?;
+
+        Ok(json(200, &PullResponse { changes, cursor }))
+    }
+
+    fn push(&self, user: &str, body: &[u8]) -> io::Result<Response> {
+        match serde_json::from_slice::<PushRequest>(body) {
+            Ok(PushRequest { changes }) => {
+                self.journals.push(user, &changes)?;
+                Ok(Response::new(204, Vec::new()))
+            }
+            Err(e) => Ok(error(400, &format!("Invalid changes: {}", e))),
+        }
+    }
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn json(status: u16, body: &impl Serialize) -> Response {
+    Response::new(status, serde_json::to_vec(body).unwrap())
+        .with_header("Content-Type", "application/json")
+}
+
+fn error(status: u16, message: &str) -> Response {
+    json(status, &serde_json::json!({ "error": message }))
+}
@@ -0,0 +1,62 @@
+use initiative_sync_server::{Journals, Server, Tokens};
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str = "\
+Usage:
+  initiative-sync-server [--listen ADDRESS] [--data-dir DIRECTORY]
+  initiative-sync-server add-user NAME [--data-dir DIRECTORY]
+
+Serves journals from DIRECTORY (default: ./initiative-sync) on ADDRESS
+(default: 127.0.0.1:7878). add-user creates a user and prints their token.";
+
+fn main() {
+    let mut listen = "127.0.0.1:7878".to_string();
+    let mut data_dir = PathBuf::from("initiative-sync");
+    let mut add_user = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--listen", Some(value)) => listen = value,
+            ("--data-dir", Some(value)) => data_dir = value.into(),
+            ("add-user", Some(value)) => add_user = Some(value),
+            _ => {
+                eprintln!("{}", USAGE);
+                exit(2);
+            }
+        }
+    }
+
+    let tokens_path = data_dir.join("tokens");
+
+    if let Some(user) = add_user {
+        match Tokens::add_user(&tokens_path, &user) {
+            Ok(token) => println!("{}", token),
+            Err(e) => {
+                eprintln!("Couldn't add {}: {}", user, e);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let server = Tokens::load(&tokens_path).and_then(|tokens| {
+        Server::bind(
+            listen.as_str(),
+            Journals::new(data_dir.join("journals")),
+            tokens,
+        )
+    });
+
+    match server {
+        Ok(server) => {
+            eprintln!("Listening on http://{}", server.local_addr());
+            server.run();
+        }
+        Err(e) => {
+            eprintln!("Couldn't start the server: {}", e);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use initiative_core::Uuid;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The users allowed to sync and the tokens they authenticate with, as read from a file with one
+/// `user token` pair per line. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Tokens {
+    users: Vec<(String, String)>,
+}
+
+impl Tokens {
+    /// Read the tokens file at `path`. A file that doesn't exist yet has no users.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Generate a token for a new user and add it to the tokens file at `path`, returning the
+    /// token.
+    pub fn add_user(path: &Path, user: &str) -> io::Result<String> {
+        if !is_valid_user(user) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "user names may only contain letters, numbers, dashes, and underscores",
+            ));
+        }
+
+        if Self::load(path)?.users.iter().any(|(name, _)| name == user) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("there is already a user named {}", user),
+            ));
+        }
+
+        // Version 4 UUIDs come from the operating system's secure random number generator.
+        let token = format!(
+            "{}{}",
+            Uuid::new_v4().to_simple(),
+            Uuid::new_v4().to_simple(),
+        );
+
+        if let Some(dir) = path.parent() {
+            create_private_dir(dir)?;
+        }
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+
+        // Anyone who can read the tokens can sync as any user.
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        writeln!(options.open(path)?, "{} {}", user, token)?;
+
+        Ok(token)
+    }
+
+    /// The user that a token belongs to, if any.
+    pub fn user(&self, token: &str) -> Option<&str> {
+        // Check every token without stopping early, so that the time taken doesn't reveal how
+        // much of a token was correct.
+        self.users.iter().fold(None, |found, (user, user_token)| {
+            if constant_time_eq(token.as_bytes(), user_token.as_bytes()) {
+                Some(user.as_str())
+            } else {
+                found
+            }
+        })
+    }
+}
+
+impl std::str::FromStr for Tokens {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let mut users = Vec::new();
+
+        for (i, line) in raw.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [user, token] if is_valid_user(user) => {
+                    users.push((user.to_string(), token.to_string()))
+                }
+                _ => return Err(format!("line {} should be a user name and a token", i + 1)),
+            }
+        }
+
+        Ok(Self { users })
+    }
+}
+
+/// User names double as directory names, so they're kept simple.
+pub fn is_valid_user(user: &str) -> bool {
+    !user.is_empty()
+        && user.len() <= 64
+        && user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Create a directory, and any missing parents, that only the server's own user can read.
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+    builder.create(dir)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let tokens: Tokens = "# Players\n\nalice abc123\n  bob   def456  \n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(Some("alice"), tokens.user("abc123"));
+        assert_eq!(Some("bob"), tokens.user("def456"));
+        assert_eq!(None, tokens.user("abc12"));
+        assert_eq!(None, tokens.user(""));
+
+        assert_eq!(
+            "line 2 should be a user name and a token",
+            "alice abc123\n../etc token".parse::<Tokens>().unwrap_err(),
+        );
+        assert_eq!(
+            "line 1 should be a user name and a token",
+            "alice".parse::<Tokens>().unwrap_err(),
+        );
+    }
+
+    #[test]
+    fn is_valid_user_test() {
+        assert!(is_valid_user("alice"));
+        assert!(is_valid_user("Game_Master-2"));
+        assert!(!is_valid_user(""));
+        assert!(!is_valid_user(".."));
+        assert!(!is_valid_user("alice/bob"));
+        assert!(!is_valid_user(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn add_user_test() {
+        let dir = std::env::temp_dir().join(format!("initiative-tokens-{}", Uuid::new_v4()));
+        let path = dir.join("tokens");
+
+        let token = Tokens::add_user(&path, "alice").unwrap();
+        assert_eq!(64, token.len());
+        assert_eq!(
+            io::ErrorKind::AlreadyExists,
+            Tokens::add_user(&path, "alice").unwrap_err().kind(),
+        );
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            Tokens::add_user(&path, "alice bob").unwrap_err().kind(),
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = |path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(0o600, mode(&path));
+            assert_eq!(0o700, mode(&dir));
+        }
+
+        let other_token = Tokens::add_user(&path, "bob").unwrap();
+        assert_ne!(token, other_token);
+
+        let tokens = Tokens::load(&path).unwrap();
+        assert_eq!(Some("alice"), tokens.user(&token));
+        assert_eq!(Some("bob"), tokens.user(&other_token));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -147,6 +147,13 @@ export async function get_things_by_name_start(name, limit) {
     .toArray()
 }
 
+export async function get_things_by_type(type) {
+  return dexie.things
+    .where("type")
+    .equals(type)
+    .toArray()
+}
+
 export async function save_thing(thing) {
   return dexie.things.put(thing)
 }
@@ -167,9 +174,21 @@ export async function export_database(data) {
   download(JSON.stringify(data), "initiative_export.json", "application/json")
 }
 
-export async function import_database(successCallback, failureCallback) {
+export async function export_log(markdown) {
+  download(markdown, "initiative_log.md", "text/markdown")
+}
+
+export async function export_markdown(markdown) {
+  download(markdown, "initiative_journal.md", "text/markdown")
+}
+
+export async function export_html(html) {
+  download(html, "initiative_journal.html", "text/html")
+}
+
+export async function import_database(successCallback, csvCallback, failureCallback) {
   const inputElement = document.createElement("input")
-  inputElement.accept = "application/json"
+  inputElement.accept = "application/json,text/csv,.csv"
   inputElement.style = "display: none"
   inputElement.type = "file"
 
@@ -180,9 +199,10 @@ export async function import_database(successCallback, failureCallback) {
     }
 
     const file = event.target.files[0]
+    const isCsv = /\.csv$/i.test(file.name)
 
-    if (!/\.json$/.test(file.name)) {
-      failureCallback("The file you selected does not appear to be JSON.")
+    if (!isCsv && !/\.json$/i.test(file.name)) {
+      failureCallback("The file you selected does not appear to be JSON or CSV.")
       return
     }
 
@@ -190,6 +210,11 @@ export async function import_database(successCallback, failureCallback) {
     reader.addEventListener("loadstart", (event) => console.log(event))
     reader.addEventListener("error", (event) => console.error(event))
     reader.addEventListener("load", (event) => {
+      if (isCsv) {
+        csvCallback(event.target.result)
+        return
+      }
+
       try {
         const data = JSON.parse(event.target.result)
         successCallback(data)
@@ -1,5 +1,6 @@
 import * as wasm from "initiative-web"
-import { export_database, import_database } from "./database"
+import { export_database, export_html, export_log, export_markdown, import_database } from "./database"
+import { configure_sync, sync_configured } from "./sync"
 import terminal from "./terminal"
 
 const terminalElement = document.getElementById("terminal")
@@ -23,6 +24,21 @@ terminalElement.addEventListener(
   async (event) => await export_database(event.detail),
 )
 
+terminalElement.addEventListener(
+  "initiative.exportLog",
+  async (event) => await export_log(event.detail),
+)
+
+terminalElement.addEventListener(
+  "initiative.exportMarkdown",
+  async (event) => await export_markdown(event.detail),
+)
+
+terminalElement.addEventListener(
+  "initiative.exportHtml",
+  async (event) => await export_html(event.detail),
+)
+
 terminalElement.addEventListener(
   "initiative.startImport",
   async (event) => await import_database(
@@ -33,12 +49,35 @@ terminalElement.addEventListener(
         terminal.output("! " + e)
       }
     },
+    async (csv) => {
+      try {
+        terminal.output(await wasm.bulk_import_csv(csv))
+      } catch (e) {
+        terminal.output("! " + e)
+      }
+    },
     async (e) => {
       terminal.output("! " + e)
     }
   ),
 )
 
+terminalElement.addEventListener(
+  "initiative.sync",
+  async (event) => {
+    if (!sync_configured() && !configure_sync()) {
+      terminal.output("! Sync isn't set up yet. You'll need the address of your sync server and a token from whoever runs it.")
+      return
+    }
+
+    try {
+      terminal.output(await wasm.sync())
+    } catch (e) {
+      terminal.output("! " + e)
+    }
+  },
+)
+
 terminalElement.addEventListener(
   "initiative.command",
   async (event) => terminal.output(await wasm.command(event.detail.command)),
@@ -0,0 +1,51 @@
+// The sync server and token are kept in local storage rather than the journal, so they're never
+// included in a backup or sent to the server themselves.
+const URL_KEY = "initiative.syncUrl"
+const TOKEN_KEY = "initiative.syncToken"
+
+export function sync_configured() {
+  return !!(localStorage.getItem(URL_KEY) && localStorage.getItem(TOKEN_KEY))
+}
+
+export function configure_sync() {
+  const url = prompt("What's the address of your sync server?", localStorage.getItem(URL_KEY) || "")
+  if (!url) {
+    return false
+  }
+
+  const token = prompt("What's your sync token?")
+  if (!token) {
+    return false
+  }
+
+  localStorage.setItem(URL_KEY, url.replace(/\/+$/, ""))
+  localStorage.setItem(TOKEN_KEY, token.trim())
+  return true
+}
+
+async function request(path, options = {}) {
+  const response = await fetch(localStorage.getItem(URL_KEY) + path, {
+    ...options,
+    headers: {
+      "Authorization": "Bearer " + localStorage.getItem(TOKEN_KEY),
+      "Content-Type": "application/json",
+    },
+  })
+
+  if (!response.ok) {
+    throw new Error(`The sync server responded with ${response.status}.`)
+  }
+
+  return response
+}
+
+export async function sync_push(changes) {
+  await request("/changes", {
+    method: "POST",
+    body: JSON.stringify({ changes }),
+  })
+}
+
+export async function sync_pull(cursor) {
+  return await (await request("/changes?cursor=" + encodeURIComponent(cursor))).json()
+}
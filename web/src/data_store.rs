@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use initiative_core::{Thing, Uuid};
+use initiative_core::{Thing, ThingQuery, Uuid};
 use wasm_bindgen::prelude::*;
 
 #[derive(Default)]
@@ -62,6 +62,22 @@ impl initiative_core::DataStore for DataStore {
             .map_err(|_| ())
     }
 
+    async fn get_things_by_query(&self, query: &ThingQuery) -> Result<Vec<Thing>, ()> {
+        let things: Vec<Thing> = match &query.thing {
+            Some(Thing::Npc(_)) => get_things_by_type("Npc").await,
+            Some(Thing::Place(_)) => get_things_by_type("Place").await,
+            None => get_all_the_things().await,
+        }
+        .map_err(|_| ())?
+        .into_serde()
+        .map_err(|_| ())?;
+
+        Ok(things
+            .into_iter()
+            .filter(|thing| query.matches(thing))
+            .collect())
+    }
+
     async fn save_thing(&mut self, thing: &Thing) -> Result<(), ()> {
         save_thing(JsValue::from_serde(thing).unwrap())
             .await
@@ -101,6 +117,9 @@ extern "C" {
     #[wasm_bindgen(catch)]
     async fn get_things_by_name_start(name: &str, limit: usize) -> Result<JsValue, JsValue>;
 
+    #[wasm_bindgen(catch)]
+    async fn get_things_by_type(thing_type: &str) -> Result<JsValue, JsValue>;
+
     #[wasm_bindgen(catch)]
     async fn save_thing(thing: JsValue) -> Result<JsValue, JsValue>;
 
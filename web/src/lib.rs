@@ -4,6 +4,7 @@
 //! (and vice versa), then pass the message along down the line.
 
 mod data_store;
+mod sync;
 mod utils;
 
 use data_store::DataStore;
@@ -52,14 +53,40 @@ pub async fn bulk_import(data: JsValue) -> Result<String, String> {
         .await
 }
 
+#[wasm_bindgen]
+pub async fn bulk_import_csv(data: String) -> Result<String, String> {
+    app().bulk_import_csv(&data).await
+}
+
+#[wasm_bindgen]
+pub async fn sync() -> Result<String, String> {
+    app().sync(&mut sync::SyncTransport).await
+}
+
 fn event_dispatcher(event: core::Event) {
     let js_event = match event {
-        core::Event::Export(data) => {
+        core::Event::Export(core::ExportData::Backup(data)) => {
             let mut init = CustomEventInit::new();
             init.detail(&JsValue::from_serde(&data).unwrap());
             CustomEvent::new_with_event_init_dict("initiative.export", &init).unwrap()
         }
+        core::Event::Export(core::ExportData::Markdown(markdown)) => {
+            let mut init = CustomEventInit::new();
+            init.detail(&JsValue::from_str(&markdown));
+            CustomEvent::new_with_event_init_dict("initiative.exportMarkdown", &init).unwrap()
+        }
+        core::Event::Export(core::ExportData::Html(html)) => {
+            let mut init = CustomEventInit::new();
+            init.detail(&JsValue::from_str(&html));
+            CustomEvent::new_with_event_init_dict("initiative.exportHtml", &init).unwrap()
+        }
+        core::Event::ExportLog(markdown) => {
+            let mut init = CustomEventInit::new();
+            init.detail(&JsValue::from_str(&markdown));
+            CustomEvent::new_with_event_init_dict("initiative.exportLog", &init).unwrap()
+        }
         core::Event::Import => CustomEvent::new("initiative.startImport").unwrap(),
+        core::Event::Sync => CustomEvent::new("initiative.sync").unwrap(),
     };
 
     get_root_element()
@@ -76,7 +103,9 @@ fn app() -> &'static mut core::app::App {
     unsafe {
         if APP.is_none() {
             let data_store = DataStore;
-            APP = Some(core::app(data_store, &event_dispatcher));
+            let mut app = core::app(data_store, &event_dispatcher);
+            app.configure_undo_history(50, true);
+            APP = Some(app);
         }
 
         APP.as_mut().unwrap()
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use initiative_core::SyncChange;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+/// Syncs with the server configured in the browser, using `fetch`.
+pub struct SyncTransport;
+
+#[derive(Deserialize)]
+struct PullResponse {
+    changes: Vec<SyncChange>,
+    cursor: u64,
+}
+
+#[async_trait(?Send)]
+impl initiative_core::SyncTransport for SyncTransport {
+    async fn push(&mut self, changes: &[SyncChange]) -> Result<(), ()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        sync_push(JsValue::from_serde(changes).unwrap())
+            .await
+            .map(|_| ())
+            .map_err(|_| ())
+    }
+
+    async fn pull(&mut self, cursor: u64) -> Result<(Vec<SyncChange>, u64), ()> {
+        let response: PullResponse = sync_pull(&cursor.to_string())
+            .await
+            .map_err(|_| ())?
+            .into_serde()
+            .map_err(|_| ())?;
+
+        Ok((response.changes, response.cursor))
+    }
+}
+
+#[wasm_bindgen(module = "/js/sync.js")]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    async fn sync_push(changes: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn sync_pull(cursor: &str) -> Result<JsValue, JsValue>;
+}